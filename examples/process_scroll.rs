@@ -0,0 +1,43 @@
+use std::io::Read as _;
+
+fn read_frames() -> impl Iterator<Item = Vec<u8>> {
+    (1..=7625).map(|i| {
+        let mut file =
+            std::fs::File::open(format!("tests/data/crawl/crawl{i}"))
+                .unwrap();
+        let mut frame = vec![];
+        file.read_to_end(&mut frame).unwrap();
+        frame
+    })
+}
+
+// the crawl frames mostly redraw the screen in place, so scroll a tall
+// screen between them (both the whole screen and a scroll region, the way
+// a pager or a chat client would) to measure the cost of scrolling
+fn process_frames(frames: &[Vec<u8>]) {
+    let mut parser = vt100::Parser::new(1000, 80, 0);
+    for frame in frames {
+        parser.process(frame);
+        parser.process(b"\x1b[1000H");
+        parser.process(&[b'\n'; 100]);
+        parser.process(b"\x1b[10;990r\x1b[10H");
+        for _ in 0..50 {
+            parser.process(b"\x1b[L\x1b[M");
+        }
+        parser.process(b"\x1b[r");
+    }
+}
+
+fn main() {
+    let frames: Vec<Vec<u8>> = read_frames().collect();
+    let start = std::time::Instant::now();
+    let mut i = 0;
+    loop {
+        i += 1;
+        process_frames(&frames);
+        if (std::time::Instant::now() - start).as_secs() >= 30 {
+            break;
+        }
+    }
+    eprintln!("{i} iterations");
+}
@@ -0,0 +1,313 @@
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Header {
+    version: u8,
+    width: u16,
+    height: u16,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    timestamp: Option<u64>,
+}
+
+/// An error encountered when reading an asciicast file with
+/// [`AsciicastPlayer::new`].
+#[derive(Debug)]
+pub enum AsciicastError {
+    /// Reading the file failed.
+    Io(std::io::Error),
+
+    /// The file doesn't start with a valid asciicast v2 header.
+    InvalidHeader,
+
+    /// The event on the given (1-based) line of the file is malformed.
+    InvalidEvent(usize),
+}
+
+impl std::fmt::Display for AsciicastError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read asciicast: {e}"),
+            Self::InvalidHeader => f.write_str("invalid asciicast header"),
+            Self::InvalidEvent(line) => {
+                write!(f, "invalid asciicast event on line {line}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AsciicastError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AsciicastError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Records terminal output in the
+/// [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) format,
+/// while also processing it with a [`Parser`](crate::Parser).
+pub struct AsciicastRecorder<W: std::io::Write> {
+    w: W,
+    parser: crate::Parser,
+    start: std::time::Instant,
+    // asciicast output events must be valid utf8, so characters split
+    // across calls to process are held back until they are complete
+    pending: Vec<u8>,
+    time: std::time::Duration,
+}
+
+impl<W: std::io::Write> AsciicastRecorder<W> {
+    /// Creates a new recorder for a terminal of the given size, and writes
+    /// the asciicast header to `w`. Timestamps of recorded events are
+    /// relative to when the recorder was created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the header fails.
+    pub fn new(
+        mut w: W,
+        rows: u16,
+        cols: u16,
+        scrollback_len: usize,
+    ) -> std::io::Result<Self> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+        let header = Header {
+            version: 2,
+            width: cols,
+            height: rows,
+            timestamp,
+        };
+        serde_json::to_writer(&mut w, &header)?;
+        w.write_all(b"\n")?;
+        Ok(Self {
+            w,
+            parser: crate::Parser::new(rows, cols, scrollback_len),
+            start: std::time::Instant::now(),
+            pending: vec![],
+            time: std::time::Duration::ZERO,
+        })
+    }
+
+    /// Processes terminal output, and records it as an output event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the event fails.
+    pub fn process(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.process_at(self.start.elapsed(), bytes)
+    }
+
+    /// Processes terminal output, and records it as an output event at the
+    /// given time since the start of the recording. Times earlier than the
+    /// previous event are treated as the time of the previous event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the event fails.
+    pub fn process_at(
+        &mut self,
+        time: std::time::Duration,
+        bytes: &[u8],
+    ) -> std::io::Result<()> {
+        self.parser.process(bytes);
+        self.pending.extend_from_slice(bytes);
+        let incomplete =
+            crate::snapshot::incomplete_utf8_suffix(&self.pending).len();
+        let complete = self.pending.len() - incomplete;
+        if complete == 0 {
+            return Ok(());
+        }
+        let data =
+            String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        self.pending.drain(..complete);
+        self.write_event(time, "o", &data)
+    }
+
+    /// Resizes the terminal, and records it as a resize event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the event fails.
+    pub fn set_size(&mut self, rows: u16, cols: u16) -> std::io::Result<()> {
+        self.set_size_at(self.start.elapsed(), rows, cols)
+    }
+
+    /// Resizes the terminal, and records it as a resize event at the given
+    /// time since the start of the recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the event fails.
+    pub fn set_size_at(
+        &mut self,
+        time: std::time::Duration,
+        rows: u16,
+        cols: u16,
+    ) -> std::io::Result<()> {
+        self.parser.screen_mut().set_size(rows, cols);
+        self.write_event(time, "r", &format!("{cols}x{rows}"))
+    }
+
+    /// Returns a reference to the [`Parser`](crate::Parser) which has
+    /// processed the recorded output.
+    #[must_use]
+    pub fn parser(&self) -> &crate::Parser {
+        &self.parser
+    }
+
+    /// Returns a reference to a [`Screen`](crate::Screen) object containing
+    /// the terminal state.
+    #[must_use]
+    pub fn screen(&self) -> &crate::Screen {
+        self.parser.screen()
+    }
+
+    /// Writes out any incomplete character which is still being held back,
+    /// flushes the writer, and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing or flushing fails.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if !self.pending.is_empty() {
+            let data = String::from_utf8_lossy(&self.pending).into_owned();
+            self.write_event(self.time, "o", &data)?;
+        }
+        self.w.flush()?;
+        Ok(self.w)
+    }
+
+    fn write_event(
+        &mut self,
+        time: std::time::Duration,
+        code: &str,
+        data: &str,
+    ) -> std::io::Result<()> {
+        self.time = self.time.max(time);
+        serde_json::to_writer(
+            &mut self.w,
+            &(self.time.as_secs_f64(), code, data),
+        )?;
+        self.w.write_all(b"\n")
+    }
+}
+
+/// Plays back a recording in the
+/// [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) format.
+///
+/// When the recording is loaded, a keyframe containing a
+/// [snapshot](crate::Parser::save_snapshot) of the full parser state is
+/// stored periodically, so that seeking only needs to replay the events
+/// since the nearest keyframe. Seeking produces the same state as stepping
+/// through the recording from the start.
+pub struct AsciicastPlayer {
+    playback: crate::playback::Playback,
+}
+
+impl AsciicastPlayer {
+    /// Reads an asciicast v2 recording. The player starts at the beginning
+    /// of the recording, before any events have been applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the recording is malformed.
+    pub fn new<R: std::io::BufRead>(r: R) -> Result<Self, AsciicastError> {
+        let mut lines = r.lines();
+        let header: Header = lines
+            .next()
+            .transpose()?
+            .and_then(|line| serde_json::from_str(&line).ok())
+            .ok_or(AsciicastError::InvalidHeader)?;
+        if header.version != 2 || header.width == 0 || header.height == 0 {
+            return Err(AsciicastError::InvalidHeader);
+        }
+        let size = (header.height, header.width);
+
+        let mut events = vec![];
+        let mut prev_time = std::time::Duration::ZERO;
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // the header is on the first line
+            let line_number = i + 2;
+            let invalid = AsciicastError::InvalidEvent(line_number);
+            let (time, code, data): (f64, String, String) =
+                serde_json::from_str(&line).map_err(|_| invalid)?;
+            let time = std::time::Duration::try_from_secs_f64(time)
+                .ok()
+                .filter(|&time| time >= prev_time)
+                .ok_or(AsciicastError::InvalidEvent(line_number))?;
+            prev_time = time;
+            let event = match code.as_str() {
+                "o" => crate::playback::Event::Output(data.into_bytes()),
+                "r" => {
+                    let (rows, cols) = parse_size(&data)
+                        .ok_or(AsciicastError::InvalidEvent(line_number))?;
+                    crate::playback::Event::Resize(rows, cols)
+                }
+                // input and marker events don't affect the terminal
+                _ => continue,
+            };
+            events.push((time, event));
+        }
+
+        Ok(Self {
+            playback: crate::playback::Playback::new(size, events),
+        })
+    }
+
+    /// Applies the next event in the recording, and returns its time. Returns
+    /// `None` if every event has already been applied.
+    pub fn step(&mut self) -> Option<std::time::Duration> {
+        self.playback.step()
+    }
+
+    /// Returns the time of the last event in the recording.
+    #[must_use]
+    pub fn duration(&self) -> std::time::Duration {
+        self.playback.duration()
+    }
+
+    /// Returns the current playback position.
+    #[must_use]
+    pub fn position(&self) -> std::time::Duration {
+        self.playback.position()
+    }
+
+    /// Moves the playback position to the given time, applying every event
+    /// which occurred at or before it.
+    pub fn seek(&mut self, time: std::time::Duration) {
+        self.playback.seek(time);
+    }
+
+    /// Returns a reference to the [`Parser`](crate::Parser) containing the
+    /// terminal state at the current playback position.
+    #[must_use]
+    pub fn parser(&self) -> &crate::Parser {
+        self.playback.parser()
+    }
+
+    /// Returns a reference to a [`Screen`](crate::Screen) object containing
+    /// the terminal state at the current playback position.
+    #[must_use]
+    pub fn screen(&self) -> &crate::Screen {
+        self.playback.parser().screen()
+    }
+}
+
+fn parse_size(data: &str) -> Option<(u16, u16)> {
+    let (cols, rows) = data.split_once('x')?;
+    let rows = rows.parse().ok().filter(|&rows| rows > 0)?;
+    let cols = cols.parse().ok().filter(|&cols| cols > 0)?;
+    Some((rows, cols))
+}
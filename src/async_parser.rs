@@ -0,0 +1,130 @@
+use tokio::io::AsyncReadExt as _;
+
+/// An adapter for feeding terminal output to a [`Parser`](crate::Parser)
+/// from async code, which publishes the state of the screen to any number
+/// of subscribers.
+///
+/// After each chunk of output is processed, if it changed anything, a
+/// clone of the screen is sent on a [`tokio::sync::watch`] channel. A clone
+/// shares its rows (and its scrollback) with the original, and rows are
+/// only copied when one of the screens next modifies them, so each
+/// subscriber can cheaply keep the last screen it rendered and use
+/// [`Screen::contents_diff`](crate::Screen::contents_diff) to render just
+/// what changed since then, independently of the other subscribers.
+///
+/// Changes are detected by comparing the screen against the last
+/// published one, which is cheap for rows which haven't been modified
+/// since, so the parser's screen (including its
+/// [`damage`](crate::Screen::damage)) is left untouched.
+///
+/// Output can be fed in either by reading it from an
+/// [`AsyncRead`](tokio::io::AsyncRead) with [`run`](Self::run), or by
+/// writing it to this adapter, which implements
+/// [`AsyncWrite`](tokio::io::AsyncWrite). This adapter doesn't implement
+/// the `futures` crate's `Sink` trait, since that would add a dependency
+/// on `futures` for what `AsyncWrite` already provides: a stream of
+/// chunks can be written to it with
+/// [`tokio::io::copy`](tokio::io::copy) or, via `tokio-util`'s
+/// `StreamReader`, from any `Stream` of byte buffers.
+pub struct AsyncParser<CB: crate::callbacks::Callbacks = ()> {
+    parser: crate::Parser<CB>,
+    screen: tokio::sync::watch::Sender<crate::Screen>,
+}
+
+impl<CB: crate::callbacks::Callbacks> AsyncParser<CB> {
+    /// Creates a new adapter around an existing parser.
+    pub fn new(parser: crate::Parser<CB>) -> Self {
+        let (screen, _) =
+            tokio::sync::watch::channel(parser.screen().clone());
+        Self { parser, screen }
+    }
+
+    /// Returns a receiver which is notified with the new state of the
+    /// screen whenever output is processed.
+    #[must_use]
+    pub fn subscribe(&self) -> tokio::sync::watch::Receiver<crate::Screen> {
+        self.screen.subscribe()
+    }
+
+    /// Processes the given bytes, and publishes the new state of the
+    /// screen to the subscribers if it changed.
+    pub fn process(&mut self, bytes: &[u8]) {
+        self.parser.process(bytes);
+        let screen = self.parser.screen();
+        self.screen.send_if_modified(|published| {
+            if screen.same_visible(published) {
+                return false;
+            }
+            *published = screen.clone();
+            true
+        });
+    }
+
+    /// Reads and processes output from `r` until it reaches the end of its
+    /// input, publishing the state of the screen after each read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from `r` fails.
+    pub async fn run<R: tokio::io::AsyncRead + Unpin>(
+        &mut self,
+        mut r: R,
+    ) -> std::io::Result<()> {
+        let mut buf = [0; 4096];
+        loop {
+            match r.read(&mut buf).await {
+                Ok(0) => return Ok(()),
+                Ok(n) => self.process(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns a reference to the wrapped [`Parser`](crate::Parser).
+    #[must_use]
+    pub fn parser(&self) -> &crate::Parser<CB> {
+        &self.parser
+    }
+
+    /// Returns a mutable reference to the wrapped
+    /// [`Parser`](crate::Parser). Changes made through it are published
+    /// along with the next processed output.
+    #[must_use]
+    pub fn parser_mut(&mut self) -> &mut crate::Parser<CB> {
+        &mut self.parser
+    }
+
+    /// Returns the wrapped [`Parser`](crate::Parser).
+    #[must_use]
+    pub fn into_parser(self) -> crate::Parser<CB> {
+        self.parser
+    }
+}
+
+impl<CB: crate::callbacks::Callbacks + Unpin> tokio::io::AsyncWrite
+    for AsyncParser<CB>
+{
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        self.get_mut().process(buf);
+        std::task::Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
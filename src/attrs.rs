@@ -2,6 +2,7 @@ use crate::term::BufWrite as _;
 
 /// Represents a foreground or background color for cells.
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// The default terminal color.
     #[default]
@@ -14,6 +15,83 @@ pub enum Color {
     Rgb(u8, u8, u8),
 }
 
+impl Color {
+    pub(crate) fn write_snapshot(self, w: &mut crate::snapshot::Writer) {
+        match self {
+            Self::Default => w.u8(0),
+            Self::Idx(i) => {
+                w.u8(1);
+                w.u8(i);
+            }
+            Self::Rgb(r, g, b) => {
+                w.u8(2);
+                w.u8(r);
+                w.u8(g);
+                w.u8(b);
+            }
+        }
+    }
+
+    pub(crate) fn read_snapshot(
+        r: &mut crate::snapshot::Reader<'_>,
+    ) -> Result<Self, crate::SnapshotError> {
+        match r.u8()? {
+            0 => Ok(Self::Default),
+            1 => Ok(Self::Idx(r.u8()?)),
+            2 => Ok(Self::Rgb(r.u8()?, r.u8()?, r.u8()?)),
+            _ => Err(crate::SnapshotError::Corrupt),
+        }
+    }
+}
+
+/// The set of colors supported by the terminal that formatted output will be
+/// displayed on.
+///
+/// Colors which the target terminal doesn't support will be replaced by the
+/// nearest color (by RGB distance) in the standard xterm palette that it
+/// does support.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorDepth {
+    /// All colors, including 24-bit RGB colors, are supported.
+    #[default]
+    TrueColor,
+
+    /// The 256 indexed xterm colors are supported.
+    Indexed256,
+
+    /// Only the 16 basic indexed colors are supported.
+    Indexed16,
+
+    /// No colors are supported. Text attributes other than color will still
+    /// be emitted.
+    Monochrome,
+}
+
+impl ColorDepth {
+    pub(crate) fn convert(self, color: Color) -> Color {
+        match (self, color) {
+            (Self::TrueColor, _)
+            | (_, Color::Default)
+            | (Self::Indexed256, Color::Idx(_)) => color,
+            (Self::Indexed256, Color::Rgb(r, g, b)) => Color::Idx(
+                crate::palette::nearest_xterm_color((r, g, b), 16..=255),
+            ),
+            (Self::Indexed16, Color::Idx(i)) if i < 16 => color,
+            (Self::Indexed16, Color::Idx(i)) => {
+                Color::Idx(crate::palette::nearest_xterm_color(
+                    crate::palette::xterm_color(i),
+                    0..=15,
+                ))
+            }
+            (Self::Indexed16, Color::Rgb(r, g, b)) => Color::Idx(
+                crate::palette::nearest_xterm_color((r, g, b), 0..=15),
+            ),
+            (Self::Monochrome, _) => Color::Default,
+        }
+    }
+}
+
 const TEXT_MODE_INTENSITY: u8 = 0b0000_0011;
 const TEXT_MODE_BOLD: u8 = 0b0000_0001;
 const TEXT_MODE_DIM: u8 = 0b0000_0010;
@@ -22,13 +100,99 @@ const TEXT_MODE_UNDERLINE: u8 = 0b0000_1000;
 const TEXT_MODE_INVERSE: u8 = 0b0001_0000;
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "AttrsData", try_from = "AttrsData")
+)]
 pub struct Attrs {
     pub fgcolor: Color,
     pub bgcolor: Color,
     pub mode: u8,
 }
 
+// the serialized form of Attrs, which spells out the text modes rather than
+// exposing the bitfield
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct AttrsData {
+    fgcolor: Color,
+    bgcolor: Color,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool,
+}
+
+#[cfg(feature = "serde")]
+impl From<Attrs> for AttrsData {
+    fn from(attrs: Attrs) -> Self {
+        Self {
+            fgcolor: attrs.fgcolor,
+            bgcolor: attrs.bgcolor,
+            bold: attrs.bold(),
+            dim: attrs.dim(),
+            italic: attrs.italic(),
+            underline: attrs.underline(),
+            inverse: attrs.inverse(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<AttrsData> for Attrs {
+    type Error = &'static str;
+
+    fn try_from(data: AttrsData) -> Result<Self, Self::Error> {
+        let mut attrs = Self {
+            fgcolor: data.fgcolor,
+            bgcolor: data.bgcolor,
+            mode: 0,
+        };
+        match (data.bold, data.dim) {
+            (true, true) => return Err("text can't be both bold and dim"),
+            (true, false) => attrs.set_bold(),
+            (false, true) => attrs.set_dim(),
+            (false, false) => {}
+        }
+        attrs.set_italic(data.italic);
+        attrs.set_underline(data.underline);
+        attrs.set_inverse(data.inverse);
+        Ok(attrs)
+    }
+}
+
 impl Attrs {
+    pub fn write_snapshot(&self, w: &mut crate::snapshot::Writer) {
+        self.fgcolor.write_snapshot(w);
+        self.bgcolor.write_snapshot(w);
+        w.u8(self.mode);
+    }
+
+    pub fn read_snapshot(
+        r: &mut crate::snapshot::Reader<'_>,
+    ) -> Result<Self, crate::SnapshotError> {
+        let fgcolor = Color::read_snapshot(r)?;
+        let bgcolor = Color::read_snapshot(r)?;
+        let mode = r.u8()?;
+        let all_modes = TEXT_MODE_INTENSITY
+            | TEXT_MODE_ITALIC
+            | TEXT_MODE_UNDERLINE
+            | TEXT_MODE_INVERSE;
+        // text can't be both bold and dim
+        if mode & !all_modes != 0
+            || mode & TEXT_MODE_INTENSITY == TEXT_MODE_INTENSITY
+        {
+            return Err(crate::SnapshotError::Corrupt);
+        }
+        Ok(Self {
+            fgcolor,
+            bgcolor,
+            mode,
+        })
+    }
+
     pub fn bold(&self) -> bool {
         self.mode & TEXT_MODE_BOLD != 0
     }
@@ -91,52 +255,66 @@ impl Attrs {
         }
     }
 
+    fn with_color_depth(&self, color_depth: ColorDepth) -> Self {
+        Self {
+            fgcolor: color_depth.convert(self.fgcolor),
+            bgcolor: color_depth.convert(self.bgcolor),
+            mode: self.mode,
+        }
+    }
+
     pub fn write_escape_code_diff(
         &self,
         contents: &mut Vec<u8>,
         other: &Self,
+        color_depth: ColorDepth,
     ) {
-        if self != other && self == &Self::default() {
+        let this = self.with_color_depth(color_depth);
+        let other = other.with_color_depth(color_depth);
+        if this == other {
+            return;
+        }
+        if this == Self::default() {
             crate::term::ClearAttrs.write_buf(contents);
             return;
         }
 
-        let attrs = crate::term::Attrs::default();
+        let attrs = crate::term::Attrs::default().color_depth(color_depth);
 
-        let attrs = if self.fgcolor == other.fgcolor {
+        let attrs = if this.fgcolor == other.fgcolor {
             attrs
         } else {
-            attrs.fgcolor(self.fgcolor)
+            attrs.fgcolor(this.fgcolor)
         };
-        let attrs = if self.bgcolor == other.bgcolor {
+        let attrs = if this.bgcolor == other.bgcolor {
             attrs
         } else {
-            attrs.bgcolor(self.bgcolor)
+            attrs.bgcolor(this.bgcolor)
         };
-        let attrs = if self.intensity() == other.intensity() {
+        let attrs = if this.intensity() == other.intensity() {
             attrs
         } else {
-            attrs.intensity(match self.intensity() {
+            attrs.intensity(match this.intensity() {
                 0 => crate::term::Intensity::Normal,
                 TEXT_MODE_BOLD => crate::term::Intensity::Bold,
                 TEXT_MODE_DIM => crate::term::Intensity::Dim,
                 _ => unreachable!(),
             })
         };
-        let attrs = if self.italic() == other.italic() {
+        let attrs = if this.italic() == other.italic() {
             attrs
         } else {
-            attrs.italic(self.italic())
+            attrs.italic(this.italic())
         };
-        let attrs = if self.underline() == other.underline() {
+        let attrs = if this.underline() == other.underline() {
             attrs
         } else {
-            attrs.underline(self.underline())
+            attrs.underline(this.underline())
         };
-        let attrs = if self.inverse() == other.inverse() {
+        let attrs = if this.inverse() == other.inverse() {
             attrs
         } else {
-            attrs.inverse(self.inverse())
+            attrs.inverse(this.inverse())
         };
 
         attrs.write_buf(contents);
@@ -0,0 +1,193 @@
+/// Identifies a client of a [`Broadcaster`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ClientId(u64);
+
+/// A chunk of output to be sent to a client of a [`Broadcaster`].
+#[derive(Clone, Debug)]
+pub struct Frame {
+    version: u64,
+    full: bool,
+    size: (u16, u16),
+    data: Vec<u8>,
+}
+
+impl Frame {
+    /// Returns the version of the screen that this frame brings the client
+    /// up to. Pass this to [`Broadcaster::ack`] once the client has
+    /// received the frame.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns true if this frame redraws the entire terminal state
+    /// rather than applying a diff to what the client already has.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Returns the size of the screen in this frame, as `(rows, cols)`.
+    ///
+    /// Escape codes can't resize the client's terminal, so the client
+    /// should be resized to this size before processing the data of a
+    /// full frame. Frames are always full when the size changed since the
+    /// last frame the client acknowledged.
+    #[must_use]
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    /// Returns the escape codes to send to the client.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the escape codes to send to the client, consuming the frame.
+    #[must_use]
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+#[derive(Default)]
+struct Client {
+    acked: Option<(u64, crate::Screen)>,
+    in_flight: Option<(u64, crate::Screen)>,
+}
+
+/// Shares the state of a single screen with multiple clients, each of
+/// which can receive updates at its own pace.
+///
+/// Each call to [`update`](Self::update) creates a new version of the
+/// screen. A client is sent one [`Frame`] at a time via
+/// [`next_frame`](Self::next_frame), and won't be sent another until it
+/// acknowledges the current one with [`ack`](Self::ack). New clients are
+/// sent the full terminal state (via
+/// [`Screen::state_formatted`](crate::Screen::state_formatted)), and
+/// after that clients are sent the difference between the latest version
+/// and the last version they acknowledged (via
+/// [`Screen::state_diff`](crate::Screen::state_diff)), so a slow client
+/// skips any versions that were superseded while it was catching up.
+/// Clients are sent the full terminal state again if the screen was
+/// resized since the last version they acknowledged, since diffs assume
+/// that the client's terminal is the same size as the screen.
+///
+/// Since unchanged rows are shared between clones of a screen, keeping a
+/// copy of the last acknowledged screen for each client is cheap.
+pub struct Broadcaster {
+    screen: crate::Screen,
+    version: u64,
+    clients: std::collections::HashMap<ClientId, Client>,
+    next_id: u64,
+}
+
+impl Broadcaster {
+    /// Creates a new broadcaster, starting from the given screen state.
+    #[must_use]
+    pub fn new(screen: &crate::Screen) -> Self {
+        Self {
+            screen: screen.clone(),
+            version: 0,
+            clients: std::collections::HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Records a new version of the screen to send to the clients. If
+    /// nothing that would be sent to the clients changed, the version
+    /// stays the same and no new frames are sent.
+    pub fn update(&mut self, screen: &crate::Screen) {
+        if screen.same_visible(&self.screen) {
+            return;
+        }
+        self.screen = screen.clone();
+        self.version += 1;
+    }
+
+    /// Returns the latest version of the screen.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the latest state of the screen.
+    #[must_use]
+    pub fn screen(&self) -> &crate::Screen {
+        &self.screen
+    }
+
+    /// Adds a new client, which will be sent the full terminal state as
+    /// its first frame.
+    pub fn add_client(&mut self) -> ClientId {
+        let id = ClientId(self.next_id);
+        self.next_id += 1;
+        self.clients.insert(id, Client::default());
+        id
+    }
+
+    /// Removes a client. Returns false if the client didn't exist.
+    pub fn remove_client(&mut self, id: ClientId) -> bool {
+        self.clients.remove(&id).is_some()
+    }
+
+    /// Returns the number of clients.
+    #[must_use]
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Returns the next frame to send to the given client.
+    ///
+    /// Returns `None` if the client is already up to date, if it hasn't
+    /// yet acknowledged the previous frame, or if it doesn't exist.
+    pub fn next_frame(&mut self, id: ClientId) -> Option<Frame> {
+        let client = self.clients.get_mut(&id)?;
+        if client.in_flight.is_some() {
+            return None;
+        }
+        let (full, data) = match &client.acked {
+            Some((version, _)) if *version == self.version => return None,
+            Some((_, prev)) if prev.size() == self.screen.size() => {
+                (false, self.screen.state_diff(prev))
+            }
+            _ => (true, self.screen.state_formatted()),
+        };
+        let frame = Frame {
+            version: self.version,
+            full,
+            size: self.screen.size(),
+            data,
+        };
+        client.in_flight = Some((self.version, self.screen.clone()));
+        Some(frame)
+    }
+
+    /// Acknowledges that the given client has received the frame with the
+    /// given version, so that later frames can be diffed against it.
+    ///
+    /// Acknowledgements for any other version than that of the frame most
+    /// recently returned by [`next_frame`](Self::next_frame) are ignored.
+    pub fn ack(&mut self, id: ClientId, version: u64) {
+        let Some(client) = self.clients.get_mut(&id) else {
+            return;
+        };
+        if client
+            .in_flight
+            .as_ref()
+            .is_some_and(|(v, _)| *v == version)
+        {
+            client.acked = client.in_flight.take();
+        }
+    }
+
+    /// Forces the given client to be sent the full terminal state as its
+    /// next frame, for instance if it lost track of its own state. Any
+    /// unacknowledged frame is discarded.
+    pub fn reset_client(&mut self, id: ClientId) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            *client = Client::default();
+        }
+    }
+}
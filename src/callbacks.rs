@@ -64,6 +64,24 @@ pub trait Callbacks {
     /// This callback is called when the terminal receives a OSC sequence
     /// (`\e]`) which is otherwise not implemented.
     fn unhandled_osc(&mut self, _: &mut crate::Screen, _params: &[&[u8]]) {}
+    /// This callback is called when the terminal receives a complete DCS
+    /// sequence (`\eP`), with its payload in `data`. Sequences are only
+    /// complete once they are ended by an ST, so sequences which are
+    /// cancelled (by CAN or SUB) or interrupted by another escape sequence
+    /// are discarded without calling this, as are sequences with a payload
+    /// longer than 1MiB. tmux passthrough sequences (`\ePtmux;...\e\\`)
+    /// are unwrapped and their contents processed as if they had been
+    /// received directly, rather than being passed to this callback.
+    fn unhandled_dcs(
+        &mut self,
+        _: &mut crate::Screen,
+        _i1: Option<u8>,
+        _i2: Option<u8>,
+        _params: &[&[u16]],
+        _c: char,
+        _data: &[u8],
+    ) {
+    }
 }
 
 impl Callbacks for () {}
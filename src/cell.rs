@@ -9,6 +9,11 @@ const LEN_BITS: u8 = 0b0001_1111;
 
 /// Represents a single terminal cell.
 #[derive(Clone, Debug, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "CellData", try_from = "CellData")
+)]
 pub struct Cell {
     contents: [u8; CONTENT_BYTES],
     len: u8,
@@ -16,6 +21,51 @@ pub struct Cell {
 }
 const _: () = assert!(std::mem::size_of::<Cell>() == 32);
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CellData {
+    contents: String,
+    is_wide: bool,
+    is_wide_continuation: bool,
+    attrs: crate::attrs::Attrs,
+}
+
+#[cfg(feature = "serde")]
+impl From<Cell> for CellData {
+    fn from(cell: Cell) -> Self {
+        Self {
+            contents: cell.contents().to_string(),
+            is_wide: cell.is_wide(),
+            is_wide_continuation: cell.is_wide_continuation(),
+            attrs: cell.attrs,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<CellData> for Cell {
+    type Error = &'static str;
+
+    fn try_from(data: CellData) -> Result<Self, Self::Error> {
+        if !Self::valid_raw_contents(data.contents.as_bytes()) {
+            return Err("cell contents are too long");
+        }
+        // valid_raw_contents ensures that the length fits in LEN_BITS
+        let mut len = u8::try_from(data.contents.len()).unwrap();
+        if data.is_wide {
+            len |= IS_WIDE;
+        }
+        if data.is_wide_continuation {
+            len |= IS_WIDE_CONTINUATION;
+        }
+        Ok(Self::from_raw_contents(
+            data.contents.as_bytes(),
+            len,
+            data.attrs,
+        ))
+    }
+}
+
 impl PartialEq<Self> for Cell {
     fn eq(&self, other: &Self) -> bool {
         if self.len != other.len {
@@ -126,6 +176,44 @@ impl Cell {
         }
     }
 
+    // whether the cell is in the same state as a newly created cell
+    pub(crate) fn is_default(&self) -> bool {
+        self.len == 0 && self.attrs == crate::attrs::Attrs::default()
+    }
+
+    // the contents and the raw length byte (including the wide flags), for
+    // storing the cell in a more compact form
+    pub(crate) fn raw_contents(&self) -> (&[u8], u8) {
+        (&self.contents[..self.len()], self.len)
+    }
+
+    pub(crate) fn raw_contents_len(len: u8) -> usize {
+        usize::from(len & LEN_BITS)
+    }
+
+    // the (is_wide, is_wide_continuation) flags from a raw length byte
+    pub(crate) fn raw_wide_flags(len: u8) -> (bool, bool) {
+        (len & IS_WIDE != 0, len & IS_WIDE_CONTINUATION != 0)
+    }
+
+    // whether the given bytes can be used as the contents of a cell
+    pub(crate) fn valid_raw_contents(contents: &[u8]) -> bool {
+        contents.len() <= CONTENT_BYTES
+            && std::str::from_utf8(contents).is_ok()
+    }
+
+    pub(crate) fn from_raw_contents(
+        contents: &[u8],
+        len: u8,
+        attrs: crate::attrs::Attrs,
+    ) -> Self {
+        let mut cell = Self::new();
+        cell.contents[..contents.len()].copy_from_slice(contents);
+        cell.len = len;
+        cell.attrs = attrs;
+        cell
+    }
+
     pub(crate) fn attrs(&self) -> &crate::attrs::Attrs {
         &self.attrs
     }
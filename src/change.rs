@@ -0,0 +1,79 @@
+/// A single difference between two screens, as returned by
+/// [`Screen::changes`](crate::Screen::changes).
+///
+/// Applying each change in order to a rendering of the previous screen
+/// brings it up to date with the current screen.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    /// The screen was resized. The new screen should be treated as blank,
+    /// and any changes to its contents are reported after this.
+    Resize {
+        /// The new number of rows.
+        rows: u16,
+        /// The new number of columns.
+        cols: u16,
+    },
+
+    /// The rows from `top` to `bottom` (inclusive) were scrolled up by
+    /// `count` rows, or down if `up` is false. Rows scrolled out of that
+    /// range are discarded, and the rows scrolled into it are blank. Any
+    /// changes to the rows reported after this are relative to the
+    /// scrolled rows.
+    Scroll {
+        /// The first row of the scrolled range.
+        top: u16,
+        /// The last row of the scrolled range.
+        bottom: u16,
+        /// The number of rows that the range was scrolled by.
+        count: u16,
+        /// Whether the rows moved up (`true`) or down (`false`).
+        up: bool,
+    },
+
+    /// A run of consecutive cells in a row changed.
+    Cells {
+        /// The row containing the cells.
+        row: u16,
+        /// The column of the first changed cell.
+        col: u16,
+        /// The new contents of the cells, starting at `col`.
+        cells: Vec<crate::Cell>,
+    },
+
+    /// Whether a row wraps onto the next row changed. This is reported
+    /// after any changes to the cells of the row.
+    Wrapped {
+        /// The row which changed.
+        row: u16,
+        /// Whether the row now wraps.
+        wrapped: bool,
+    },
+
+    /// The cursor moved to the given position.
+    CursorPosition {
+        /// The new cursor row.
+        row: u16,
+        /// The new cursor column. As with
+        /// [`Screen::cursor_position`](crate::Screen::cursor_position),
+        /// this can be equal to the number of columns.
+        col: u16,
+    },
+
+    /// The cursor was hidden (`true`) or shown (`false`).
+    HideCursor(bool),
+
+    /// Application keypad mode was enabled or disabled.
+    ApplicationKeypad(bool),
+
+    /// Application cursor mode was enabled or disabled.
+    ApplicationCursor(bool),
+
+    /// Bracketed paste mode was enabled or disabled.
+    BracketedPaste(bool),
+
+    /// The mouse reporting mode changed.
+    MouseProtocolMode(crate::MouseProtocolMode),
+
+    /// The mouse reporting encoding changed.
+    MouseProtocolEncoding(crate::MouseProtocolEncoding),
+}
@@ -0,0 +1,152 @@
+// a read-only representation of a row, used for rows in the scrollback.
+// trailing blank cells are dropped, the cell contents are stored as a
+// single string, and attributes are stored as runs of identical attributes.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompactRow {
+    cols: u16,
+    wrapped: bool,
+    // the raw length byte of each stored cell (including the wide flags)
+    lens: Box<[u8]>,
+    // the concatenated contents of each stored cell
+    text: Box<[u8]>,
+    // (number of cells, attrs) for each run of cells with the same attrs
+    attrs: Box<[(u16, crate::attrs::Attrs)]>,
+}
+
+impl CompactRow {
+    pub fn new(row: &crate::row::Row) -> Self {
+        let len = row
+            .cells()
+            .rposition(|cell| !cell.is_default())
+            .map_or(0, |i| i + 1);
+
+        let mut lens = Vec::with_capacity(len);
+        let mut text = vec![];
+        let mut attrs: Vec<(u16, crate::attrs::Attrs)> = vec![];
+        for cell in row.cells().take(len) {
+            let (contents, len) = cell.raw_contents();
+            lens.push(len);
+            text.extend_from_slice(contents);
+            match attrs.last_mut() {
+                Some((count, run_attrs)) if run_attrs == cell.attrs() => {
+                    *count += 1;
+                }
+                _ => attrs.push((1, *cell.attrs())),
+            }
+        }
+
+        Self {
+            // a row's width always fits in a u16 (see Size)
+            cols: row.cells().len().try_into().unwrap(),
+            wrapped: row.wrapped(),
+            lens: lens.into_boxed_slice(),
+            text: text.into_boxed_slice(),
+            attrs: attrs.into_boxed_slice(),
+        }
+    }
+
+    pub fn expand(&self) -> crate::row::Row {
+        let mut cells = Vec::with_capacity(usize::from(self.cols));
+        let mut lens = self.lens.iter();
+        let mut text = &self.text[..];
+        for &(count, attrs) in &*self.attrs {
+            for _ in 0..count {
+                // there is exactly one length for each cell covered by the
+                // attribute runs
+                let len = *lens.next().unwrap();
+                let (contents, rest) =
+                    text.split_at(crate::Cell::raw_contents_len(len));
+                cells.push(crate::Cell::from_raw_contents(
+                    contents, len, attrs,
+                ));
+                text = rest;
+            }
+        }
+        cells.resize(usize::from(self.cols), crate::Cell::new());
+        crate::row::Row::from_cells(cells, self.wrapped)
+    }
+
+    // checks that the row is internally consistent, since it may have been
+    // loaded from arbitrary data
+    pub fn is_valid(&self) -> bool {
+        let cells: usize = self
+            .attrs
+            .iter()
+            .map(|&(count, _)| usize::from(count))
+            .sum();
+        if cells != self.lens.len() || cells > usize::from(self.cols) {
+            return false;
+        }
+        // the cells after the stored ones are blank, so a wide cell can't
+        // be the last stored cell
+        if !crate::row::valid_wide_cells(
+            self.lens
+                .iter()
+                .map(|&len| crate::Cell::raw_wide_flags(len)),
+        ) {
+            return false;
+        }
+        let mut text = &self.text[..];
+        for &len in &*self.lens {
+            let len = crate::Cell::raw_contents_len(len);
+            if len > text.len() {
+                return false;
+            }
+            let (contents, rest) = text.split_at(len);
+            if !crate::Cell::valid_raw_contents(contents) {
+                return false;
+            }
+            text = rest;
+        }
+        text.is_empty()
+    }
+
+    pub fn write_snapshot(&self, w: &mut crate::snapshot::Writer) {
+        w.uint(self.cols);
+        w.bool(self.wrapped);
+        w.bytes(&self.lens);
+        w.bytes(&self.text);
+        w.usize(self.attrs.len());
+        for (count, attrs) in &*self.attrs {
+            w.uint(*count);
+            attrs.write_snapshot(w);
+        }
+    }
+
+    pub fn read_snapshot(
+        r: &mut crate::snapshot::Reader<'_>,
+    ) -> Result<Self, crate::SnapshotError> {
+        let cols = r.u16()?;
+        let wrapped = r.bool()?;
+        let lens = r.bytes()?.into();
+        let text = r.bytes()?.into();
+        let attrs_len = r.usize()?;
+        // each attribute run covers at least one cell
+        if attrs_len > usize::from(cols) {
+            return Err(crate::SnapshotError::Corrupt);
+        }
+        let mut attrs = Vec::with_capacity(attrs_len);
+        for _ in 0..attrs_len {
+            attrs.push((r.u16()?, crate::attrs::Attrs::read_snapshot(r)?));
+        }
+        let row = Self {
+            cols,
+            wrapped,
+            lens,
+            text,
+            attrs: attrs.into_boxed_slice(),
+        };
+        if !row.is_valid() {
+            return Err(crate::SnapshotError::Corrupt);
+        }
+        Ok(row)
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.lens.len()
+            + self.text.len()
+            + std::mem::size_of_val(&*self.attrs)
+    }
+}
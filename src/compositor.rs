@@ -0,0 +1,378 @@
+/// A rectangular area of the composited screen.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Rect {
+    /// The row of the top edge of the area.
+    pub row: u16,
+    /// The column of the left edge of the area.
+    pub col: u16,
+    /// The number of rows in the area.
+    pub rows: u16,
+    /// The number of columns in the area.
+    pub cols: u16,
+}
+
+impl Rect {
+    /// Creates a new area with the given position and size.
+    #[must_use]
+    pub fn new(row: u16, col: u16, rows: u16, cols: u16) -> Self {
+        Self {
+            row,
+            col,
+            rows,
+            cols,
+        }
+    }
+
+    fn contains(self, pos: crate::grid::Pos) -> bool {
+        (self.row..self.row.saturating_add(self.rows)).contains(&pos.row)
+            && (self.col..self.col.saturating_add(self.cols))
+                .contains(&pos.col)
+    }
+
+    // the area including a one cell border on every side, clipped at the
+    // top left corner of the screen
+    fn outset(self) -> Self {
+        let row = self.row.saturating_sub(1);
+        let col = self.col.saturating_sub(1);
+        Self {
+            row,
+            col,
+            rows: self.row.saturating_add(self.rows).saturating_add(1) - row,
+            cols: self.col.saturating_add(self.cols).saturating_add(1) - col,
+        }
+    }
+}
+
+/// A border drawn around a [`Pane`].
+#[derive(Clone, Debug, Default)]
+pub struct Border {
+    title: String,
+    fgcolor: crate::Color,
+    bgcolor: crate::Color,
+}
+
+impl Border {
+    /// Creates a new border, drawn in the default colors with no title.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the title drawn at the left of the top edge of the border.
+    #[must_use]
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Sets the foreground color of the border.
+    #[must_use]
+    pub fn fgcolor(mut self, fgcolor: crate::Color) -> Self {
+        self.fgcolor = fgcolor;
+        self
+    }
+
+    /// Sets the background color of the border.
+    #[must_use]
+    pub fn bgcolor(mut self, bgcolor: crate::Color) -> Self {
+        self.bgcolor = bgcolor;
+        self
+    }
+}
+
+/// A screen to be drawn by a [`Compositor`], along with where and how to
+/// draw it.
+#[derive(Clone, Debug)]
+pub struct Pane<'a> {
+    screen: &'a crate::Screen,
+    rect: Rect,
+    border: Option<Border>,
+    focused: bool,
+}
+
+impl<'a> Pane<'a> {
+    /// Creates a new pane which draws the visible contents of `screen` in
+    /// the area `rect`.
+    ///
+    /// If the screen is larger than the area, it is clipped at the right
+    /// and bottom edges. If it is smaller, the rest of the area is cleared.
+    #[must_use]
+    pub fn new(screen: &'a crate::Screen, rect: Rect) -> Self {
+        Self {
+            screen,
+            rect,
+            border: None,
+            focused: false,
+        }
+    }
+
+    /// Draws a border in the cells surrounding the pane's area.
+    #[must_use]
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Sets whether this pane is focused. The cursor position and input
+    /// modes of the composited screen are taken from the focused pane.
+    #[must_use]
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    fn draw(&self, screen: &mut crate::Screen) {
+        let (rows, cols) = screen.size();
+        let clip = Rect {
+            rows: self.rect.rows.min(rows.saturating_sub(self.rect.row)),
+            cols: self.rect.cols.min(cols.saturating_sub(self.rect.col)),
+            ..self.rect
+        };
+        let blank = crate::Cell::new();
+        for row in 0..clip.rows {
+            for col in 0..clip.cols {
+                let pos = crate::grid::Pos {
+                    row: clip.row + row,
+                    col: clip.col + col,
+                };
+                let cell = self.screen.cell(row, col).unwrap_or(&blank);
+                if cell.is_wide_continuation() && col > 0 {
+                    // already drawn along with the wide cell before it
+                    continue;
+                }
+                if (cell.is_wide() && col + 1 >= clip.cols)
+                    || cell.is_wide_continuation()
+                {
+                    // only half of the character is visible
+                    let mut cell = cell.clone();
+                    cell.clear(*cell.attrs());
+                    screen.put_cell(pos, &cell);
+                } else {
+                    screen.put_cell(pos, cell);
+                    if cell.is_wide() {
+                        let mut next = pos;
+                        next.col += 1;
+                        // the continuation must exist because the wide
+                        // cell is not in the last column
+                        screen.put_cell(
+                            next,
+                            self.screen.cell(row, col + 1).unwrap(),
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(border) = &self.border {
+            self.draw_border(screen, border);
+        }
+    }
+
+    fn draw_border(&self, screen: &mut crate::Screen, border: &Border) {
+        let attrs = crate::attrs::Attrs {
+            fgcolor: border.fgcolor,
+            bgcolor: border.bgcolor,
+            ..Default::default()
+        };
+        let mut put = |row: Option<u16>, col: Option<u16>, c: char| {
+            if let (Some(row), Some(col)) = (row, col) {
+                let mut cell = crate::Cell::new();
+                cell.set(c, attrs);
+                screen.put_cell(crate::grid::Pos { row, col }, &cell);
+            }
+        };
+
+        let Rect {
+            row,
+            col,
+            rows,
+            cols,
+        } = self.rect;
+        let top = row.checked_sub(1);
+        let bottom = row.checked_add(rows);
+        let left = col.checked_sub(1);
+        let right = col.checked_add(cols);
+        put(top, left, '┌');
+        put(top, right, '┐');
+        put(bottom, left, '└');
+        put(bottom, right, '┘');
+        for i in 0..rows {
+            put(row.checked_add(i), left, '│');
+            put(row.checked_add(i), right, '│');
+        }
+
+        let mut title = border.title.chars().filter(|c| {
+            unicode_width::UnicodeWidthChar::width(*c) == Some(1)
+        });
+        for i in 0..cols {
+            let c = if i == 0 { None } else { title.next() };
+            put(top, col.checked_add(i), c.unwrap_or('─'));
+            put(bottom, col.checked_add(i), '─');
+        }
+    }
+
+    fn covered_area(&self) -> Rect {
+        if self.border.is_some() {
+            self.rect.outset()
+        } else {
+            self.rect
+        }
+    }
+
+    fn cursor_position(&self) -> Option<crate::grid::Pos> {
+        if self.screen.hide_cursor() {
+            return None;
+        }
+        let (screen_rows, screen_cols) = self.screen.size();
+        let (row, col) = self.screen.cursor_position();
+        // a cursor waiting to wrap is just past the end of its row, but it
+        // is drawn in the last column, like terminals do
+        let col = col.min(screen_cols.saturating_sub(1));
+        if row >= self.rect.rows
+            || col >= self.rect.cols
+            || row >= screen_rows
+        {
+            return None;
+        }
+        Some(crate::grid::Pos {
+            row: self.rect.row.checked_add(row)?,
+            col: self.rect.col.checked_add(col)?,
+        })
+    }
+}
+
+/// Combines several screens into a single output screen, such as for
+/// drawing split panes or popup windows.
+///
+/// Each call to [`render`](Self::render) draws the given panes in order,
+/// so later panes (and their borders) are drawn over earlier ones, and
+/// everything is clipped to the size of the compositor. Areas not covered
+/// by any pane are left blank.
+/// The cursor position, cursor visibility and input modes of the result
+/// are taken from the focused pane, and the cursor is hidden if there is
+/// no focused pane or if its cursor isn't visible.
+///
+/// The result of rendering is returned as terminal escape codes which
+/// update the previously rendered output, as produced by
+/// [`Screen::state_diff`](crate::Screen::state_diff), so only the cells
+/// which changed are redrawn. The output screen is kept between renders,
+/// and only the cells which changed are written to it.
+#[derive(Clone, Debug)]
+pub struct Compositor {
+    rows: u16,
+    cols: u16,
+    screen: crate::Screen,
+    drawn: bool,
+}
+
+impl Compositor {
+    /// Creates a new compositor with an output screen of the given size.
+    #[must_use]
+    pub fn new(rows: u16, cols: u16) -> Self {
+        Self {
+            rows,
+            cols,
+            screen: crate::Screen::blank(rows, cols),
+            drawn: false,
+        }
+    }
+
+    /// Resizes the output screen. The next call to
+    /// [`render`](Self::render) will redraw the entire screen.
+    pub fn set_size(&mut self, rows: u16, cols: u16) {
+        let color_depth = self.screen.color_depth();
+        self.rows = rows;
+        self.cols = cols;
+        self.screen = crate::Screen::blank(rows, cols);
+        self.screen.set_color_depth(color_depth);
+        self.drawn = false;
+    }
+
+    /// Returns the size of the output screen.
+    ///
+    /// The return value will be (rows, cols).
+    #[must_use]
+    pub fn size(&self) -> (u16, u16) {
+        (self.rows, self.cols)
+    }
+
+    /// Sets the set of colors that the terminal being drawn to supports.
+    /// Colors which are not supported are replaced by the nearest
+    /// supported color. The next call to [`render`](Self::render) will
+    /// redraw the entire screen.
+    pub fn set_color_depth(&mut self, color_depth: crate::ColorDepth) {
+        self.screen.set_color_depth(color_depth);
+        self.drawn = false;
+    }
+
+    /// Returns the set of colors that output is generated for.
+    #[must_use]
+    pub fn color_depth(&self) -> crate::ColorDepth {
+        self.screen.color_depth()
+    }
+
+    /// Makes the next call to [`render`](Self::render) redraw the entire
+    /// screen, such as when the terminal being drawn to has been cleared.
+    pub fn reset(&mut self) {
+        self.drawn = false;
+    }
+
+    /// Returns the most recently rendered output screen, if any.
+    #[must_use]
+    pub fn screen(&self) -> Option<&crate::Screen> {
+        self.drawn.then_some(&self.screen)
+    }
+
+    /// Draws the given panes, and returns the terminal escape codes needed
+    /// to update the previous output to match. The first call (and the
+    /// first call after [`set_size`](Self::set_size) or
+    /// [`reset`](Self::reset)) returns the entire terminal state.
+    pub fn render(&mut self, panes: &[Pane<'_>]) -> Vec<u8> {
+        // rows are shared with the previous output until they are drawn
+        // differently, so keeping it around to diff against is cheap
+        let prev = self.drawn.then(|| self.screen.clone());
+        let screen = &mut self.screen;
+
+        // clear everything which isn't about to be drawn over, rather than
+        // clearing the whole screen, so that cells drawn the same way as
+        // last time are left alone
+        let blank = crate::Cell::new();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let pos = crate::grid::Pos { row, col };
+                if !panes.iter().any(|pane| pane.covered_area().contains(pos))
+                {
+                    screen.put_cell(pos, &blank);
+                }
+            }
+        }
+        for pane in panes {
+            pane.draw(screen);
+        }
+
+        if let Some((i, pane)) = panes
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, pane)| pane.focused)
+        {
+            // the cursor is hidden if it is drawn over by another pane
+            let pos = pane.cursor_position().filter(|pos| {
+                pos.row < self.rows
+                    && pos.col < self.cols
+                    && !panes[i + 1..]
+                        .iter()
+                        .any(|pane| pane.covered_area().contains(*pos))
+            });
+            screen.copy_cursor_state(pane.screen, pos);
+        } else {
+            screen.clear_cursor_state();
+        }
+
+        self.drawn = true;
+        prev.map_or_else(
+            || screen.state_formatted(),
+            |prev| screen.state_diff(&prev),
+        )
+    }
+}
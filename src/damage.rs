@@ -0,0 +1,54 @@
+/// Describes which parts of the visible screen have changed since damage
+/// tracking was last reset.
+///
+/// To bring an existing rendering of the screen up to date, first move its
+/// contents up by [`scrolled`](Self::scrolled) rows (down, if it is
+/// negative), and then redraw each of the rows in
+/// [`dirty_rows`](Self::dirty_rows). Rows which were moved onto the screen
+/// by scrolling are always included in the dirty rows.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Damage {
+    scrolled: i32,
+    dirty_rows: Vec<u16>,
+}
+
+impl Damage {
+    pub(crate) fn new(
+        scrolled: i32,
+        dirty_rows: impl IntoIterator<Item = u16>,
+    ) -> Self {
+        Self {
+            scrolled,
+            dirty_rows: dirty_rows.into_iter().collect(),
+        }
+    }
+
+    /// Returns the number of rows that the whole screen has scrolled up
+    /// by. A negative value means that the screen scrolled down.
+    ///
+    /// Scrolling that only affected part of the screen (because a scroll
+    /// region was active, for instance) is reported via dirty rows instead.
+    #[must_use]
+    pub fn scrolled(&self) -> i32 {
+        self.scrolled
+    }
+
+    /// Returns the indexes of the rows which need to be redrawn, in
+    /// increasing order.
+    #[must_use]
+    pub fn dirty_rows(&self) -> &[u16] {
+        &self.dirty_rows
+    }
+
+    /// Returns whether the given row needs to be redrawn.
+    #[must_use]
+    pub fn is_dirty(&self, row: u16) -> bool {
+        self.dirty_rows.binary_search(&row).is_ok()
+    }
+
+    /// Returns true if nothing on the screen has changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scrolled == 0 && self.dirty_rows.is_empty()
+    }
+}
@@ -0,0 +1,296 @@
+/// A condition on the state of the screen, to wait for with
+/// [`Expect::expect`].
+pub enum Expectation {
+    /// The given text appears starting at the given row and column.
+    TextAt {
+        /// The row the text starts on.
+        row: u16,
+        /// The column the text starts at.
+        col: u16,
+        /// The expected text.
+        text: String,
+    },
+
+    /// The given text appears anywhere in
+    /// [`Screen::contents`](crate::Screen::contents).
+    Contains(String),
+
+    /// The regex matches [`Screen::contents`](crate::Screen::contents).
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+
+    /// The cursor is at the given row and column.
+    CursorAt {
+        /// The row of the cursor.
+        row: u16,
+        /// The column of the cursor.
+        col: u16,
+    },
+
+    /// The screen hasn't changed for the given amount of time, or the
+    /// output has ended.
+    Stable(std::time::Duration),
+
+    /// The given function returns true.
+    Predicate(Box<dyn Fn(&crate::Screen) -> bool>),
+}
+
+impl Expectation {
+    fn matches(
+        &self,
+        screen: &crate::Screen,
+        unchanged: std::time::Duration,
+        eof: bool,
+    ) -> bool {
+        match self {
+            Self::TextAt { row, col, text } => {
+                let (_, cols) = screen.size();
+                screen
+                    .rows(*col, cols.saturating_sub(*col))
+                    .nth(usize::from(*row))
+                    .is_some_and(|line| line.starts_with(text.as_str()))
+            }
+            Self::Contains(text) => screen.contents().contains(text.as_str()),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(&screen.contents()),
+            Self::CursorAt { row, col } => {
+                screen.cursor_position() == (*row, *col)
+            }
+            Self::Stable(duration) => eof || unchanged >= *duration,
+            Self::Predicate(f) => f(screen),
+        }
+    }
+}
+
+impl std::fmt::Debug for Expectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TextAt { row, col, text } => {
+                write!(f, "text {text:?} at ({row}, {col})")
+            }
+            Self::Contains(text) => write!(f, "text {text:?}"),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => write!(f, "regex /{regex}/"),
+            Self::CursorAt { row, col } => {
+                write!(f, "cursor at ({row}, {col})")
+            }
+            Self::Stable(duration) => {
+                write!(f, "screen to be stable for {duration:?}")
+            }
+            Self::Predicate(_) => f.write_str("predicate"),
+        }
+    }
+}
+
+/// An error returned by [`Expect::expect`].
+#[derive(Debug)]
+pub enum ExpectError {
+    /// The expectation wasn't met before the timeout.
+    Timeout {
+        /// A description of the expectation.
+        expectation: String,
+        /// A rendering of the screen at the time of the failure.
+        screen: String,
+    },
+
+    /// The output ended without the expectation being met.
+    Eof {
+        /// A description of the expectation.
+        expectation: String,
+        /// A rendering of the screen at the time of the failure.
+        screen: String,
+    },
+
+    /// Reading more output failed.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ExpectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout {
+                expectation,
+                screen,
+            } => write!(f, "timed out waiting for {expectation}\n{screen}"),
+            Self::Eof {
+                expectation,
+                screen,
+            } => write!(
+                f,
+                "output ended while waiting for {expectation}\n{screen}"
+            ),
+            Self::Io(e) => write!(f, "failed to read output: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ExpectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ExpectError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// A source of terminal output which can be waited on until the screen
+/// reaches an expected state, for driving terminal programs in tests.
+///
+/// This is implemented for [`ChannelParser`], which processes output sent
+/// from another thread, and for [`Session`](crate::Session) when the `pty`
+/// feature is enabled.
+pub trait Expect {
+    /// Returns the current state of the screen.
+    fn screen(&self) -> &crate::Screen;
+
+    /// Waits up to `timeout` for more output, and processes any output
+    /// which arrives. Returns `false` if no more output will ever arrive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the output fails.
+    fn wait_for_output(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> std::io::Result<bool>;
+
+    /// Processes output until the screen matches `expectation`, failing if
+    /// that doesn't happen within `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expectation isn't met before the timeout or
+    /// the end of the output, or if reading the output fails. The error
+    /// includes a rendering of the screen at the time of the failure.
+    fn expect(
+        &mut self,
+        expectation: &Expectation,
+        timeout: std::time::Duration,
+    ) -> Result<(), ExpectError> {
+        let start = std::time::Instant::now();
+        let deadline = start + timeout;
+        let mut prev = self.screen().clone();
+        let mut changed = start;
+        let mut eof = false;
+        loop {
+            let now = std::time::Instant::now();
+            if expectation.matches(self.screen(), now - changed, eof) {
+                return Ok(());
+            }
+            if eof || now >= deadline {
+                let expectation = format!("{expectation:?}");
+                let screen = dump(self.screen());
+                return Err(if eof {
+                    ExpectError::Eof {
+                        expectation,
+                        screen,
+                    }
+                } else {
+                    ExpectError::Timeout {
+                        expectation,
+                        screen,
+                    }
+                });
+            }
+
+            let mut wait = deadline - now;
+            if let Expectation::Stable(duration) = expectation {
+                wait = wait.min(
+                    (changed + *duration).saturating_duration_since(now),
+                );
+            }
+            eof = !self.wait_for_output(wait)?;
+            if !self.screen().same_visible(&prev) {
+                prev = self.screen().clone();
+                changed = std::time::Instant::now();
+            }
+        }
+    }
+}
+
+/// A [`Parser`](crate::Parser) which processes output received from a
+/// channel, so that output written by another thread can be waited on with
+/// [`Expect::expect`].
+///
+/// The output ends once every sender for the channel has been dropped.
+pub struct ChannelParser<CB: crate::callbacks::Callbacks = ()> {
+    parser: crate::Parser<CB>,
+    receiver: std::sync::mpsc::Receiver<Vec<u8>>,
+}
+
+impl<CB: crate::callbacks::Callbacks> ChannelParser<CB> {
+    /// Creates a new `ChannelParser` which processes the output received
+    /// from `receiver` with `parser`.
+    #[must_use]
+    pub fn new(
+        parser: crate::Parser<CB>,
+        receiver: std::sync::mpsc::Receiver<Vec<u8>>,
+    ) -> Self {
+        Self { parser, receiver }
+    }
+
+    /// Returns a reference to the [`Parser`](crate::Parser) which processes
+    /// the output.
+    #[must_use]
+    pub fn parser(&self) -> &crate::Parser<CB> {
+        &self.parser
+    }
+
+    /// Returns the [`Parser`](crate::Parser) which processes the output.
+    #[must_use]
+    pub fn into_parser(self) -> crate::Parser<CB> {
+        self.parser
+    }
+}
+
+impl<CB: crate::callbacks::Callbacks> Expect for ChannelParser<CB> {
+    fn screen(&self) -> &crate::Screen {
+        self.parser.screen()
+    }
+
+    fn wait_for_output(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> std::io::Result<bool> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(bytes) => self.parser.process(&bytes),
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                return Ok(true);
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                return Ok(false);
+            }
+        }
+        // process anything else which has already arrived too
+        while let Ok(bytes) = self.receiver.try_recv() {
+            self.parser.process(&bytes);
+        }
+        Ok(true)
+    }
+}
+
+// renders the screen inside a border, with the cursor position
+fn dump(screen: &crate::Screen) -> String {
+    let (rows, cols) = screen.size();
+    let (cursor_row, cursor_col) = screen.cursor_position();
+    let border = format!("+{}+\n", "-".repeat(usize::from(cols)));
+    let mut dump = format!(
+        "screen ({rows}x{cols}, cursor at ({cursor_row}, {cursor_col})):\n"
+    );
+    dump.push_str(&border);
+    for row in screen.rows(0, cols) {
+        let width = unicode_width::UnicodeWidthStr::width(row.as_str());
+        dump.push('|');
+        dump.push_str(&row);
+        dump.push_str(&" ".repeat(usize::from(cols).saturating_sub(width)));
+        dump.push_str("|\n");
+    }
+    dump.push_str(&border);
+    dump
+}
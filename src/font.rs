@@ -0,0 +1,983 @@
+// glyph data for the 8x13 "fixed" font from the xorg misc-misc font
+// collection, which is in the public domain. each glyph is stored as one
+// byte per row of pixels, with the most significant bit as the leftmost
+// pixel.
+
+pub const GLYPH_WIDTH: u16 = 8;
+pub const GLYPH_HEIGHT: u16 = 13;
+pub const GLYPH_UNDERLINE: u16 = 12;
+
+// drawn for characters that aren't included in the font
+const FALLBACK: [u8; 13] = [
+    0x00, 0x00, 0x7e, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x7e, 0x00,
+    0x00,
+];
+
+pub fn glyph(c: char) -> &'static [u8; 13] {
+    let idx = match u32::from(c) {
+        c @ 0x20..=0x7e => c - 0x20,
+        c @ 0xa0..=0xff => c - 0xa0 + 0x5f,
+        _ => return &FALLBACK,
+    };
+    // idx is always less than the number of glyphs, because of the ranges
+    // matched above
+    &GLYPHS[usize::try_from(idx).unwrap()]
+}
+
+const GLYPHS: [[u8; 13]; 191] = [
+    // ' '
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // '!'
+    [
+        0x00, 0x00, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x10,
+        0x00, 0x00,
+    ],
+    // '"'
+    [
+        0x00, 0x00, 0x24, 0x24, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // '#'
+    [
+        0x00, 0x00, 0x00, 0x24, 0x24, 0x7e, 0x24, 0x7e, 0x24, 0x24, 0x00,
+        0x00, 0x00,
+    ],
+    // '$'
+    [
+        0x00, 0x00, 0x10, 0x3c, 0x50, 0x50, 0x38, 0x14, 0x14, 0x78, 0x10,
+        0x00, 0x00,
+    ],
+    // '%'
+    [
+        0x00, 0x00, 0x22, 0x52, 0x24, 0x08, 0x08, 0x10, 0x24, 0x2a, 0x44,
+        0x00, 0x00,
+    ],
+    // '&'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x30, 0x48, 0x48, 0x30, 0x4a, 0x44, 0x3a,
+        0x00, 0x00,
+    ],
+    // "'"
+    [
+        0x00, 0x00, 0x10, 0x10, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // '('
+    [
+        0x00, 0x00, 0x04, 0x08, 0x08, 0x10, 0x10, 0x10, 0x08, 0x08, 0x04,
+        0x00, 0x00,
+    ],
+    // ')'
+    [
+        0x00, 0x00, 0x20, 0x10, 0x10, 0x08, 0x08, 0x08, 0x10, 0x10, 0x20,
+        0x00, 0x00,
+    ],
+    // '*'
+    [
+        0x00, 0x00, 0x24, 0x18, 0x7e, 0x18, 0x24, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // '+'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x10, 0x10, 0x7c, 0x10, 0x10, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // ','
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x38, 0x30,
+        0x40, 0x00,
+    ],
+    // '-'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // '.'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x38,
+        0x10, 0x00,
+    ],
+    // '/'
+    [
+        0x00, 0x00, 0x02, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x80,
+        0x00, 0x00,
+    ],
+    // '0'
+    [
+        0x00, 0x00, 0x18, 0x24, 0x42, 0x42, 0x42, 0x42, 0x42, 0x24, 0x18,
+        0x00, 0x00,
+    ],
+    // '1'
+    [
+        0x00, 0x00, 0x10, 0x30, 0x50, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // '2'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x42, 0x02, 0x04, 0x18, 0x20, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // '3'
+    [
+        0x00, 0x00, 0x7e, 0x02, 0x04, 0x08, 0x1c, 0x02, 0x02, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // '4'
+    [
+        0x00, 0x00, 0x04, 0x0c, 0x14, 0x24, 0x44, 0x44, 0x7e, 0x04, 0x04,
+        0x00, 0x00,
+    ],
+    // '5'
+    [
+        0x00, 0x00, 0x7e, 0x40, 0x40, 0x5c, 0x62, 0x02, 0x02, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // '6'
+    [
+        0x00, 0x00, 0x1c, 0x20, 0x40, 0x40, 0x5c, 0x62, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // '7'
+    [
+        0x00, 0x00, 0x7e, 0x02, 0x04, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20,
+        0x00, 0x00,
+    ],
+    // '8'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x3c, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // '9'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x42, 0x46, 0x3a, 0x02, 0x02, 0x04, 0x38,
+        0x00, 0x00,
+    ],
+    // ':'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x10, 0x38, 0x10, 0x00, 0x00, 0x10, 0x38,
+        0x10, 0x00,
+    ],
+    // ';'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x10, 0x38, 0x10, 0x00, 0x00, 0x38, 0x30,
+        0x40, 0x00,
+    ],
+    // '<'
+    [
+        0x00, 0x00, 0x02, 0x04, 0x08, 0x10, 0x20, 0x10, 0x08, 0x04, 0x02,
+        0x00, 0x00,
+    ],
+    // '='
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x7e, 0x00, 0x00, 0x7e, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // '>'
+    [
+        0x00, 0x00, 0x40, 0x20, 0x10, 0x08, 0x04, 0x08, 0x10, 0x20, 0x40,
+        0x00, 0x00,
+    ],
+    // '?'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x42, 0x02, 0x04, 0x08, 0x08, 0x00, 0x08,
+        0x00, 0x00,
+    ],
+    // '@'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x42, 0x4e, 0x52, 0x56, 0x4a, 0x40, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'A'
+    [
+        0x00, 0x00, 0x18, 0x24, 0x42, 0x42, 0x42, 0x7e, 0x42, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // 'B'
+    [
+        0x00, 0x00, 0x78, 0x44, 0x42, 0x44, 0x78, 0x44, 0x42, 0x44, 0x78,
+        0x00, 0x00,
+    ],
+    // 'C'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x40, 0x40, 0x40, 0x40, 0x40, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'D'
+    [
+        0x00, 0x00, 0x78, 0x44, 0x42, 0x42, 0x42, 0x42, 0x42, 0x44, 0x78,
+        0x00, 0x00,
+    ],
+    // 'E'
+    [
+        0x00, 0x00, 0x7e, 0x40, 0x40, 0x40, 0x78, 0x40, 0x40, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // 'F'
+    [
+        0x00, 0x00, 0x7e, 0x40, 0x40, 0x40, 0x78, 0x40, 0x40, 0x40, 0x40,
+        0x00, 0x00,
+    ],
+    // 'G'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x40, 0x40, 0x40, 0x4e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // 'H'
+    [
+        0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x7e, 0x42, 0x42, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // 'I'
+    [
+        0x00, 0x00, 0x7c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // 'J'
+    [
+        0x00, 0x00, 0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x44, 0x38,
+        0x00, 0x00,
+    ],
+    // 'K'
+    [
+        0x00, 0x00, 0x42, 0x44, 0x48, 0x50, 0x60, 0x50, 0x48, 0x44, 0x42,
+        0x00, 0x00,
+    ],
+    // 'L'
+    [
+        0x00, 0x00, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // 'M'
+    [
+        0x00, 0x00, 0x82, 0x82, 0xc6, 0xaa, 0x92, 0x92, 0x82, 0x82, 0x82,
+        0x00, 0x00,
+    ],
+    // 'N'
+    [
+        0x00, 0x00, 0x42, 0x42, 0x62, 0x52, 0x4a, 0x46, 0x42, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // 'O'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'P'
+    [
+        0x00, 0x00, 0x7c, 0x42, 0x42, 0x42, 0x7c, 0x40, 0x40, 0x40, 0x40,
+        0x00, 0x00,
+    ],
+    // 'Q'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x42, 0x52, 0x4a, 0x3c,
+        0x02, 0x00,
+    ],
+    // 'R'
+    [
+        0x00, 0x00, 0x7c, 0x42, 0x42, 0x42, 0x7c, 0x50, 0x48, 0x44, 0x42,
+        0x00, 0x00,
+    ],
+    // 'S'
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x40, 0x40, 0x3c, 0x02, 0x02, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'T'
+    [
+        0x00, 0x00, 0xfe, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+        0x00, 0x00,
+    ],
+    // 'U'
+    [
+        0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'V'
+    [
+        0x00, 0x00, 0x82, 0x82, 0x44, 0x44, 0x44, 0x28, 0x28, 0x28, 0x10,
+        0x00, 0x00,
+    ],
+    // 'W'
+    [
+        0x00, 0x00, 0x82, 0x82, 0x82, 0x82, 0x92, 0x92, 0x92, 0xaa, 0x44,
+        0x00, 0x00,
+    ],
+    // 'X'
+    [
+        0x00, 0x00, 0x82, 0x82, 0x44, 0x28, 0x10, 0x28, 0x44, 0x82, 0x82,
+        0x00, 0x00,
+    ],
+    // 'Y'
+    [
+        0x00, 0x00, 0x82, 0x82, 0x44, 0x28, 0x10, 0x10, 0x10, 0x10, 0x10,
+        0x00, 0x00,
+    ],
+    // 'Z'
+    [
+        0x00, 0x00, 0x7e, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // '['
+    [
+        0x00, 0x00, 0x3c, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x3c,
+        0x00, 0x00,
+    ],
+    // '\\'
+    [
+        0x00, 0x00, 0x80, 0x80, 0x40, 0x20, 0x10, 0x08, 0x04, 0x02, 0x02,
+        0x00, 0x00,
+    ],
+    // ']'
+    [
+        0x00, 0x00, 0x78, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x78,
+        0x00, 0x00,
+    ],
+    // '^'
+    [
+        0x00, 0x00, 0x10, 0x28, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // '_'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xfe, 0x00,
+    ],
+    // '`'
+    [
+        0x00, 0x10, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // 'a'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x02, 0x3e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // 'b'
+    [
+        0x00, 0x00, 0x40, 0x40, 0x40, 0x5c, 0x62, 0x42, 0x42, 0x62, 0x5c,
+        0x00, 0x00,
+    ],
+    // 'c'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x42, 0x40, 0x40, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'd'
+    [
+        0x00, 0x00, 0x02, 0x02, 0x02, 0x3a, 0x46, 0x42, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // 'e'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x42, 0x7e, 0x40, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'f'
+    [
+        0x00, 0x00, 0x1c, 0x22, 0x20, 0x20, 0x7c, 0x20, 0x20, 0x20, 0x20,
+        0x00, 0x00,
+    ],
+    // 'g'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3a, 0x44, 0x44, 0x38, 0x40, 0x3c,
+        0x42, 0x3c,
+    ],
+    // 'h'
+    [
+        0x00, 0x00, 0x40, 0x40, 0x40, 0x5c, 0x62, 0x42, 0x42, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // 'i'
+    [
+        0x00, 0x00, 0x00, 0x10, 0x00, 0x30, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // 'j'
+    [
+        0x00, 0x00, 0x00, 0x04, 0x00, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x44,
+        0x44, 0x38,
+    ],
+    // 'k'
+    [
+        0x00, 0x00, 0x40, 0x40, 0x40, 0x44, 0x48, 0x70, 0x48, 0x44, 0x42,
+        0x00, 0x00,
+    ],
+    // 'l'
+    [
+        0x00, 0x00, 0x30, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // 'm'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0xec, 0x92, 0x92, 0x92, 0x92, 0x82,
+        0x00, 0x00,
+    ],
+    // 'n'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x5c, 0x62, 0x42, 0x42, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // 'o'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 'p'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x5c, 0x62, 0x42, 0x62, 0x5c, 0x40,
+        0x40, 0x40,
+    ],
+    // 'q'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3a, 0x46, 0x42, 0x46, 0x3a, 0x02,
+        0x02, 0x02,
+    ],
+    // 'r'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x5c, 0x22, 0x20, 0x20, 0x20, 0x20,
+        0x00, 0x00,
+    ],
+    // 's'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x42, 0x30, 0x0c, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // 't'
+    [
+        0x00, 0x00, 0x00, 0x20, 0x20, 0x7c, 0x20, 0x20, 0x20, 0x22, 0x1c,
+        0x00, 0x00,
+    ],
+    // 'u'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x3a,
+        0x00, 0x00,
+    ],
+    // 'v'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x28, 0x28, 0x10,
+        0x00, 0x00,
+    ],
+    // 'w'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x82, 0x82, 0x92, 0x92, 0xaa, 0x44,
+        0x00, 0x00,
+    ],
+    // 'x'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x24, 0x18, 0x18, 0x24, 0x42,
+        0x00, 0x00,
+    ],
+    // 'y'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x42, 0x46, 0x3a, 0x02,
+        0x42, 0x3c,
+    ],
+    // 'z'
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x7e, 0x04, 0x08, 0x10, 0x20, 0x7e,
+        0x00, 0x00,
+    ],
+    // '{'
+    [
+        0x00, 0x00, 0x0e, 0x10, 0x10, 0x08, 0x30, 0x08, 0x10, 0x10, 0x0e,
+        0x00, 0x00,
+    ],
+    // '|'
+    [
+        0x00, 0x00, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+        0x00, 0x00,
+    ],
+    // '}'
+    [
+        0x00, 0x00, 0x70, 0x08, 0x08, 0x10, 0x0c, 0x10, 0x08, 0x08, 0x70,
+        0x00, 0x00,
+    ],
+    // '~'
+    [
+        0x00, 0x00, 0x24, 0x54, 0x48, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00A0
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00A1
+    [
+        0x00, 0x00, 0x10, 0x00, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+        0x00, 0x00,
+    ],
+    // U+00A2
+    [
+        0x00, 0x00, 0x10, 0x38, 0x54, 0x50, 0x50, 0x54, 0x38, 0x10, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00A3
+    [
+        0x00, 0x00, 0x1c, 0x22, 0x20, 0x70, 0x20, 0x20, 0x20, 0x62, 0xdc,
+        0x00, 0x00,
+    ],
+    // U+00A4
+    [
+        0x00, 0x00, 0x00, 0x00, 0x42, 0x3c, 0x24, 0x24, 0x3c, 0x42, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00A5
+    [
+        0x00, 0x00, 0x82, 0x82, 0x44, 0x28, 0x7c, 0x10, 0x7c, 0x10, 0x10,
+        0x00, 0x00,
+    ],
+    // U+00A6
+    [
+        0x00, 0x00, 0x10, 0x10, 0x10, 0x10, 0x00, 0x10, 0x10, 0x10, 0x10,
+        0x00, 0x00,
+    ],
+    // U+00A7
+    [
+        0x00, 0x18, 0x24, 0x20, 0x18, 0x24, 0x24, 0x18, 0x04, 0x24, 0x18,
+        0x00, 0x00,
+    ],
+    // U+00A8
+    [
+        0x00, 0x24, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00A9
+    [
+        0x00, 0x38, 0x44, 0x92, 0xaa, 0xa2, 0xaa, 0x92, 0x44, 0x38, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00AA
+    [
+        0x00, 0x00, 0x38, 0x04, 0x3c, 0x44, 0x3c, 0x00, 0x7c, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00AB
+    [
+        0x00, 0x00, 0x00, 0x12, 0x24, 0x48, 0x90, 0x48, 0x24, 0x12, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00AC
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7e, 0x02, 0x02, 0x02, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00AD
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00AE
+    [
+        0x00, 0x38, 0x44, 0x92, 0xaa, 0xaa, 0xb2, 0xaa, 0x44, 0x38, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00AF
+    [
+        0x00, 0x00, 0x7e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00B0
+    [
+        0x00, 0x00, 0x18, 0x24, 0x24, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00B1
+    [
+        0x00, 0x00, 0x00, 0x10, 0x10, 0x7c, 0x10, 0x10, 0x00, 0x7c, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00B2
+    [
+        0x00, 0x30, 0x48, 0x08, 0x30, 0x40, 0x78, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00B3
+    [
+        0x00, 0x30, 0x48, 0x10, 0x08, 0x48, 0x30, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00B4
+    [
+        0x00, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00B5
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x66, 0x5a,
+        0x40, 0x00,
+    ],
+    // U+00B6
+    [
+        0x00, 0x00, 0x3e, 0x74, 0x74, 0x74, 0x34, 0x14, 0x14, 0x14, 0x14,
+        0x00, 0x00,
+    ],
+    // U+00B7
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00B8
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x18,
+    ],
+    // U+00B9
+    [
+        0x00, 0x20, 0x60, 0x20, 0x20, 0x20, 0x70, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00BA
+    [
+        0x00, 0x00, 0x30, 0x48, 0x48, 0x30, 0x00, 0x78, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00BB
+    [
+        0x00, 0x00, 0x00, 0x90, 0x48, 0x24, 0x12, 0x24, 0x48, 0x90, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00BC
+    [
+        0x00, 0x40, 0xc0, 0x40, 0x40, 0x42, 0xe6, 0x0a, 0x12, 0x1a, 0x06,
+        0x00, 0x00,
+    ],
+    // U+00BD
+    [
+        0x00, 0x40, 0xc0, 0x40, 0x40, 0x4c, 0xf2, 0x02, 0x0c, 0x10, 0x1e,
+        0x00, 0x00,
+    ],
+    // U+00BE
+    [
+        0x00, 0x60, 0x90, 0x20, 0x10, 0x92, 0x66, 0x0a, 0x12, 0x1a, 0x06,
+        0x00, 0x00,
+    ],
+    // U+00BF
+    [
+        0x00, 0x00, 0x10, 0x00, 0x10, 0x10, 0x20, 0x40, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00C0
+    [
+        0x00, 0x10, 0x08, 0x00, 0x18, 0x24, 0x42, 0x42, 0x7e, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // U+00C1
+    [
+        0x00, 0x08, 0x10, 0x00, 0x18, 0x24, 0x42, 0x42, 0x7e, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // U+00C2
+    [
+        0x00, 0x18, 0x24, 0x00, 0x18, 0x24, 0x42, 0x42, 0x7e, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // U+00C3
+    [
+        0x00, 0x32, 0x4c, 0x00, 0x18, 0x24, 0x42, 0x42, 0x7e, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // U+00C4
+    [
+        0x00, 0x24, 0x24, 0x00, 0x18, 0x24, 0x42, 0x42, 0x7e, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // U+00C5
+    [
+        0x00, 0x18, 0x24, 0x18, 0x18, 0x24, 0x42, 0x42, 0x7e, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // U+00C6
+    [
+        0x00, 0x00, 0x6e, 0x90, 0x90, 0x90, 0x9c, 0xf0, 0x90, 0x90, 0x9e,
+        0x00, 0x00,
+    ],
+    // U+00C7
+    [
+        0x00, 0x00, 0x3c, 0x42, 0x40, 0x40, 0x40, 0x40, 0x40, 0x42, 0x3c,
+        0x08, 0x10,
+    ],
+    // U+00C8
+    [
+        0x00, 0x10, 0x08, 0x00, 0x7e, 0x40, 0x40, 0x78, 0x40, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // U+00C9
+    [
+        0x00, 0x08, 0x10, 0x00, 0x7e, 0x40, 0x40, 0x78, 0x40, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // U+00CA
+    [
+        0x00, 0x18, 0x24, 0x00, 0x7e, 0x40, 0x40, 0x78, 0x40, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // U+00CB
+    [
+        0x00, 0x24, 0x24, 0x00, 0x7e, 0x40, 0x40, 0x78, 0x40, 0x40, 0x7e,
+        0x00, 0x00,
+    ],
+    // U+00CC
+    [
+        0x00, 0x20, 0x10, 0x00, 0x7c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00CD
+    [
+        0x00, 0x08, 0x10, 0x00, 0x7c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00CE
+    [
+        0x00, 0x18, 0x24, 0x00, 0x7c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00CF
+    [
+        0x00, 0x44, 0x44, 0x00, 0x7c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00D0
+    [
+        0x00, 0x00, 0x78, 0x44, 0x42, 0x42, 0xe2, 0x42, 0x42, 0x44, 0x78,
+        0x00, 0x00,
+    ],
+    // U+00D1
+    [
+        0x00, 0x64, 0x98, 0x00, 0x82, 0xc2, 0xa2, 0x92, 0x8a, 0x86, 0x82,
+        0x00, 0x00,
+    ],
+    // U+00D2
+    [
+        0x00, 0x20, 0x10, 0x00, 0x7c, 0x82, 0x82, 0x82, 0x82, 0x82, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00D3
+    [
+        0x00, 0x08, 0x10, 0x00, 0x7c, 0x82, 0x82, 0x82, 0x82, 0x82, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00D4
+    [
+        0x00, 0x18, 0x24, 0x00, 0x7c, 0x82, 0x82, 0x82, 0x82, 0x82, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00D5
+    [
+        0x00, 0x64, 0x98, 0x00, 0x7c, 0x82, 0x82, 0x82, 0x82, 0x82, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00D6
+    [
+        0x00, 0x44, 0x44, 0x00, 0x7c, 0x82, 0x82, 0x82, 0x82, 0x82, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00D7
+    [
+        0x00, 0x00, 0x00, 0x00, 0x42, 0x24, 0x18, 0x18, 0x24, 0x42, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00D8
+    [
+        0x00, 0x02, 0x3c, 0x46, 0x4a, 0x4a, 0x52, 0x52, 0x52, 0x62, 0x3c,
+        0x40, 0x00,
+    ],
+    // U+00D9
+    [
+        0x00, 0x20, 0x10, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00DA
+    [
+        0x00, 0x08, 0x10, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00DB
+    [
+        0x00, 0x18, 0x24, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00DC
+    [
+        0x00, 0x24, 0x24, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00DD
+    [
+        0x00, 0x08, 0x10, 0x00, 0x44, 0x44, 0x28, 0x10, 0x10, 0x10, 0x10,
+        0x00, 0x00,
+    ],
+    // U+00DE
+    [
+        0x00, 0x00, 0x40, 0x7c, 0x42, 0x42, 0x42, 0x7c, 0x40, 0x40, 0x40,
+        0x00, 0x00,
+    ],
+    // U+00DF
+    [
+        0x00, 0x00, 0x38, 0x44, 0x44, 0x48, 0x50, 0x4c, 0x42, 0x42, 0x5c,
+        0x00, 0x00,
+    ],
+    // U+00E0
+    [
+        0x00, 0x00, 0x10, 0x08, 0x00, 0x3c, 0x02, 0x3e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00E1
+    [
+        0x00, 0x00, 0x04, 0x08, 0x00, 0x3c, 0x02, 0x3e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00E2
+    [
+        0x00, 0x00, 0x18, 0x24, 0x00, 0x3c, 0x02, 0x3e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00E3
+    [
+        0x00, 0x00, 0x32, 0x4c, 0x00, 0x3c, 0x02, 0x3e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00E4
+    [
+        0x00, 0x00, 0x24, 0x24, 0x00, 0x3c, 0x02, 0x3e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00E5
+    [
+        0x00, 0x18, 0x24, 0x18, 0x00, 0x3c, 0x02, 0x3e, 0x42, 0x46, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00E6
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x6c, 0x12, 0x7c, 0x90, 0x92, 0x6c,
+        0x00, 0x00,
+    ],
+    // U+00E7
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x42, 0x40, 0x40, 0x42, 0x3c,
+        0x08, 0x10,
+    ],
+    // U+00E8
+    [
+        0x00, 0x00, 0x10, 0x08, 0x00, 0x3c, 0x42, 0x7e, 0x40, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00E9
+    [
+        0x00, 0x00, 0x08, 0x10, 0x00, 0x3c, 0x42, 0x7e, 0x40, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00EA
+    [
+        0x00, 0x00, 0x18, 0x24, 0x00, 0x3c, 0x42, 0x7e, 0x40, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00EB
+    [
+        0x00, 0x00, 0x24, 0x24, 0x00, 0x3c, 0x42, 0x7e, 0x40, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00EC
+    [
+        0x00, 0x00, 0x20, 0x10, 0x00, 0x30, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00ED
+    [
+        0x00, 0x00, 0x10, 0x20, 0x00, 0x30, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00EE
+    [
+        0x00, 0x00, 0x30, 0x48, 0x00, 0x30, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00EF
+    [
+        0x00, 0x00, 0x48, 0x48, 0x00, 0x30, 0x10, 0x10, 0x10, 0x10, 0x7c,
+        0x00, 0x00,
+    ],
+    // U+00F0
+    [
+        0x00, 0x24, 0x18, 0x28, 0x04, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00F1
+    [
+        0x00, 0x00, 0x32, 0x4c, 0x00, 0x5c, 0x62, 0x42, 0x42, 0x42, 0x42,
+        0x00, 0x00,
+    ],
+    // U+00F2
+    [
+        0x00, 0x00, 0x20, 0x10, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00F3
+    [
+        0x00, 0x00, 0x08, 0x10, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00F4
+    [
+        0x00, 0x00, 0x18, 0x24, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00F5
+    [
+        0x00, 0x00, 0x32, 0x4c, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00F6
+    [
+        0x00, 0x00, 0x24, 0x24, 0x00, 0x3c, 0x42, 0x42, 0x42, 0x42, 0x3c,
+        0x00, 0x00,
+    ],
+    // U+00F7
+    [
+        0x00, 0x00, 0x00, 0x10, 0x10, 0x00, 0x7c, 0x00, 0x10, 0x10, 0x00,
+        0x00, 0x00,
+    ],
+    // U+00F8
+    [
+        0x00, 0x00, 0x00, 0x00, 0x02, 0x3c, 0x46, 0x4a, 0x52, 0x62, 0x3c,
+        0x40, 0x00,
+    ],
+    // U+00F9
+    [
+        0x00, 0x00, 0x20, 0x10, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00FA
+    [
+        0x00, 0x00, 0x08, 0x10, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00FB
+    [
+        0x00, 0x00, 0x18, 0x24, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00FC
+    [
+        0x00, 0x00, 0x28, 0x28, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x3a,
+        0x00, 0x00,
+    ],
+    // U+00FD
+    [
+        0x00, 0x00, 0x08, 0x10, 0x00, 0x42, 0x42, 0x42, 0x46, 0x3a, 0x02,
+        0x42, 0x3c,
+    ],
+    // U+00FE
+    [
+        0x00, 0x00, 0x00, 0x40, 0x40, 0x5c, 0x62, 0x42, 0x42, 0x62, 0x5c,
+        0x40, 0x40,
+    ],
+    // U+00FF
+    [
+        0x00, 0x00, 0x24, 0x24, 0x00, 0x42, 0x42, 0x42, 0x46, 0x3a, 0x02,
+        0x42, 0x3c,
+    ],
+];
@@ -1,18 +1,41 @@
 use crate::term::BufWrite as _;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Grid {
     size: Size,
     pos: Pos,
     saved_pos: Pos,
-    rows: Vec<crate::row::Row>,
+    // rows are shared between clones of the grid, and only copied when
+    // they are modified
+    rows: std::collections::VecDeque<std::sync::Arc<crate::row::Row>>,
     scroll_top: u16,
     scroll_bottom: u16,
     origin_mode: bool,
     saved_origin_mode: bool,
-    scrollback: std::collections::VecDeque<crate::row::Row>,
+    scrollback: crate::scrollback::Scrollback,
     scrollback_len: usize,
     scrollback_offset: usize,
+    scrollback_max_bytes: Option<usize>,
+    // this and the fields below are recalculated after deserializing (see
+    // restored)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scrollback_bytes: usize,
+    // expanded copies of the scrollback rows which are currently visible
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scrollback_view: Vec<(
+        std::sync::Arc<crate::compact::CompactRow>,
+        std::sync::Arc<crate::row::Row>,
+    )>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scrolled: i32,
+    // whether each row has changed since the damage was last cleared. this
+    // is kept outside of the rows so that tracking it doesn't require
+    // copying rows which are shared with another grid.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dirty: std::collections::VecDeque<bool>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    damaged: bool,
 }
 
 impl Grid {
@@ -21,14 +44,20 @@ impl Grid {
             size,
             pos: Pos::default(),
             saved_pos: Pos::default(),
-            rows: vec![],
+            rows: std::collections::VecDeque::new(),
             scroll_top: 0,
             scroll_bottom: size.rows - 1,
             origin_mode: false,
             saved_origin_mode: false,
-            scrollback: std::collections::VecDeque::new(),
+            scrollback: crate::scrollback::Scrollback::default(),
             scrollback_len,
             scrollback_offset: 0,
+            scrollback_max_bytes: None,
+            scrollback_bytes: 0,
+            scrollback_view: vec![],
+            scrolled: 0,
+            dirty: std::collections::VecDeque::new(),
+            damaged: true,
         }
     }
 
@@ -36,15 +65,16 @@ impl Grid {
         if self.rows.is_empty() {
             self.rows.extend(
                 std::iter::repeat_with(|| {
-                    crate::row::Row::new(self.size.cols)
+                    std::sync::Arc::new(crate::row::Row::new(self.size.cols))
                 })
                 .take(usize::from(self.size.rows)),
             );
+            self.dirty.resize(self.rows.len(), true);
         }
     }
 
-    fn new_row(&self) -> crate::row::Row {
-        crate::row::Row::new(self.size.cols)
+    fn new_row(&self) -> std::sync::Arc<crate::row::Row> {
+        std::sync::Arc::new(crate::row::Row::new(self.size.cols))
     }
 
     pub fn clear(&mut self) {
@@ -64,9 +94,14 @@ impl Grid {
     }
 
     pub fn set_size(&mut self, size: Size) {
+        self.damaged = true;
+
         if size.cols != self.size.cols {
-            for row in &mut self.rows {
-                row.wrap(false);
+            for (row, dirty) in self.rows.iter_mut().zip(&mut self.dirty) {
+                if row.wrapped() {
+                    std::sync::Arc::make_mut(row).wrap(false);
+                    *dirty = true;
+                }
             }
         }
 
@@ -75,10 +110,12 @@ impl Grid {
         }
 
         self.size = size;
-        for row in &mut self.rows {
+        for row in self.drawing_rows_mut() {
             row.resize(size.cols, crate::Cell::new());
         }
         self.rows.resize(usize::from(size.rows), self.new_row());
+        self.dirty.resize(self.rows.len(), true);
+        self.update_scrollback_view();
 
         if self.scroll_bottom >= size.rows {
             self.scroll_bottom = size.rows - 1;
@@ -103,6 +140,20 @@ impl Grid {
         self.pos
     }
 
+    // the cursor position as reported to the application, which is relative
+    // to the scrolling region in origin mode. the cursor sits past the last
+    // column while a wrap is pending, but it is reported as being in the
+    // last column.
+    #[cfg(feature = "pty")]
+    pub fn reported_pos(&self) -> Pos {
+        let mut pos = self.pos;
+        if self.origin_mode {
+            pos.row = pos.row.saturating_sub(self.scroll_top);
+        }
+        pos.col = pos.col.min(self.size.cols - 1);
+        pos
+    }
+
     pub fn set_pos(&mut self, mut pos: Pos) {
         if self.origin_mode {
             pos.row = pos.row.saturating_add(self.scroll_top);
@@ -113,6 +164,12 @@ impl Grid {
         self.col_clamp();
     }
 
+    // sets the cursor position without clamping or adjusting for origin
+    // mode, for restoring a position previously returned by pos()
+    pub fn restore_pos(&mut self, pos: Pos) {
+        self.pos = pos;
+    }
+
     pub fn save_cursor(&mut self) {
         self.saved_pos = self.pos;
         self.saved_origin_mode = self.origin_mode;
@@ -124,48 +181,72 @@ impl Grid {
     }
 
     pub fn visible_rows(&self) -> impl Iterator<Item = &crate::row::Row> {
-        let scrollback_len = self.scrollback.len();
         let rows_len = self.rows.len();
-        self.scrollback
+        self.scrollback_view
             .iter()
-            .skip(scrollback_len - self.scrollback_offset)
+            .map(|(_, row)| row)
             // when scrollback_offset > rows_len (e.g. rows = 3,
-            // scrollback_len = 10, offset = 9) the skip(10 - 9)
-            // will take 9 rows instead of 3. we need to set
-            // the upper bound to rows_len (e.g. 3)
-            .take(rows_len)
-            // same for rows_len - scrollback_offset (e.g. 3 - 9).
-            // it'll panic with overflow. we have to saturate the subtraction.
+            // scrollback_len = 10, offset = 9), the scrollback view only
+            // contains rows_len rows, and so no rows from the grid itself
+            // are visible. we have to saturate the subtraction.
             .chain(
                 self.rows
                     .iter()
                     .take(rows_len.saturating_sub(self.scrollback_offset)),
             )
+            .map(std::convert::AsRef::as_ref)
     }
 
-    pub fn drawing_rows(&self) -> impl Iterator<Item = &crate::row::Row> {
-        self.rows.iter()
+    pub fn all_rows(
+        &self,
+    ) -> impl Iterator<Item = std::borrow::Cow<'_, crate::row::Row>> {
+        self.scrollback
+            .iter()
+            .map(|row| std::borrow::Cow::Owned(row.expand()))
+            .chain(
+                self.rows
+                    .iter()
+                    .map(|row| std::borrow::Cow::Borrowed(row.as_ref())),
+            )
     }
 
+    // this copies every row that is currently shared with another grid, so
+    // it should only be used when every row is actually being modified
     pub fn drawing_rows_mut(
         &mut self,
     ) -> impl Iterator<Item = &mut crate::row::Row> {
-        self.rows.iter_mut()
+        self.dirty.iter_mut().for_each(|dirty| *dirty = true);
+        self.rows.iter_mut().map(std::sync::Arc::make_mut)
     }
 
     pub fn visible_row(&self, row: u16) -> Option<&crate::row::Row> {
-        self.visible_rows().nth(usize::from(row))
+        let row = usize::from(row);
+        if row >= self.rows.len() {
+            return None;
+        }
+        if row < self.scrollback_offset {
+            self.scrollback_view.get(row).map(|(_, row)| row)
+        } else {
+            self.rows.get(row - self.scrollback_offset)
+        }
+        .map(std::convert::AsRef::as_ref)
     }
 
     pub fn drawing_row(&self, row: u16) -> Option<&crate::row::Row> {
-        self.drawing_rows().nth(usize::from(row))
+        self.rows
+            .get(usize::from(row))
+            .map(std::convert::AsRef::as_ref)
     }
 
     pub fn drawing_row_mut(
         &mut self,
         row: u16,
     ) -> Option<&mut crate::row::Row> {
-        self.drawing_rows_mut().nth(usize::from(row))
+        let row = usize::from(row);
+        if let Some(dirty) = self.dirty.get_mut(row) {
+            *dirty = true;
+        }
+        self.rows.get_mut(row).map(std::sync::Arc::make_mut)
     }
 
     pub fn current_row_mut(&mut self) -> &mut crate::row::Row {
@@ -196,7 +277,260 @@ impl Grid {
     }
 
     pub fn set_scrollback(&mut self, rows: usize) {
-        self.scrollback_offset = rows.min(self.scrollback.len());
+        let offset = rows.min(self.scrollback.len());
+        if offset != self.scrollback_offset {
+            self.damaged = true;
+        }
+        self.scrollback_offset = offset;
+        self.update_scrollback_view();
+    }
+
+    pub fn scrollback_max_bytes(&self) -> Option<usize> {
+        self.scrollback_max_bytes
+    }
+
+    pub fn set_scrollback_max_bytes(&mut self, max_bytes: Option<usize>) {
+        self.scrollback_max_bytes = max_bytes;
+        self.trim_scrollback();
+        self.update_scrollback_view();
+    }
+
+    pub fn scrollback_bytes(&self) -> usize {
+        self.scrollback_bytes
+    }
+
+    fn trim_scrollback(&mut self) {
+        while self.scrollback.len() > self.scrollback_len
+            || self
+                .scrollback_max_bytes
+                .is_some_and(|max| self.scrollback_bytes > max)
+        {
+            let Some(row) = self.scrollback.pop_front() else {
+                break;
+            };
+            self.scrollback_bytes -= row.memory_usage();
+        }
+        if self.scrollback_offset > self.scrollback.len() {
+            self.scrollback_offset = self.scrollback.len();
+            self.damaged = true;
+        }
+    }
+
+    // deserializes a grid, checking that it is consistent enough to be used
+    // and recalculating the state that isn't serialized
+    #[cfg(feature = "serde")]
+    pub fn deserialize_checked<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let mut grid: Self = serde::Deserialize::deserialize(deserializer)?;
+        if !grid.is_consistent() {
+            return Err(serde::de::Error::custom("inconsistent grid state"));
+        }
+        grid.restored();
+        Ok(grid)
+    }
+
+    pub fn write_snapshot(&self, w: &mut crate::snapshot::Writer) {
+        for n in [
+            self.size.rows,
+            self.size.cols,
+            self.pos.row,
+            self.pos.col,
+            self.saved_pos.row,
+            self.saved_pos.col,
+            self.scroll_top,
+            self.scroll_bottom,
+        ] {
+            w.uint(n);
+        }
+        w.bool(self.origin_mode);
+        w.bool(self.saved_origin_mode);
+        w.usize(self.scrollback_len);
+        w.usize(self.scrollback_offset);
+        w.bool(self.scrollback_max_bytes.is_some());
+        w.usize(self.scrollback_max_bytes.unwrap_or(0));
+        // rows are stored in the same form as the scrollback, since that
+        // is much smaller for mostly empty rows
+        w.usize(self.rows.len());
+        for row in &self.rows {
+            crate::compact::CompactRow::new(row).write_snapshot(w);
+        }
+        w.usize(self.scrollback.len());
+        for row in self.scrollback.iter() {
+            row.write_snapshot(w);
+        }
+    }
+
+    pub fn read_snapshot(
+        r: &mut crate::snapshot::Reader<'_>,
+    ) -> Result<Self, crate::SnapshotError> {
+        let size = Size {
+            rows: r.u16()?,
+            cols: r.u16()?,
+        };
+        let pos = Pos {
+            row: r.u16()?,
+            col: r.u16()?,
+        };
+        let saved_pos = Pos {
+            row: r.u16()?,
+            col: r.u16()?,
+        };
+        let scroll_top = r.u16()?;
+        let scroll_bottom = r.u16()?;
+        let origin_mode = r.bool()?;
+        let saved_origin_mode = r.bool()?;
+        let scrollback_len = r.usize()?;
+        let scrollback_offset = r.usize()?;
+        let has_scrollback_max_bytes = r.bool()?;
+        let scrollback_max_bytes =
+            Some(r.usize()?).filter(|_| has_scrollback_max_bytes);
+
+        // limit the initial allocations, since the lengths may be corrupt
+        let rows_len = r.usize()?;
+        let mut rows = std::collections::VecDeque::with_capacity(
+            rows_len.min(usize::from(size.rows)),
+        );
+        for _ in 0..rows_len {
+            let row = crate::compact::CompactRow::read_snapshot(r)?;
+            rows.push_back(std::sync::Arc::new(row.expand()));
+        }
+        let scrollback_rows = r.usize()?;
+        let mut scrollback = crate::scrollback::Scrollback::default();
+        for _ in 0..scrollback_rows {
+            scrollback.push_back(std::sync::Arc::new(
+                crate::compact::CompactRow::read_snapshot(r)?,
+            ));
+        }
+
+        let mut grid = Self {
+            size,
+            pos,
+            saved_pos,
+            rows,
+            scroll_top,
+            scroll_bottom,
+            origin_mode,
+            saved_origin_mode,
+            scrollback,
+            scrollback_len,
+            scrollback_offset,
+            scrollback_max_bytes,
+            scrollback_bytes: 0,
+            scrollback_view: vec![],
+            scrolled: 0,
+            dirty: std::collections::VecDeque::new(),
+            damaged: true,
+        };
+        if !grid.is_consistent() {
+            return Err(crate::SnapshotError::Corrupt);
+        }
+        grid.restored();
+        Ok(grid)
+    }
+
+    // checks the invariants that the rest of the grid code relies on, for
+    // grids which were loaded from arbitrary data
+    fn is_consistent(&self) -> bool {
+        let size = self.size;
+        let valid_pos =
+            |pos: Pos| pos.row < size.rows && pos.col <= size.cols;
+        size.rows > 0
+            && size.cols > 0
+            // the alternate grid doesn't allocate its rows until it is used
+            && (self.rows.is_empty()
+                || self.rows.len() == usize::from(size.rows))
+            && self.rows.iter().all(|row| {
+                row.cells().len() == usize::from(size.cols)
+                    && row.wide_cells_valid()
+            })
+            && valid_pos(self.pos)
+            && valid_pos(self.saved_pos)
+            && self.scroll_top <= self.scroll_bottom
+            && self.scroll_bottom < size.rows
+            && self.scrollback.len() <= self.scrollback_len
+            && self.scrollback_offset <= self.scrollback.len()
+            && self.scrollback.iter().all(|row| row.is_valid())
+    }
+
+    // recalculates the state which isn't saved when a grid is serialized
+    fn restored(&mut self) {
+        self.scrollback_bytes =
+            self.scrollback.iter().map(|row| row.memory_usage()).sum();
+        self.dirty = std::iter::repeat(true).take(self.rows.len()).collect();
+        self.update_scrollback_view();
+        self.damaged = true;
+    }
+
+    // scrollback rows are only expanded back into full rows when they are
+    // visible. rows which were already visible are reused, since this is
+    // also called whenever new rows are added to the scrollback.
+    fn update_scrollback_view(&mut self) {
+        let count = self.scrollback_offset.min(self.rows.len());
+        if count == 0 {
+            self.scrollback_view.clear();
+            return;
+        }
+
+        let start = self.scrollback.len() - self.scrollback_offset;
+        let prev = std::mem::take(&mut self.scrollback_view);
+        self.scrollback_view = self
+            .scrollback
+            .range(start..start + count)
+            .map(|compact| {
+                let row = prev
+                    .iter()
+                    .find(|(prev_compact, _)| {
+                        std::sync::Arc::ptr_eq(prev_compact, compact)
+                    })
+                    .map_or_else(
+                        || std::sync::Arc::new(compact.expand()),
+                        |(_, row)| std::sync::Arc::clone(row),
+                    );
+                (std::sync::Arc::clone(compact), row)
+            })
+            .collect();
+    }
+
+    pub fn damage(&self) -> crate::Damage {
+        // rows in the scrollback aren't tracked, so if any of them are
+        // visible, the whole screen has to be redrawn
+        if self.damaged
+            || self.scrollback_offset > 0
+            || self.scrolled.unsigned_abs() >= u32::from(self.size.rows)
+        {
+            return crate::Damage::new(0, 0..self.size.rows);
+        }
+        crate::Damage::new(
+            self.scrolled,
+            (0..)
+                .zip(&self.dirty)
+                .filter_map(|(i, &dirty)| dirty.then_some(i)),
+        )
+    }
+
+    pub fn clear_damage(&mut self) {
+        self.dirty.iter_mut().for_each(|dirty| *dirty = false);
+        self.scrolled = 0;
+        self.damaged = false;
+    }
+
+    pub fn damage_all(&mut self) {
+        self.damaged = true;
+    }
+
+    // whether everything other than the contents of the rows is the same as
+    // in the other grid
+    pub fn same_state(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.pos == other.pos
+            && self.saved_pos == other.saved_pos
+            && self.scroll_top == other.scroll_top
+            && self.scroll_bottom == other.scroll_bottom
+            && self.origin_mode == other.origin_mode
+            && self.saved_origin_mode == other.saved_origin_mode
+            && self.scrollback.len() == other.scrollback.len()
+            && self.scrollback_offset == other.scrollback_offset
     }
 
     pub fn write_contents(&self, contents: &mut String) {
@@ -217,6 +551,7 @@ impl Grid {
     pub fn write_contents_formatted(
         &self,
         contents: &mut Vec<u8>,
+        color_depth: crate::ColorDepth,
     ) -> crate::attrs::Attrs {
         crate::term::ClearAttrs.write_buf(contents);
         crate::term::ClearScreen.write_buf(contents);
@@ -236,6 +571,7 @@ impl Grid {
                 wrapping,
                 Some(prev_pos),
                 Some(prev_attrs),
+                color_depth,
             );
             prev_pos = new_pos;
             prev_attrs = new_attrs;
@@ -246,6 +582,7 @@ impl Grid {
             contents,
             Some(prev_pos),
             Some(prev_attrs),
+            color_depth,
         );
 
         prev_attrs
@@ -255,14 +592,45 @@ impl Grid {
         &self,
         contents: &mut Vec<u8>,
         prev: &Self,
+        prev_attrs: crate::attrs::Attrs,
+        color_depth: crate::ColorDepth,
+    ) -> crate::attrs::Attrs {
+        // if the rows have moved vertically, scroll them into place instead
+        // of redrawing them (find_shift only returns a shift when that is
+        // expected to be shorter)
+        if let Some(shift) = self.find_shift(prev) {
+            return self.write_contents_diff_shifted(
+                contents,
+                prev,
+                shift,
+                prev_attrs,
+                color_depth,
+            );
+        }
+
+        self.write_rows_diff(
+            contents,
+            prev.visible_rows(),
+            prev.pos,
+            prev_attrs,
+            color_depth,
+        )
+    }
+
+    fn write_rows_diff<R: std::borrow::Borrow<crate::row::Row>>(
+        &self,
+        contents: &mut Vec<u8>,
+        prev_rows: impl Iterator<Item = R>,
+        mut prev_pos: Pos,
         mut prev_attrs: crate::attrs::Attrs,
+        color_depth: crate::ColorDepth,
     ) -> crate::attrs::Attrs {
-        let mut prev_pos = prev.pos;
         let mut wrapping = false;
         let mut prev_wrapping = false;
         for (i, (row, prev_row)) in
-            self.visible_rows().zip(prev.visible_rows()).enumerate()
+            self.visible_rows().zip(prev_rows).enumerate()
         {
+            let prev_row = prev_row.borrow();
             // we limit the number of cols to a u16 (see Size), so
             // visible_rows() can never return more rows than will fit
             let i = i.try_into().unwrap();
@@ -276,6 +644,7 @@ impl Grid {
                 prev_wrapping,
                 prev_pos,
                 prev_attrs,
+                color_depth,
             );
             prev_pos = new_pos;
             prev_attrs = new_attrs;
@@ -287,16 +656,233 @@ impl Grid {
             contents,
             Some(prev_pos),
             Some(prev_attrs),
+            color_depth,
         );
 
         prev_attrs
     }
 
+    // looks for a range of rows in prev which appear shifted up or down in
+    // self, if scrolling them into place is expected to be shorter than
+    // redrawing them. rows are only compared in full when their text
+    // matches, so that this stays cheap when nothing has actually scrolled.
+    fn find_shift(&self, prev: &Self) -> Option<Shift> {
+        if self.size != prev.size {
+            return None;
+        }
+        // shifting rows can't save anything unless at least two rows would
+        // otherwise need to be redrawn. this is checked up front since it
+        // is the common case (a few cells changing), and doesn't need to
+        // allocate anything.
+        self.visible_rows()
+            .zip(prev.visible_rows())
+            .filter(|(row, prev_row)| !row.same_contents(prev_row))
+            .nth(1)?;
+        let rows: Vec<_> = self.visible_rows().collect();
+        let prev_rows: Vec<_> = prev.visible_rows().collect();
+        let changed: Vec<_> = rows
+            .iter()
+            .zip(&prev_rows)
+            .map(|(row, prev_row)| !row.same_contents(prev_row))
+            .collect();
+
+        let mut prev_by_text: std::collections::HashMap<u64, Vec<usize>> =
+            std::collections::HashMap::new();
+        // rows which haven't changed can't have moved anywhere else
+        for (i, prev_row) in prev_rows.iter().enumerate() {
+            if !changed[i] {
+                continue;
+            }
+            if let Some(hash) = text_hash(prev_row) {
+                prev_by_text.entry(hash).or_default().push(i);
+            }
+        }
+        // (up, count) -> number of changed rows which would be fixed by
+        // shifting by that amount
+        let mut votes: std::collections::HashMap<(bool, usize), usize> =
+            std::collections::HashMap::new();
+        for (i, row) in rows.iter().enumerate() {
+            if !changed[i] {
+                continue;
+            }
+            let Some(candidates) =
+                text_hash(row).and_then(|hash| prev_by_text.get(&hash))
+            else {
+                continue;
+            };
+            for &j in candidates {
+                if j != i && row.same_contents(prev_rows[j]) {
+                    *votes.entry((j > i, i.abs_diff(j))).or_default() += 1;
+                }
+            }
+        }
+        let (up, count) = votes
+            .into_iter()
+            .max_by_key(|&((up, count), votes)| {
+                (votes, std::cmp::Reverse(count), up)
+            })
+            .map(|(shift, _)| shift)?;
+
+        // find the run of rows which are matched by this shift that fixes
+        // the most changed rows
+        let mut best: Option<(usize, usize, usize)> = None;
+        let mut run: Option<(usize, usize)> = None;
+        for i in 0..=rows.len() - count {
+            let (row, prev_row) =
+                if up { (i, i + count) } else { (i + count, i) };
+            if i < rows.len() - count
+                && rows[row].same_contents(prev_rows[prev_row])
+            {
+                let (start, fixed) = run.unwrap_or((i, 0));
+                run = Some((start, fixed + usize::from(changed[row])));
+            } else if let Some((start, fixed)) = run.take() {
+                if best.map_or(true, |(_, _, best_fixed)| fixed > best_fixed)
+                {
+                    best = Some((start, i - 1 + count, fixed));
+                }
+            }
+        }
+        let (top, bottom, fixed) = best?;
+
+        // the rows scrolled into the region are blank, so any of them which
+        // weren't already going to be redrawn and aren't blank now will need
+        // to be redrawn after scrolling. the escape sequences for scrolling
+        // cost about as much as drawing a short row, so only scroll if that
+        // saves redrawing at least two rows.
+        let scrolled_in = if up {
+            bottom + 1 - count..=bottom
+        } else {
+            top..=top + count - 1
+        };
+        let blank = crate::Cell::new();
+        let broken = scrolled_in
+            .filter(|&i| {
+                !changed[i] && rows[i].cells().any(|cell| *cell != blank)
+            })
+            .count();
+        if fixed < broken + 2 {
+            return None;
+        }
+
+        // all of these are bounded by the number of rows, which fits in a
+        // u16 (see Size)
+        Some(Shift {
+            top: top.try_into().unwrap(),
+            bottom: bottom.try_into().unwrap(),
+            count: count.try_into().unwrap(),
+            up,
+        })
+    }
+
+    fn write_contents_diff_shifted(
+        &self,
+        contents: &mut Vec<u8>,
+        prev: &Self,
+        shift: Shift,
+        mut prev_attrs: crate::attrs::Attrs,
+        color_depth: crate::ColorDepth,
+    ) -> crate::attrs::Attrs {
+        // some terminals fill the rows scrolled in with the current
+        // background color
+        if prev_attrs != crate::attrs::Attrs::default() {
+            crate::term::ClearAttrs.write_buf(contents);
+            prev_attrs = crate::attrs::Attrs::default();
+        }
+
+        let Shift {
+            top,
+            bottom,
+            count,
+            up,
+        } = shift;
+        let mut prev_pos = prev.pos;
+        if top == 0 && bottom == self.size.rows - 1 {
+            if up {
+                crate::term::ScrollUp::new(count).write_buf(contents);
+            } else {
+                crate::term::ScrollDown::new(count).write_buf(contents);
+            }
+            // terminals don't agree on whether scrolling clears a pending
+            // wrap, so make sure we know where the cursor is
+            if prev_pos.col >= self.size.cols {
+                prev_pos.col = self.size.cols - 1;
+                crate::term::MoveTo::new(prev_pos).write_buf(contents);
+            }
+        } else if bottom == self.size.rows - 1 {
+            prev_pos = Pos { row: top, col: 0 };
+            crate::term::MoveTo::new(prev_pos).write_buf(contents);
+            if up {
+                crate::term::DeleteLines::new(count).write_buf(contents);
+            } else {
+                crate::term::InsertLines::new(count).write_buf(contents);
+            }
+        } else {
+            crate::term::SetScrollRegion::new(top, bottom)
+                .write_buf(contents);
+            if up {
+                crate::term::ScrollUp::new(count).write_buf(contents);
+            } else {
+                crate::term::ScrollDown::new(count).write_buf(contents);
+            }
+            // resetting the scroll region also moves the cursor to the top
+            // left corner
+            crate::term::ResetScrollRegion.write_buf(contents);
+            prev_pos = Pos::default();
+        }
+
+        // now diff against what the rows should look like after scrolling
+        let blank = crate::row::Row::new(self.size.cols);
+        let mut shifted_rows = shift.apply(prev, &blank);
+        // scrolling down unwraps the last row of the scroll region
+        let last = &mut shifted_rows[usize::from(bottom)];
+        if !up && last.wrapped() {
+            last.to_mut().wrap(false);
+        }
+        self.write_rows_diff(
+            contents,
+            shifted_rows.into_iter(),
+            prev_pos,
+            prev_attrs,
+            color_depth,
+        )
+    }
+
+    // appends the changes to the visible rows needed to turn prev into
+    // self. rows which have moved are scrolled into place first, in the same
+    // cases where write_contents_diff would scroll them.
+    pub fn changes(&self, prev: &Self, changes: &mut Vec<crate::Change>) {
+        let blank = crate::row::Row::new(self.size.cols);
+        let prev_rows = self.find_shift(prev).map_or_else(
+            || {
+                prev.visible_rows()
+                    .map(std::borrow::Cow::Borrowed)
+                    .collect()
+            },
+            |shift| {
+                changes.push(crate::Change::Scroll {
+                    top: shift.top,
+                    bottom: shift.bottom,
+                    count: shift.count,
+                    up: shift.up,
+                });
+                shift.apply(prev, &blank)
+            },
+        );
+        for (i, (row, prev_row)) in
+            (0..).zip(self.visible_rows().zip(prev_rows))
+        {
+            if !row.same_contents(&prev_row) {
+                row.changes(&prev_row, i, changes);
+            }
+        }
+    }
+
     pub fn write_cursor_position_formatted(
         &self,
         contents: &mut Vec<u8>,
         prev_pos: Option<Pos>,
         prev_attrs: Option<crate::attrs::Attrs>,
+        color_depth: crate::ColorDepth,
     ) {
         let prev_attrs = prev_attrs.unwrap_or_default();
         // writing a character to the last column of a row doesn't wrap the
@@ -333,9 +919,17 @@ impl Grid {
                 } else {
                     crate::term::MoveTo::new(pos).write_buf(contents);
                 }
-                cell.attrs().write_escape_code_diff(contents, &prev_attrs);
+                cell.attrs().write_escape_code_diff(
+                    contents,
+                    &prev_attrs,
+                    color_depth,
+                );
                 contents.extend(cell.contents().as_bytes());
-                prev_attrs.write_escape_code_diff(contents, cell.attrs());
+                prev_attrs.write_escape_code_diff(
+                    contents,
+                    cell.attrs(),
+                    color_depth,
+                );
             } else {
                 // if the cell doesn't have contents, we can't have gotten
                 // here by drawing a character in the last column. this means
@@ -380,11 +974,13 @@ impl Grid {
                                 cell.attrs().write_escape_code_diff(
                                     contents,
                                     &prev_attrs,
+                                    color_depth,
                                 );
                                 contents.extend(cell.contents().as_bytes());
                                 prev_attrs.write_escape_code_diff(
                                     contents,
                                     cell.attrs(),
+                                    color_depth,
                                 );
                             }
                         } else {
@@ -392,11 +988,13 @@ impl Grid {
                             cell.attrs().write_escape_code_diff(
                                 contents,
                                 &prev_attrs,
+                                color_depth,
                             );
                             contents.extend(cell.contents().as_bytes());
                             prev_attrs.write_escape_code_diff(
                                 contents,
                                 cell.attrs(),
+                                color_depth,
                             );
                         }
                         contents.extend(
@@ -433,15 +1031,20 @@ impl Grid {
                         // we assume self.pos.row is always valid, and
                         // self.size.cols - 1 is always a valid column
                         .unwrap();
-                    end_cell
-                        .attrs()
-                        .write_escape_code_diff(contents, &prev_attrs);
+                    end_cell.attrs().write_escape_code_diff(
+                        contents,
+                        &prev_attrs,
+                        color_depth,
+                    );
                     crate::term::SaveCursor.write_buf(contents);
                     crate::term::Backspace.write_buf(contents);
                     crate::term::EraseChar::new(1).write_buf(contents);
                     crate::term::RestoreCursor.write_buf(contents);
-                    prev_attrs
-                        .write_escape_code_diff(contents, end_cell.attrs());
+                    prev_attrs.write_escape_code_diff(
+                        contents,
+                        end_cell.attrs(),
+                        color_depth,
+                    );
                 }
             }
         } else if let Some(prev_pos) = prev_pos {
@@ -460,18 +1063,22 @@ impl Grid {
 
     pub fn erase_all_forward(&mut self, attrs: crate::attrs::Attrs) {
         let pos = self.pos;
-        for row in self.drawing_rows_mut().skip(usize::from(pos.row) + 1) {
-            row.clear(attrs);
+        let rows = usize::from(pos.row) + 1..;
+        for row in self.rows.range_mut(rows.clone()) {
+            std::sync::Arc::make_mut(row).clear(attrs);
         }
+        self.dirty.range_mut(rows).for_each(|dirty| *dirty = true);
 
         self.erase_row_forward(attrs);
     }
 
     pub fn erase_all_backward(&mut self, attrs: crate::attrs::Attrs) {
         let pos = self.pos;
-        for row in self.drawing_rows_mut().take(usize::from(pos.row)) {
-            row.clear(attrs);
+        let rows = ..usize::from(pos.row);
+        for row in self.rows.range_mut(rows) {
+            std::sync::Arc::make_mut(row).clear(attrs);
         }
+        self.dirty.range_mut(rows).for_each(|dirty| *dirty = true);
 
         self.erase_row_backward(attrs);
     }
@@ -542,47 +1149,138 @@ impl Grid {
     }
 
     pub fn insert_lines(&mut self, count: u16) {
-        for _ in 0..count {
-            self.rows.remove(usize::from(self.scroll_bottom));
-            self.rows.insert(usize::from(self.pos.row), self.new_row());
-            // self.scroll_bottom is maintained to always be a valid row
-            self.rows[usize::from(self.scroll_bottom)].wrap(false);
+        // the cursor isn't required to be inside the scroll region here, in
+        // which case the rows between the bottom of the scroll region and
+        // the cursor are shifted up instead
+        if self.pos.row <= self.scroll_bottom {
+            self.rotate_rows_down(self.pos.row, self.scroll_bottom, count);
+        } else {
+            self.rotate_rows_up(self.scroll_bottom, self.pos.row, count);
         }
+        self.unwrap_scroll_bottom();
     }
 
     pub fn delete_lines(&mut self, count: u16) {
-        for _ in 0..(count.min(self.size.rows - self.pos.row)) {
-            self.rows
-                .insert(usize::from(self.scroll_bottom) + 1, self.new_row());
-            self.rows.remove(usize::from(self.pos.row));
+        let count = count.min(self.size.rows - self.pos.row);
+        // see insert_lines - in this case, the rows between the bottom of
+        // the scroll region and the row above the cursor are shifted down
+        if self.pos.row <= self.scroll_bottom {
+            self.rotate_rows_up(self.pos.row, self.scroll_bottom, count);
+        } else if self.pos.row > self.scroll_bottom + 1 {
+            self.rotate_rows_down(
+                self.scroll_bottom + 1,
+                self.pos.row - 1,
+                count,
+            );
+        }
+    }
+
+    fn unwrap_scroll_bottom(&mut self) {
+        // self.scroll_bottom is maintained to always be a valid row
+        let bottom = usize::from(self.scroll_bottom);
+        if self.rows[bottom].wrapped() {
+            std::sync::Arc::make_mut(&mut self.rows[bottom]).wrap(false);
+            self.dirty[bottom] = true;
         }
     }
 
     pub fn scroll_up(&mut self, count: u16) {
-        for _ in 0..(count.min(self.size.rows - self.scroll_top)) {
-            self.rows
-                .insert(usize::from(self.scroll_bottom) + 1, self.new_row());
-            let removed = self.rows.remove(usize::from(self.scroll_top));
-            if self.scrollback_len > 0 && !self.scroll_region_active() {
-                self.scrollback.push_back(removed);
-                while self.scrollback.len() > self.scrollback_len {
-                    self.scrollback.pop_front();
-                }
-                if self.scrollback_offset > 0 {
-                    self.scrollback_offset =
-                        self.scrollback.len().min(self.scrollback_offset + 1);
-                }
+        let count = count.min(self.size.rows - self.scroll_top);
+        if self.scrollback_len == 0 || self.scroll_region_active() {
+            self.rotate_rows_up(self.scroll_top, self.scroll_bottom, count);
+            return;
+        }
+
+        for _ in 0..count {
+            let mut row = self
+                .rows
+                .pop_front()
+                // the grid always has at least one row
+                .unwrap();
+            let compact = crate::compact::CompactRow::new(&row);
+            // reuse the allocation for the new row if nothing else is
+            // referring to the old row
+            if let Some(row_mut) = std::sync::Arc::get_mut(&mut row) {
+                row_mut.clear(crate::attrs::Attrs::default());
+            } else {
+                row = self.new_row();
+            }
+            self.rows.push_back(row);
+            self.dirty.pop_front();
+            self.dirty.push_back(true);
+            self.scrolled = self.scrolled.saturating_add(1);
+            self.scrollback_bytes += compact.memory_usage();
+            self.scrollback.push_back(std::sync::Arc::new(compact));
+            self.trim_scrollback();
+            if self.scrollback_offset > 0 {
+                self.scrollback_offset =
+                    self.scrollback.len().min(self.scrollback_offset + 1);
             }
         }
+        self.update_scrollback_view();
     }
 
     pub fn scroll_down(&mut self, count: u16) {
-        for _ in 0..count {
-            self.rows.remove(usize::from(self.scroll_bottom));
-            self.rows
-                .insert(usize::from(self.scroll_top), self.new_row());
-            // self.scroll_bottom is maintained to always be a valid row
-            self.rows[usize::from(self.scroll_bottom)].wrap(false);
+        self.rotate_rows_down(self.scroll_top, self.scroll_bottom, count);
+        self.unwrap_scroll_bottom();
+    }
+
+    // moves the rows from top to bottom (inclusive) up by count rows,
+    // discarding the rows that move past the top and filling in new rows at
+    // the bottom
+    fn rotate_rows_up(&mut self, top: u16, bottom: u16, count: u16) {
+        let top = usize::from(top);
+        let bottom = usize::from(bottom);
+        let count = usize::from(count).min(bottom - top + 1);
+        if top == 0 && bottom == self.rows.len() - 1 {
+            for _ in 0..count {
+                self.rows.pop_front();
+                self.rows.push_back(self.new_row());
+                self.dirty.pop_front();
+                self.dirty.push_back(true);
+                self.scrolled = self.scrolled.saturating_add(1);
+            }
+        } else {
+            let cols = self.size.cols;
+            let rows = &mut self.rows.make_contiguous()[top..=bottom];
+            rows.rotate_left(count);
+            for row in &mut rows[bottom + 1 - top - count..] {
+                *row = std::sync::Arc::new(crate::row::Row::new(cols));
+            }
+            // only the whole screen scrolling is tracked, so rows that
+            // move within part of the screen have to be redrawn
+            self.dirty
+                .range_mut(top..=bottom)
+                .for_each(|dirty| *dirty = true);
+        }
+    }
+
+    // moves the rows from top to bottom (inclusive) down by count rows,
+    // discarding the rows that move past the bottom and filling in new rows
+    // at the top
+    fn rotate_rows_down(&mut self, top: u16, bottom: u16, count: u16) {
+        let top = usize::from(top);
+        let bottom = usize::from(bottom);
+        let count = usize::from(count).min(bottom - top + 1);
+        if top == 0 && bottom == self.rows.len() - 1 {
+            for _ in 0..count {
+                self.rows.pop_back();
+                self.rows.push_front(self.new_row());
+                self.dirty.pop_back();
+                self.dirty.push_front(true);
+                self.scrolled = self.scrolled.saturating_sub(1);
+            }
+        } else {
+            let cols = self.size.cols;
+            let rows = &mut self.rows.make_contiguous()[top..=bottom];
+            rows.rotate_right(count);
+            for row in &mut rows[..count] {
+                *row = std::sync::Arc::new(crate::row::Row::new(cols));
+            }
+            // see rotate_rows_up
+            self.dirty
+                .range_mut(top..=bottom)
+                .for_each(|dirty| *dirty = true);
         }
     }
 
@@ -682,12 +1380,13 @@ impl Grid {
             let scrolled = self.row_inc_scroll(1);
             prev_pos.row -= scrolled;
             let new_pos = self.pos;
-            self.drawing_row_mut(prev_pos.row)
-                // we assume self.pos.row is always valid, and so prev_pos.row
-                // must be valid because it is always less than or equal to
-                // self.pos.row
-                .unwrap()
-                .wrap(wrap && prev_pos.row + 1 == new_pos.row);
+            let wrap = wrap && prev_pos.row + 1 == new_pos.row;
+            // we assume self.pos.row is always valid, and so prev_pos.row
+            // must be valid because it is always less than or equal to
+            // self.pos.row
+            if self.drawing_row(prev_pos.row).unwrap().wrapped() != wrap {
+                self.drawing_row_mut(prev_pos.row).unwrap().wrap(wrap);
+            }
         }
     }
 
@@ -729,13 +1428,71 @@ impl Grid {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+struct Shift {
+    top: u16,
+    bottom: u16,
+    count: u16,
+    up: bool,
+}
+
+impl Shift {
+    // the visible rows of prev as they would be after shifting them, with
+    // the rows shifted in replaced by blank
+    fn apply<'a>(
+        self,
+        prev: &'a Grid,
+        blank: &'a crate::row::Row,
+    ) -> Vec<std::borrow::Cow<'a, crate::row::Row>> {
+        let Self {
+            top,
+            bottom,
+            count,
+            up,
+        } = self;
+        let prev_rows: Vec<_> = prev.visible_rows().collect();
+        (0..prev.size.rows)
+            .map(|i| {
+                let prev_row = if i < top || i > bottom {
+                    Some(i)
+                } else if up {
+                    (i + count <= bottom).then_some(i + count)
+                } else {
+                    (i >= top + count).then(|| i - count)
+                };
+                std::borrow::Cow::Borrowed(
+                    prev_row.map_or(blank, |j| prev_rows[usize::from(j)]),
+                )
+            })
+            .collect()
+    }
+}
+
+// a cheap (fnv-1a) hash of the text in a row, or None if the row has no
+// text at all
+fn text_hash(row: &crate::row::Row) -> Option<u64> {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    let mut has_contents = false;
+    for cell in row.cells() {
+        has_contents |= cell.has_contents();
+        // include a separator so that empty cells aren't ignored
+        for &byte in cell.contents().as_bytes().iter().chain(&[0xff]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100_0000_01b3);
+        }
+    }
+    has_contents.then_some(hash)
+}
+
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Size {
     pub rows: u16,
     pub cols: u16,
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pos {
     pub row: u16,
     pub col: u16,
@@ -0,0 +1,140 @@
+pub fn write_html<R: std::borrow::Borrow<crate::row::Row>>(
+    contents: &mut String,
+    rows: impl Iterator<Item = R>,
+    palette: &crate::Palette,
+) {
+    contents.push_str(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>\n",
+    );
+    contents.push_str("<pre style=\"color:");
+    push_rgb(contents, palette.default_fgcolor());
+    contents.push_str(";background-color:");
+    push_rgb(contents, palette.default_bgcolor());
+    contents.push_str("\">");
+
+    // like Screen::contents, trailing blank rows are not included
+    let mut blank_rows = 0;
+    for row in rows {
+        let mut line = String::new();
+        write_row_html(&mut line, row.borrow(), palette);
+        if line.is_empty() {
+            blank_rows += 1;
+        } else {
+            for _ in 0..blank_rows {
+                contents.push('\n');
+            }
+            blank_rows = 0;
+            contents.push_str(&line);
+            contents.push('\n');
+        }
+    }
+
+    contents.push_str("</pre>\n</body>\n</html>\n");
+}
+
+fn write_row_html(
+    contents: &mut String,
+    row: &crate::row::Row,
+    palette: &crate::Palette,
+) {
+    let Some(len) = row
+        .cells()
+        .rposition(|cell| {
+            !cell.is_wide_continuation() && !is_blank(cell, palette)
+        })
+        .map(|col| col + 1)
+    else {
+        return;
+    };
+
+    let mut span_style: Option<String> = None;
+    for cell in row.cells().take(len) {
+        if cell.is_wide_continuation() {
+            continue;
+        }
+
+        let style = cell_style(cell, palette);
+        if span_style != style {
+            if span_style.is_some() {
+                contents.push_str("</span>");
+            }
+            if let Some(style) = &style {
+                contents.push_str("<span style=\"");
+                contents.push_str(style);
+                contents.push_str("\">");
+            }
+            span_style = style;
+        }
+
+        if cell.has_contents() {
+            push_escaped(contents, cell.contents());
+        } else {
+            contents.push(' ');
+        }
+    }
+    if span_style.is_some() {
+        contents.push_str("</span>");
+    }
+}
+
+fn is_blank(cell: &crate::Cell, palette: &crate::Palette) -> bool {
+    matches!(cell.contents(), "" | " ")
+        && !cell.underline()
+        && palette.resolve(cell, false).1 == palette.default_bgcolor()
+}
+
+fn cell_style(
+    cell: &crate::Cell,
+    palette: &crate::Palette,
+) -> Option<String> {
+    let mut style = String::new();
+    let (fgcolor, bgcolor) = palette.resolve(cell, false);
+    if fgcolor != palette.default_fgcolor() {
+        style.push_str("color:");
+        push_rgb(&mut style, fgcolor);
+        style.push(';');
+    }
+    if bgcolor != palette.default_bgcolor() {
+        style.push_str("background-color:");
+        push_rgb(&mut style, bgcolor);
+        style.push(';');
+    }
+    if cell.bold() {
+        style.push_str("font-weight:bold;");
+    }
+    if cell.italic() {
+        style.push_str("font-style:italic;");
+    }
+    if cell.underline() {
+        style.push_str("text-decoration:underline;");
+    }
+    if style.is_empty() {
+        None
+    } else {
+        // remove the trailing semicolon
+        style.pop();
+        Some(style)
+    }
+}
+
+pub fn push_rgb(contents: &mut String, (r, g, b): (u8, u8, u8)) {
+    const HEX: &[u8] = b"0123456789abcdef";
+    contents.push('#');
+    for c in [r, g, b] {
+        contents.push(char::from(HEX[usize::from(c >> 4)]));
+        contents.push(char::from(HEX[usize::from(c & 0xf)]));
+    }
+}
+
+pub fn push_escaped(contents: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '&' => contents.push_str("&amp;"),
+            '<' => contents.push_str("&lt;"),
+            '>' => contents.push_str("&gt;"),
+            '"' => contents.push_str("&quot;"),
+            '\'' => contents.push_str("&#39;"),
+            c => contents.push(c),
+        }
+    }
+}
@@ -47,18 +47,63 @@
 #![allow(clippy::too_many_lines)]
 #![allow(clippy::type_complexity)]
 
+#[cfg(feature = "asciicast")]
+mod asciicast;
+#[cfg(feature = "tokio")]
+mod async_parser;
 mod attrs;
+mod broadcast;
 mod callbacks;
 mod cell;
+mod change;
+mod compact;
+mod compositor;
+mod damage;
+mod expect;
+#[cfg(feature = "png")]
+mod font;
 mod grid;
+mod html;
+mod palette;
 mod parser;
 mod perform;
+mod playback;
+#[cfg(all(feature = "pty", unix))]
+mod pty;
+#[cfg(feature = "png")]
+mod raster;
 mod row;
 mod screen;
+mod scrollback;
+mod snapshot;
+mod svg;
 mod term;
+#[cfg(feature = "testing")]
+mod testing;
+mod tmux;
+mod typescript;
 
-pub use attrs::Color;
+#[cfg(feature = "asciicast")]
+pub use asciicast::{AsciicastError, AsciicastPlayer, AsciicastRecorder};
+#[cfg(feature = "tokio")]
+pub use async_parser::AsyncParser;
+pub use attrs::{Color, ColorDepth};
+pub use broadcast::{Broadcaster, ClientId, Frame};
 pub use callbacks::Callbacks;
 pub use cell::Cell;
+pub use change::Change;
+pub use compositor::{Border, Compositor, Pane, Rect};
+pub use damage::Damage;
+pub use expect::{ChannelParser, Expect, ExpectError, Expectation};
+pub use palette::Palette;
 pub use parser::Parser;
+#[cfg(all(feature = "pty", unix))]
+pub use pty::{Session, SessionCallbacks};
 pub use screen::{MouseProtocolEncoding, MouseProtocolMode, Screen};
+pub use snapshot::SnapshotError;
+#[cfg(feature = "testing")]
+pub use testing::assert_screen_snapshot;
+pub use tmux::{
+    TmuxClient, TmuxError, TmuxEvent, TmuxLayout, TmuxLayoutKind,
+};
+pub use typescript::{TypescriptError, TypescriptPlayer};
@@ -0,0 +1,205 @@
+const XTERM_BASE_COLORS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+const XTERM_CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// A mapping from [`Color`](crate::Color) values to concrete RGB values.
+///
+/// The default palette uses the standard xterm colors: the 16 base colors,
+/// the 6x6x6 color cube, and the 24 step grayscale ramp, with light gray
+/// text on a black background.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Palette {
+    colors: [(u8, u8, u8); 256],
+    fgcolor: (u8, u8, u8),
+    bgcolor: (u8, u8, u8),
+}
+
+impl Palette {
+    /// Creates a new palette containing the standard xterm colors.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut colors = [(0, 0, 0); 256];
+        for idx in 0..=u8::MAX {
+            colors[usize::from(idx)] = xterm_color(idx);
+        }
+        Self {
+            colors,
+            fgcolor: XTERM_BASE_COLORS[7],
+            bgcolor: XTERM_BASE_COLORS[0],
+        }
+    }
+
+    /// Returns the RGB value used for the given indexed color.
+    #[must_use]
+    pub fn indexed(&self, idx: u8) -> (u8, u8, u8) {
+        self.colors[usize::from(idx)]
+    }
+
+    /// Sets the RGB value used for the given indexed color.
+    pub fn set_indexed(&mut self, idx: u8, rgb: (u8, u8, u8)) {
+        self.colors[usize::from(idx)] = rgb;
+    }
+
+    /// Returns the RGB value used for text drawn with the default
+    /// foreground color.
+    #[must_use]
+    pub fn default_fgcolor(&self) -> (u8, u8, u8) {
+        self.fgcolor
+    }
+
+    /// Sets the RGB value used for text drawn with the default foreground
+    /// color.
+    pub fn set_default_fgcolor(&mut self, rgb: (u8, u8, u8)) {
+        self.fgcolor = rgb;
+    }
+
+    /// Returns the RGB value used for cells drawn with the default
+    /// background color.
+    #[must_use]
+    pub fn default_bgcolor(&self) -> (u8, u8, u8) {
+        self.bgcolor
+    }
+
+    /// Sets the RGB value used for cells drawn with the default background
+    /// color.
+    pub fn set_default_bgcolor(&mut self, rgb: (u8, u8, u8)) {
+        self.bgcolor = rgb;
+    }
+
+    /// Returns the RGB value for the given color when used as a foreground
+    /// color.
+    #[must_use]
+    pub fn fgcolor(&self, color: crate::Color) -> (u8, u8, u8) {
+        match color {
+            crate::Color::Default => self.fgcolor,
+            crate::Color::Idx(i) => self.indexed(i),
+            crate::Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// Returns the RGB value for the given color when used as a background
+    /// color.
+    #[must_use]
+    pub fn bgcolor(&self, color: crate::Color) -> (u8, u8, u8) {
+        match color {
+            crate::Color::Default => self.bgcolor,
+            crate::Color::Idx(i) => self.indexed(i),
+            crate::Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// Returns the final (foreground, background) RGB values that should be
+    /// used to draw the given cell.
+    ///
+    /// This takes the inverse and dim attributes of the cell into account.
+    /// If `bold_is_bright` is true, bold text drawn with one of the first
+    /// eight indexed colors will use the corresponding bright color instead,
+    /// as many terminals do.
+    #[must_use]
+    pub fn resolve(
+        &self,
+        cell: &crate::Cell,
+        bold_is_bright: bool,
+    ) -> ((u8, u8, u8), (u8, u8, u8)) {
+        let fgcolor = match cell.fgcolor() {
+            crate::Color::Idx(i)
+                if bold_is_bright && cell.bold() && i < 8 =>
+            {
+                crate::Color::Idx(i + 8)
+            }
+            color => color,
+        };
+        let mut fgcolor = self.fgcolor(fgcolor);
+        let mut bgcolor = self.bgcolor(cell.bgcolor());
+        if cell.inverse() {
+            std::mem::swap(&mut fgcolor, &mut bgcolor);
+        }
+        if cell.dim() {
+            fgcolor = dim(fgcolor, bgcolor);
+        }
+        (fgcolor, bgcolor)
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn xterm_color(idx: u8) -> (u8, u8, u8) {
+    match idx {
+        0..=15 => XTERM_BASE_COLORS[usize::from(idx)],
+        16..=231 => {
+            let idx = idx - 16;
+            (
+                XTERM_CUBE_LEVELS[usize::from(idx / 36)],
+                XTERM_CUBE_LEVELS[usize::from(idx / 6 % 6)],
+                XTERM_CUBE_LEVELS[usize::from(idx % 6)],
+            )
+        }
+        232..=255 => {
+            let level = 8 + (idx - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+// ties are broken in favor of the lowest index, so that the result is
+// deterministic
+pub fn nearest_xterm_color(
+    rgb: (u8, u8, u8),
+    candidates: std::ops::RangeInclusive<u8>,
+) -> u8 {
+    let distance = |idx: u8| {
+        let (r, g, b) = xterm_color(idx);
+        let dr = i32::from(r) - i32::from(rgb.0);
+        let dg = i32::from(g) - i32::from(rgb.1);
+        let db = i32::from(b) - i32::from(rgb.2);
+        dr * dr + dg * dg + db * db
+    };
+    let start = *candidates.start();
+    let (best, _) = candidates.fold(
+        (start, distance(start)),
+        |(best, best_distance), idx| {
+            let distance = distance(idx);
+            if distance < best_distance {
+                (idx, distance)
+            } else {
+                (best, best_distance)
+            }
+        },
+    );
+    best
+}
+
+// dim text is drawn two thirds of the way from the background color to the
+// foreground color
+fn dim(fgcolor: (u8, u8, u8), bgcolor: (u8, u8, u8)) -> (u8, u8, u8) {
+    let blend = |fg: u8, bg: u8| {
+        let blended = (u16::from(fg) * 2 + u16::from(bg)) / 3;
+        // the weighted average of two u8 values always fits in a u8
+        u8::try_from(blended).unwrap()
+    };
+    (
+        blend(fgcolor.0, bgcolor.0),
+        blend(fgcolor.1, bgcolor.1),
+        blend(fgcolor.2, bgcolor.2),
+    )
+}
@@ -1,8 +1,16 @@
 /// A parser for terminal output which produces an in-memory representation of
 /// the terminal contents.
 pub struct Parser<CB: crate::callbacks::Callbacks = ()> {
-    parser: vte::Parser,
+    vte: vte::Parser,
     screen: crate::perform::WrappedScreen<CB>,
+    // the bytes needed to bring a new vte::Parser into the same state as
+    // vte, used for snapshots. this is empty when vte is in the ground
+    // state, starts with an escape character when an escape sequence is in
+    // progress, and otherwise contains the start of an incomplete utf8
+    // character. the payload of a DCS sequence is never included, since it
+    // is kept by the WrappedScreen, and neither is the payload of an SOS,
+    // PM or APC sequence, since vte ignores it.
+    pending: Vec<u8>,
 }
 
 impl Parser {
@@ -11,14 +19,36 @@ impl Parser {
     #[must_use]
     pub fn new(rows: u16, cols: u16, scrollback_len: usize) -> Self {
         Self {
-            parser: vte::Parser::new(),
+            vte: vte::Parser::new(),
             screen: crate::perform::WrappedScreen::new(
                 rows,
                 cols,
                 scrollback_len,
             ),
+            pending: vec![],
         }
     }
+
+    /// Creates a new terminal parser which continues from the state in the
+    /// given [`Screen`](crate::Screen).
+    ///
+    /// Note that a screen doesn't include the state of any escape sequence
+    /// which was only partially processed when it was captured.
+    #[must_use]
+    pub fn from_screen(screen: crate::Screen) -> Self {
+        Self::from_screen_with_callbacks(screen, ())
+    }
+
+    /// Restores a parser from a snapshot created by
+    /// [`save_snapshot`](Self::save_snapshot).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` isn't a valid snapshot, or was written
+    /// by a newer version of this crate with an incompatible format.
+    pub fn load_snapshot(data: &[u8]) -> Result<Self, crate::SnapshotError> {
+        Self::load_snapshot_with_callbacks(data, ())
+    }
 }
 
 impl<CB: crate::callbacks::Callbacks> Parser<CB> {
@@ -33,20 +63,146 @@ impl<CB: crate::callbacks::Callbacks> Parser<CB> {
         callbacks: CB,
     ) -> Self {
         Self {
-            parser: vte::Parser::new(),
+            vte: vte::Parser::new(),
             screen: crate::perform::WrappedScreen::new_with_callbacks(
                 rows,
                 cols,
                 scrollback_len,
                 callbacks,
             ),
+            pending: vec![],
+        }
+    }
+
+    /// Creates a new terminal parser which continues from the state in the
+    /// given [`Screen`](crate::Screen). Terminal events will be reported via
+    /// method calls on the provided
+    /// [`Callbacks`](crate::callbacks::Callbacks) implementation.
+    pub fn from_screen_with_callbacks(
+        screen: crate::Screen,
+        callbacks: CB,
+    ) -> Self {
+        Self {
+            vte: vte::Parser::new(),
+            screen: crate::perform::WrappedScreen::from_screen(
+                screen, callbacks,
+            ),
+            pending: vec![],
         }
     }
 
     /// Processes the contents of the given byte string, and updates the
     /// in-memory terminal state.
     pub fn process(&mut self, bytes: &[u8]) {
-        self.parser.advance(&mut self.screen, bytes);
+        // an escape character always starts a new escape sequence no matter
+        // what state the parser was in, so we only need to look at what
+        // happens after the last one
+        if let Some(i) = bytes.iter().rposition(|&b| b == 0x1b) {
+            let (head, tail) = bytes.split_at(i + 1);
+            self.advance(head);
+            self.screen.ground = false;
+            self.advance(tail);
+            self.pending.clear();
+            if self.screen.ground {
+                self.pending.extend_from_slice(
+                    crate::snapshot::incomplete_utf8_suffix(tail),
+                );
+            } else {
+                self.pending.extend_from_slice(&bytes[i..]);
+            }
+        } else {
+            self.screen.ground = false;
+            self.advance(bytes);
+            if self.screen.ground || self.pending.first() != Some(&0x1b) {
+                self.pending.extend_from_slice(
+                    &bytes[bytes.len().saturating_sub(3)..],
+                );
+                let suffix =
+                    crate::snapshot::incomplete_utf8_suffix(&self.pending);
+                self.pending.drain(..self.pending.len() - suffix.len());
+            } else {
+                self.pending.extend_from_slice(bytes);
+            }
+        }
+        if let Some(header_len) = string_header_len(&self.pending) {
+            self.pending.truncate(header_len);
+        }
+        self.screen.flush_text();
+    }
+
+    fn advance(&mut self, bytes: &[u8]) {
+        // the parser doesn't report whether a DCS sequence was ended by a
+        // c1 st, so process each one separately to see whether it ended
+        // one. c1 st bytes are rare (apart from in utf8 text), so most
+        // calls don't need to split their input at all.
+        if !bytes.contains(&0x9c) {
+            self.vte.advance(&mut self.screen, bytes);
+            self.screen.unhooked = false;
+            return;
+        }
+        for chunk in bytes.split_inclusive(|&b| b == 0x9c) {
+            let Some((&0x9c, rest)) = chunk.split_last() else {
+                self.vte.advance(&mut self.screen, chunk);
+                continue;
+            };
+            self.vte.advance(&mut self.screen, rest);
+            self.screen.unhooked = false;
+            self.vte.advance(&mut self.screen, &[0x9c]);
+            if self.screen.unhooked {
+                self.screen.end_dcs();
+            }
+        }
+        self.screen.unhooked = false;
+    }
+
+    /// Saves the full state of the parser, including the screen, the
+    /// scrollback, and any escape sequence which has only been partially
+    /// processed, in a compact binary format.
+    ///
+    /// The state can be restored with
+    /// [`load_snapshot`](crate::Parser::load_snapshot), after which
+    /// processing can continue as if the parser had never been saved. The
+    /// [`Callbacks`](crate::callbacks::Callbacks) state is not included.
+    /// Snapshots are versioned, and snapshots saved by a given release of
+    /// this crate can be loaded by any later release with the same major
+    /// and minor version.
+    #[must_use]
+    pub fn save_snapshot(&self) -> Vec<u8> {
+        let mut w = crate::snapshot::Writer::new();
+        w.bytes(&self.pending);
+        self.screen.screen.write_snapshot(&mut w);
+        self.screen.write_snapshot(&mut w);
+        w.finish()
+    }
+
+    /// Restores a parser from a snapshot created by
+    /// [`save_snapshot`](Self::save_snapshot). Terminal events will be
+    /// reported via method calls on the provided
+    /// [`Callbacks`](crate::callbacks::Callbacks) implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` isn't a valid snapshot, or was written
+    /// by a newer version of this crate with an incompatible format.
+    pub fn load_snapshot_with_callbacks(
+        data: &[u8],
+        callbacks: CB,
+    ) -> Result<Self, crate::SnapshotError> {
+        let mut r = crate::snapshot::Reader::new(data)?;
+        let pending = r.bytes()?.to_vec();
+        let screen = crate::screen::Screen::read_snapshot(&mut r)?;
+        let mut screen =
+            crate::perform::WrappedScreen::from_screen(screen, callbacks);
+        screen.read_snapshot(&mut r)?;
+        r.finish()?;
+
+        let mut vte = vte::Parser::new();
+        vte.advance(&mut crate::snapshot::Discard, &pending);
+        Ok(Self {
+            vte,
+            screen,
+            pending,
+        })
     }
 
     /// Returns a reference to a [`Screen`](crate::Screen) object containing
@@ -94,3 +250,18 @@ impl std::io::Write for Parser {
         Ok(())
     }
 }
+
+// the length of the start of a DCS, SOS, PM or APC sequence, before its
+// payload. returns None for other escape sequences, or if the start of the
+// payload hasn't been reached yet.
+fn string_header_len(pending: &[u8]) -> Option<usize> {
+    match pending.get(1)? {
+        b'X' | b'^' | b'_' => Some(2),
+        // parameters and intermediates, followed by the final byte
+        b'P' => pending[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map(|i| i + 3),
+        _ => None,
+    }
+}
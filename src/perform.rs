@@ -1,10 +1,46 @@
 const BASE64: &[u8] =
     b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
 const CLIPBOARD_SELECTOR: &[u8] = b"cpqs01234567";
+// DCS sequences with longer payloads than this are discarded
+const DCS_MAX_LEN: usize = 1024 * 1024;
+// the start of the payload of a tmux passthrough sequence (the 't' of
+// "tmux" is the final byte of the DCS sequence itself)
+const TMUX_PASSTHROUGH: &[u8] = b"mux;";
+
+// a DCS sequence which has been started but not finished
+struct Dcs {
+    i1: Option<u8>,
+    i2: Option<u8>,
+    params: Vec<Vec<u16>>,
+    c: char,
+    data: Vec<u8>,
+    too_long: bool,
+}
 
 pub struct WrappedScreen<CB: crate::callbacks::Callbacks = ()> {
     pub screen: crate::screen::Screen,
     pub callbacks: CB,
+    // printable ascii characters that haven't been written to the screen
+    // yet, so that they can be written a row at a time
+    text: Vec<u8>,
+    // set whenever the parser returns to the ground state (see
+    // Parser::process)
+    pub ground: bool,
+    dcs: Option<Dcs>,
+    // set when dcs was ended by an escape character. it is only complete
+    // if the escape character turns out to be the start of an st, so it is
+    // held until the next sequence shows whether it is.
+    dcs_ended: bool,
+    // set when dcs has just ended, so that Parser::process can check how it
+    // ended
+    pub unhooked: bool,
+    // set after the first part of a tmux passthrough sequence, until the
+    // st which ends it
+    tmux_passthrough: bool,
+    // set when a sequence wrapped in a tmux passthrough sequence was ended
+    // by an escape character, so the next st belongs to it rather than to
+    // the passthrough sequence
+    tmux_wrapped_st: bool,
 }
 
 impl WrappedScreen<()> {
@@ -20,18 +56,191 @@ impl<CB: crate::callbacks::Callbacks> WrappedScreen<CB> {
         scrollback_len: usize,
         callbacks: CB,
     ) -> Self {
-        Self {
-            screen: crate::screen::Screen::new(
+        Self::from_screen(
+            crate::screen::Screen::new(
                 crate::grid::Size { rows, cols },
                 scrollback_len,
             ),
             callbacks,
+        )
+    }
+
+    pub fn from_screen(screen: crate::screen::Screen, callbacks: CB) -> Self {
+        Self {
+            screen,
+            callbacks,
+            text: vec![],
+            ground: true,
+            dcs: None,
+            dcs_ended: false,
+            unhooked: false,
+            tmux_passthrough: false,
+            tmux_wrapped_st: false,
+        }
+    }
+
+    pub fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            self.screen.text_ascii(&self.text);
+            self.text.clear();
+        }
+    }
+
+    // the DCS state isn't part of the screen, so it is saved separately
+    pub fn write_snapshot(&self, w: &mut crate::snapshot::Writer) {
+        match &self.dcs {
+            None => w.u8(0),
+            Some(dcs) => {
+                w.u8(if self.dcs_ended { 2 } else { 1 });
+                // intermediates are never 0
+                w.u8(dcs.i1.unwrap_or(0));
+                w.u8(dcs.i2.unwrap_or(0));
+                w.usize(dcs.params.len());
+                for param in &dcs.params {
+                    w.usize(param.len());
+                    for &n in param {
+                        w.uint(n);
+                    }
+                }
+                // final bytes are always ascii
+                w.u8(u8::try_from(dcs.c).unwrap());
+                w.bool(dcs.too_long);
+                w.bytes(&dcs.data);
+            }
+        }
+        w.bool(self.tmux_passthrough);
+        w.bool(self.tmux_wrapped_st);
+    }
+
+    pub fn read_snapshot(
+        &mut self,
+        r: &mut crate::snapshot::Reader,
+    ) -> Result<(), crate::SnapshotError> {
+        let state = r.u8()?;
+        if state > 2 {
+            return Err(crate::SnapshotError::Corrupt);
+        }
+        if state > 0 {
+            let intermediate = |b: u8| (b != 0).then_some(b);
+            let i1 = intermediate(r.u8()?);
+            let i2 = intermediate(r.u8()?);
+            let mut params = vec![];
+            for _ in 0..r.usize()? {
+                let mut param = vec![];
+                for _ in 0..r.usize()? {
+                    param.push(r.u16()?);
+                }
+                params.push(param);
+            }
+            let c = r.u8()?;
+            let too_long = r.bool()?;
+            let data = r.bytes()?.to_vec();
+            if !(0x40..=0x7e).contains(&c)
+                || data.len() > DCS_MAX_LEN
+                || (too_long && !data.is_empty())
+            {
+                return Err(crate::SnapshotError::Corrupt);
+            }
+            self.dcs = Some(Dcs {
+                i1,
+                i2,
+                params,
+                c: c.into(),
+                data,
+                too_long,
+            });
+            self.dcs_ended = state == 2;
+        }
+        self.tmux_passthrough = r.bool()?;
+        self.tmux_wrapped_st = r.bool()?;
+        Ok(())
+    }
+
+    // called when the current DCS sequence was ended by an st
+    pub fn end_dcs(&mut self) {
+        self.dcs_ended = false;
+        let Some(dcs) = self.dcs.take() else {
+            return;
+        };
+        if dcs.too_long {
+            return;
+        }
+        if let Some(data) = dcs.tmux_passthrough() {
+            self.tmux_text(data);
+            return;
+        }
+        self.callbacks.unhandled_dcs(
+            &mut self.screen,
+            dcs.i1,
+            dcs.i2,
+            &dcs.params.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            dcs.c,
+            &dcs.data,
+        );
+    }
+
+    // called when a sequence starts after a DCS sequence which was ended by
+    // an escape character that didn't start an st. regular DCS sequences
+    // are discarded, but this is how tmux passthrough sequences continue.
+    fn abort_dcs(&mut self) {
+        if !std::mem::take(&mut self.dcs_ended) {
+            return;
+        }
+        let Some(dcs) = self.dcs.take() else {
+            return;
+        };
+        if dcs.too_long {
+            return;
+        }
+        if let Some(data) = dcs.tmux_passthrough() {
+            self.tmux_text(data);
+            self.tmux_passthrough = true;
+            self.tmux_wrapped_st = false;
+        }
+    }
+
+    // the data before the first escape character in a tmux passthrough
+    // sequence can't contain any escape sequences, so it is just text and
+    // control characters
+    fn tmux_text(&mut self, data: &[u8]) {
+        for c in String::from_utf8_lossy(data).chars() {
+            match c {
+                '\0'..='\x1f' => {
+                    // we just checked that this is ascii
+                    vte::Perform::execute(self, u8::try_from(c).unwrap());
+                }
+                '\x7f' => {}
+                _ => vte::Perform::print(self, c),
+            }
+        }
+        self.flush_text();
+    }
+}
+
+impl Dcs {
+    // the wrapped data, if this is a tmux passthrough sequence
+    fn tmux_passthrough(&self) -> Option<&[u8]> {
+        if self.c == 't' && self.i1.is_none() {
+            self.data.strip_prefix(TMUX_PASSTHROUGH)
+        } else {
+            None
         }
     }
 }
 
 impl<CB: crate::callbacks::Callbacks> vte::Perform for WrappedScreen<CB> {
     fn print(&mut self, c: char) {
+        self.ground = true;
+        if self.dcs_ended {
+            self.abort_dcs();
+        }
+        if matches!(c, ' '..='~') {
+            // printable ascii characters are always a single byte
+            self.text.push(u8::try_from(c).unwrap());
+            return;
+        }
+
+        self.flush_text();
         if c == '\u{fffd}' || ('\u{80}'..'\u{a0}').contains(&c) {
             self.callbacks.unhandled_char(&mut self.screen, c);
         } else {
@@ -40,6 +249,14 @@ impl<CB: crate::callbacks::Callbacks> vte::Perform for WrappedScreen<CB> {
     }
 
     fn execute(&mut self, b: u8) {
+        self.flush_text();
+        // these cancel any escape sequence in progress, including a DCS
+        // sequence which hasn't seen its st yet
+        if b == 0x18 || b == 0x1a {
+            self.ground = true;
+            self.dcs = None;
+            self.dcs_ended = false;
+        }
         match b {
             7 => self.callbacks.audible_bell(&mut self.screen),
             8 => self.screen.bs(),
@@ -56,6 +273,35 @@ impl<CB: crate::callbacks::Callbacks> vte::Perform for WrappedScreen<CB> {
     }
 
     fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, b: u8) {
+        self.flush_text();
+        self.ground = true;
+        // tmux doubles the escape characters in the sequences it passes
+        // through, and the parser treats the first one of each pair as the
+        // end of the dcs sequence and ignores the second, so the wrapped
+        // sequences are processed normally. all that is left is the st
+        // which ends the passthrough sequence.
+        if intermediates.is_empty() && b == b'\\' {
+            if self.dcs_ended {
+                // the st of a tmux passthrough sequence is consumed along
+                // with it, but other DCS sequences report their st as
+                // they always have
+                let tmux = self
+                    .dcs
+                    .as_ref()
+                    .is_some_and(|dcs| dcs.tmux_passthrough().is_some());
+                self.end_dcs();
+                if tmux {
+                    return;
+                }
+            } else if self.tmux_wrapped_st {
+                self.tmux_wrapped_st = false;
+            } else if self.tmux_passthrough {
+                self.tmux_passthrough = false;
+                return;
+            }
+        } else {
+            self.abort_dcs();
+        }
         if let Some(i) = intermediates.first() {
             self.callbacks.unhandled_escape(
                 &mut self.screen,
@@ -91,6 +337,9 @@ impl<CB: crate::callbacks::Callbacks> vte::Perform for WrappedScreen<CB> {
         _ignore: bool,
         c: char,
     ) {
+        self.flush_text();
+        self.ground = true;
+        self.abort_dcs();
         let unhandled = |screen: &mut crate::screen::Screen| {
             self.callbacks.unhandled_csi(
                 screen,
@@ -195,7 +444,59 @@ impl<CB: crate::callbacks::Callbacks> vte::Perform for WrappedScreen<CB> {
         }
     }
 
-    fn osc_dispatch(&mut self, params: &[&[u8]], _bel_terminated: bool) {
+    fn hook(
+        &mut self,
+        params: &vte::Params,
+        intermediates: &[u8],
+        _ignore: bool,
+        c: char,
+    ) {
+        self.flush_text();
+        self.abort_dcs();
+        self.dcs = Some(Dcs {
+            i1: intermediates.first().copied(),
+            i2: intermediates.get(1).copied(),
+            params: params.iter().map(<[u16]>::to_vec).collect(),
+            c,
+            data: vec![],
+            too_long: false,
+        });
+    }
+
+    fn put(&mut self, b: u8) {
+        if let Some(dcs) = &mut self.dcs {
+            if dcs.too_long {
+                return;
+            }
+            if dcs.data.len() < DCS_MAX_LEN {
+                dcs.data.push(b);
+            } else {
+                // the sequence will be discarded, so there's no need to
+                // keep the data around until it ends
+                dcs.too_long = true;
+                dcs.data = vec![];
+            }
+        }
+    }
+
+    fn unhook(&mut self) {
+        self.flush_text();
+        // the parser is back in the ground state unless the sequence was
+        // ended by an escape character, in which case whatever follows it
+        // will reset this
+        self.ground = true;
+        // the sequence is only complete once we know that it was ended by
+        // an st. Parser::process checks for a c1 st, and esc_dispatch checks
+        // for an escape character followed by a backslash.
+        self.dcs_ended = self.dcs.is_some();
+        self.unhooked = true;
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bel_terminated: bool) {
+        self.flush_text();
+        self.ground = true;
+        self.abort_dcs();
+        self.tmux_wrapped_st = self.tmux_passthrough && !bel_terminated;
         match params {
             [b"0", s] => {
                 self.callbacks.set_window_icon_name(&mut self.screen, s);
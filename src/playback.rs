@@ -0,0 +1,123 @@
+// the amount of output to replay between keyframes while seeking
+const KEYFRAME_INTERVAL: usize = 64 * 1024;
+
+pub enum Event {
+    Output(Vec<u8>),
+    Resize(u16, u16),
+}
+
+struct Keyframe {
+    // the index of the first event which isn't included in the keyframe
+    event: usize,
+    snapshot: Vec<u8>,
+}
+
+// the shared implementation of the recording players, which apply a list of
+// timestamped events to a parser. a keyframe containing a snapshot of the
+// parser is stored periodically, so that seeking only needs to replay the
+// events since the nearest keyframe.
+pub struct Playback {
+    size: (u16, u16),
+    events: Vec<(std::time::Duration, Event)>,
+    keyframes: Vec<Keyframe>,
+    parser: crate::Parser,
+    next: usize,
+    time: std::time::Duration,
+}
+
+impl Playback {
+    pub fn new(
+        size: (u16, u16),
+        events: Vec<(std::time::Duration, Event)>,
+    ) -> Self {
+        let mut playback = Self {
+            size,
+            events,
+            keyframes: vec![],
+            parser: crate::Parser::new(size.0, size.1, 0),
+            next: 0,
+            time: std::time::Duration::ZERO,
+        };
+        playback.build_keyframes();
+        playback
+    }
+
+    pub fn duration(&self) -> std::time::Duration {
+        self.events
+            .last()
+            .map_or(std::time::Duration::ZERO, |&(time, _)| time)
+    }
+
+    pub fn position(&self) -> std::time::Duration {
+        self.time
+    }
+
+    pub fn step(&mut self) -> Option<std::time::Duration> {
+        let (time, event) = self.events.get(self.next)?;
+        apply(&mut self.parser, event);
+        self.next += 1;
+        self.time = *time;
+        Some(*time)
+    }
+
+    pub fn seek(&mut self, time: std::time::Duration) {
+        let target = self.events.partition_point(|&(t, _)| t <= time);
+        let keyframe = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.event <= target)
+            .checked_sub(1)
+            .map(|i| &self.keyframes[i]);
+        let start = keyframe.map_or(0, |keyframe| keyframe.event);
+
+        // playing forward from the current position is preferred when it
+        // doesn't mean replaying more events than loading the keyframe
+        if target < self.next || start > self.next {
+            if let Some(keyframe) = keyframe {
+                // keyframes are snapshots saved by this version of the
+                // crate, so they are always valid
+                self.parser =
+                    crate::Parser::load_snapshot(&keyframe.snapshot).unwrap();
+            } else {
+                self.parser = crate::Parser::new(self.size.0, self.size.1, 0);
+            }
+            self.next = start;
+        }
+
+        for (_, event) in &self.events[self.next..target] {
+            apply(&mut self.parser, event);
+        }
+        self.next = target;
+        self.time = time;
+    }
+
+    pub fn parser(&self) -> &crate::Parser {
+        &self.parser
+    }
+
+    fn build_keyframes(&mut self) {
+        let mut parser = crate::Parser::new(self.size.0, self.size.1, 0);
+        let mut since_keyframe = 0;
+        for (i, (_, event)) in self.events.iter().enumerate() {
+            apply(&mut parser, event);
+            if let Event::Output(bytes) = event {
+                since_keyframe += bytes.len();
+            }
+            if since_keyframe >= KEYFRAME_INTERVAL {
+                self.keyframes.push(Keyframe {
+                    event: i + 1,
+                    snapshot: parser.save_snapshot(),
+                });
+                since_keyframe = 0;
+            }
+        }
+    }
+}
+
+fn apply(parser: &mut crate::Parser, event: &Event) {
+    match event {
+        Event::Output(bytes) => parser.process(bytes),
+        &Event::Resize(rows, cols) => {
+            parser.screen_mut().set_size(rows, cols);
+        }
+    }
+}
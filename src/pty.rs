@@ -0,0 +1,502 @@
+use std::io::{Read as _, Write as _};
+use std::os::fd::AsRawFd as _;
+use std::os::unix::process::CommandExt as _;
+
+nix::ioctl_write_ptr_bad!(
+    set_window_size,
+    nix::libc::TIOCSWINSZ,
+    nix::pty::Winsize
+);
+nix::ioctl_write_int_bad!(set_controlling_terminal, nix::libc::TIOCSCTTY);
+
+// the amount of output processed by a single call to Session::read, so that
+// a child which produces output continuously can't keep the caller from
+// doing anything else
+const READ_LIMIT: usize = 64 * 1024;
+
+/// A process running in a pseudoterminal, whose output is processed by a
+/// [`Parser`](crate::Parser).
+///
+/// The session takes care of the glue between the two: the child's window
+/// size is kept in sync with the screen size, resize requests from the
+/// child (see [`Callbacks::resize`](crate::Callbacks::resize)) are applied,
+/// and device status and device attributes queries are answered. The
+/// queries are still passed on to
+/// [`Callbacks::unhandled_csi`](crate::Callbacks::unhandled_csi) after
+/// being answered, so they shouldn't be answered there again.
+///
+/// Dropping the session kills the child if it is still running, and waits
+/// for it to exit.
+pub struct Session<CB: crate::callbacks::Callbacks = ()> {
+    pty: std::fs::File,
+    child: std::process::Child,
+    parser: crate::Parser<SessionCallbacks<CB>>,
+    // replies to the child's requests which haven't been written yet,
+    // because the pty wasn't ready to accept them
+    replies: Vec<u8>,
+    eof: bool,
+}
+
+impl Session {
+    /// Spawns `cmd` in a new pseudoterminal of the given size, with a parser
+    /// which keeps the given amount of scrollback. `TERM` is set to
+    /// `xterm-256color` unless `cmd` sets it explicitly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pseudoterminal can't be created or the
+    /// command fails to start.
+    pub fn spawn(
+        cmd: std::process::Command,
+        rows: u16,
+        cols: u16,
+        scrollback_len: usize,
+    ) -> std::io::Result<Self> {
+        Self::spawn_with_callbacks(cmd, rows, cols, scrollback_len, ())
+    }
+}
+
+impl<CB: crate::callbacks::Callbacks> Session<CB> {
+    /// Spawns `cmd` in a new pseudoterminal of the given size, like
+    /// [`spawn`](Session::spawn). Terminal events will be reported via
+    /// method calls on the provided
+    /// [`Callbacks`](crate::callbacks::Callbacks) implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pseudoterminal can't be created or the
+    /// command fails to start.
+    pub fn spawn_with_callbacks(
+        mut cmd: std::process::Command,
+        rows: u16,
+        cols: u16,
+        scrollback_len: usize,
+        callbacks: CB,
+    ) -> std::io::Result<Self> {
+        let pty = nix::pty::openpty(&winsize(rows, cols), None)?;
+        // neither side of the pty should leak into the child, other than as
+        // its standard streams
+        for fd in [&pty.master, &pty.slave] {
+            nix::fcntl::fcntl(
+                fd,
+                nix::fcntl::FcntlArg::F_SETFD(nix::fcntl::FdFlag::FD_CLOEXEC),
+            )?;
+        }
+        nix::fcntl::fcntl(
+            &pty.master,
+            nix::fcntl::FcntlArg::F_SETFL(nix::fcntl::OFlag::O_NONBLOCK),
+        )?;
+
+        if !cmd.get_envs().any(|(key, _)| key == "TERM") {
+            cmd.env("TERM", "xterm-256color");
+        }
+        cmd.stdin(pty.slave.try_clone()?)
+            .stdout(pty.slave.try_clone()?)
+            .stderr(pty.slave);
+        // SAFETY: setsid and ioctl are async-signal-safe, and the closure
+        // doesn't allocate
+        unsafe {
+            cmd.pre_exec(|| {
+                nix::unistd::setsid()?;
+                set_controlling_terminal(0, 0)?;
+                Ok(())
+            });
+        }
+        let child = cmd.spawn()?;
+        // the slave fds are closed when cmd is dropped, so that reading from
+        // the master reports the end of the output once the child exits
+        drop(cmd);
+
+        Ok(Self {
+            pty: pty.master.into(),
+            child,
+            parser: crate::Parser::new_with_callbacks(
+                rows,
+                cols,
+                scrollback_len,
+                SessionCallbacks {
+                    callbacks,
+                    replies: vec![],
+                    resize: None,
+                },
+            ),
+            replies: vec![],
+            eof: false,
+        })
+    }
+
+    /// Reads and processes the output which is currently available from the
+    /// child, without blocking. At most 64KiB of output is processed per
+    /// call, so this should be called again until it returns an error of
+    /// kind [`WouldBlock`](std::io::ErrorKind::WouldBlock) in order to
+    /// read everything. Returns the number of bytes which were processed,
+    /// or `Ok(0)` once the child has exited and all of its output has been
+    /// read. Returns an error of kind
+    /// [`WouldBlock`](std::io::ErrorKind::WouldBlock) if no output is
+    /// available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the pseudoterminal fails, or if
+    /// writing a reply or applying a resize request fails.
+    pub fn read(&mut self) -> std::io::Result<usize> {
+        let mut buf = [0; 4096];
+        let mut total = 0;
+        self.flush_replies()?;
+        while !self.eof && total < READ_LIMIT {
+            match self.pty.read(&mut buf) {
+                Ok(0) => self.eof = true,
+                Ok(n) => {
+                    self.parser.process(&buf[..n]);
+                    total += n;
+                    self.handle_requests()?;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    if total == 0 {
+                        return Err(e);
+                    }
+                    break;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                // linux reports EIO once the slave side has been closed
+                Err(e) if e.raw_os_error() == Some(nix::libc::EIO) => {
+                    self.eof = true;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(total)
+    }
+
+    /// Writes input to the child, waiting for the pseudoterminal to accept
+    /// all of it. Any replies to the child's requests which haven't been
+    /// written yet are written first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the pseudoterminal fails.
+    pub fn write_input(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        let replies = std::mem::take(&mut self.replies);
+        self.write_all(&replies)?;
+        self.write_all(bytes)
+    }
+
+    fn write_all(&mut self, mut bytes: &[u8]) -> std::io::Result<()> {
+        while !bytes.is_empty() {
+            match self.pty.write(bytes) {
+                Ok(n) => bytes = &bytes[n..],
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    let mut fds = [nix::poll::PollFd::new(
+                        std::os::fd::AsFd::as_fd(&self.pty),
+                        nix::poll::PollFlags::POLLOUT,
+                    )];
+                    match nix::poll::poll(
+                        &mut fds,
+                        nix::poll::PollTimeout::NONE,
+                    ) {
+                        Ok(_) | Err(nix::errno::Errno::EINTR) => {}
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Resizes the screen, and informs the child of the new window size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the window size of the pseudoterminal can't be
+    /// changed.
+    pub fn set_size(&mut self, rows: u16, cols: u16) -> std::io::Result<()> {
+        self.parser.screen_mut().set_size(rows, cols);
+        // SAFETY: the pointer is to a valid winsize struct for the duration
+        // of the call
+        unsafe {
+            set_window_size(self.pty.as_raw_fd(), &winsize(rows, cols))
+        }?;
+        Ok(())
+    }
+
+    /// Returns the exit status of the child if it has exited, without
+    /// blocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the status of the child can't be checked.
+    pub fn try_wait(
+        &mut self,
+    ) -> std::io::Result<Option<std::process::ExitStatus>> {
+        self.child.try_wait()
+    }
+
+    /// Waits for the child to exit, and returns its exit status.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if waiting for the child fails.
+    pub fn wait(&mut self) -> std::io::Result<std::process::ExitStatus> {
+        self.child.wait()
+    }
+
+    /// Returns the process id of the child.
+    #[must_use]
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Returns a reference to a [`Screen`](crate::Screen) object containing
+    /// the terminal state.
+    #[must_use]
+    pub fn screen(&self) -> &crate::Screen {
+        self.parser.screen()
+    }
+
+    /// Returns a reference to the [`Parser`](crate::Parser) which processes
+    /// the child's output, for instance in order to save a
+    /// [snapshot](crate::Parser::save_snapshot) of it.
+    #[must_use]
+    pub fn parser(&self) -> &crate::Parser<SessionCallbacks<CB>> {
+        &self.parser
+    }
+
+    /// Returns a reference to the [`Callbacks`](crate::callbacks::Callbacks)
+    /// state object passed into the constructor.
+    pub fn callbacks(&self) -> &CB {
+        &self.parser.callbacks().callbacks
+    }
+
+    /// Returns a mutable reference to the
+    /// [`Callbacks`](crate::callbacks::Callbacks) state object passed into
+    /// the constructor.
+    pub fn callbacks_mut(&mut self) -> &mut CB {
+        &mut self.parser.callbacks_mut().callbacks
+    }
+
+    fn handle_requests(&mut self) -> std::io::Result<()> {
+        let replies =
+            std::mem::take(&mut self.parser.callbacks_mut().replies);
+        self.replies.extend_from_slice(&replies);
+        self.flush_replies()?;
+        if let Some((rows, cols)) = self.parser.callbacks_mut().resize.take()
+        {
+            self.set_size(rows, cols)?;
+        }
+        Ok(())
+    }
+
+    // writes as many of the queued replies as the pty will accept without
+    // blocking. blocking here could deadlock, since the child may itself be
+    // blocked on writing output which we aren't reading.
+    fn flush_replies(&mut self) -> std::io::Result<()> {
+        let mut written = 0;
+        while written < self.replies.len() {
+            match self.pty.write(&self.replies[written..]) {
+                Ok(n) => written += n,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    break;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.replies.drain(..written);
+        Ok(())
+    }
+}
+
+impl<CB: crate::callbacks::Callbacks> Drop for Session<CB> {
+    fn drop(&mut self) {
+        // errors are ignored here, since there's nothing useful to do with
+        // them (killing fails if the child has already exited, which is
+        // fine)
+        if matches!(self.child.try_wait(), Ok(None)) {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+}
+
+impl<CB: crate::callbacks::Callbacks> std::os::fd::AsFd for Session<CB> {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.pty.as_fd()
+    }
+}
+
+impl<CB: crate::callbacks::Callbacks> crate::expect::Expect for Session<CB> {
+    fn screen(&self) -> &crate::Screen {
+        self.screen()
+    }
+
+    fn wait_for_output(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> std::io::Result<bool> {
+        let mut fds = [nix::poll::PollFd::new(
+            std::os::fd::AsFd::as_fd(&self.pty),
+            nix::poll::PollFlags::POLLIN,
+        )];
+        let timeout = nix::poll::PollTimeout::try_from(timeout)
+            .unwrap_or(nix::poll::PollTimeout::MAX);
+        match nix::poll::poll(&mut fds, timeout) {
+            Ok(_) | Err(nix::errno::Errno::EINTR) => {}
+            Err(e) => return Err(e.into()),
+        }
+        match self.read() {
+            Ok(0) => Ok(false),
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The [`Callbacks`](crate::Callbacks) implementation used by the parser of
+/// a [`Session`].
+///
+/// This wraps the callbacks passed into the session's constructor, in
+/// order to collect the requests which need to be sent back to the child.
+pub struct SessionCallbacks<CB: crate::callbacks::Callbacks> {
+    callbacks: CB,
+    replies: Vec<u8>,
+    resize: Option<(u16, u16)>,
+}
+
+impl<CB: crate::callbacks::Callbacks> SessionCallbacks<CB> {
+    /// Returns a reference to the wrapped
+    /// [`Callbacks`](crate::callbacks::Callbacks) state object.
+    pub fn callbacks(&self) -> &CB {
+        &self.callbacks
+    }
+}
+
+impl<CB: crate::callbacks::Callbacks> crate::callbacks::Callbacks
+    for SessionCallbacks<CB>
+{
+    fn audible_bell(&mut self, screen: &mut crate::Screen) {
+        self.callbacks.audible_bell(screen);
+    }
+
+    fn visual_bell(&mut self, screen: &mut crate::Screen) {
+        self.callbacks.visual_bell(screen);
+    }
+
+    fn resize(&mut self, screen: &mut crate::Screen, request: (u16, u16)) {
+        self.callbacks.resize(screen, request);
+        if request.0 > 0 && request.1 > 0 {
+            self.resize = Some(request);
+        }
+    }
+
+    fn set_window_icon_name(
+        &mut self,
+        screen: &mut crate::Screen,
+        icon_name: &[u8],
+    ) {
+        self.callbacks.set_window_icon_name(screen, icon_name);
+    }
+
+    fn set_window_title(&mut self, screen: &mut crate::Screen, title: &[u8]) {
+        self.callbacks.set_window_title(screen, title);
+    }
+
+    fn copy_to_clipboard(
+        &mut self,
+        screen: &mut crate::Screen,
+        ty: &[u8],
+        data: &[u8],
+    ) {
+        self.callbacks.copy_to_clipboard(screen, ty, data);
+    }
+
+    fn paste_from_clipboard(
+        &mut self,
+        screen: &mut crate::Screen,
+        ty: &[u8],
+    ) {
+        self.callbacks.paste_from_clipboard(screen, ty);
+    }
+
+    fn unhandled_char(&mut self, screen: &mut crate::Screen, c: char) {
+        self.callbacks.unhandled_char(screen, c);
+    }
+
+    fn unhandled_control(&mut self, screen: &mut crate::Screen, b: u8) {
+        self.callbacks.unhandled_control(screen, b);
+    }
+
+    fn unhandled_escape(
+        &mut self,
+        screen: &mut crate::Screen,
+        i1: Option<u8>,
+        i2: Option<u8>,
+        b: u8,
+    ) {
+        self.callbacks.unhandled_escape(screen, i1, i2, b);
+    }
+
+    fn unhandled_csi(
+        &mut self,
+        screen: &mut crate::Screen,
+        i1: Option<u8>,
+        i2: Option<u8>,
+        params: &[&[u16]],
+        c: char,
+    ) {
+        match (i1, i2, params, c) {
+            // device status report
+            (None, None, [[5]], 'n') => {
+                self.replies.extend_from_slice(b"\x1b[0n");
+            }
+            // cursor position report
+            (None, None, [[6]], 'n') => {
+                let pos = screen.grid().reported_pos();
+                self.replies.extend_from_slice(
+                    format!("\x1b[{};{}R", pos.row + 1, pos.col + 1)
+                        .as_bytes(),
+                );
+            }
+            // primary device attributes (a vt100 with advanced video)
+            (None, None, [] | [[0]], 'c') => {
+                self.replies.extend_from_slice(b"\x1b[?1;2c");
+            }
+            _ => {}
+        }
+        // the queries are passed on even though they have been answered,
+        // so that the user can see them
+        self.callbacks.unhandled_csi(screen, i1, i2, params, c);
+    }
+
+    fn unhandled_osc(
+        &mut self,
+        screen: &mut crate::Screen,
+        params: &[&[u8]],
+    ) {
+        self.callbacks.unhandled_osc(screen, params);
+    }
+
+    fn unhandled_dcs(
+        &mut self,
+        screen: &mut crate::Screen,
+        i1: Option<u8>,
+        i2: Option<u8>,
+        params: &[&[u16]],
+        c: char,
+        data: &[u8],
+    ) {
+        self.callbacks
+            .unhandled_dcs(screen, i1, i2, params, c, data);
+    }
+}
+
+fn winsize(rows: u16, cols: u16) -> nix::pty::Winsize {
+    nix::pty::Winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
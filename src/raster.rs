@@ -0,0 +1,162 @@
+use crate::font::{GLYPH_HEIGHT, GLYPH_UNDERLINE, GLYPH_WIDTH};
+
+struct Image {
+    width: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: usize, height: usize, color: (u8, u8, u8)) -> Self {
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&[color.0, color.1, color.2]);
+        }
+        Self { width, pixels }
+    }
+
+    fn set(&mut self, x: usize, y: usize, color: (u8, u8, u8)) {
+        let idx = (y * self.width + x) * 3;
+        self.pixels[idx..idx + 3]
+            .copy_from_slice(&[color.0, color.1, color.2]);
+    }
+
+    fn get(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        let idx = (y * self.width + x) * 3;
+        (self.pixels[idx], self.pixels[idx + 1], self.pixels[idx + 2])
+    }
+
+    fn fill(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        color: (u8, u8, u8),
+    ) {
+        for y in y..y + height {
+            for x in x..x + width {
+                self.set(x, y, color);
+            }
+        }
+    }
+}
+
+pub fn write_png<'a, W: std::io::Write>(
+    w: W,
+    rows: impl Iterator<Item = &'a crate::row::Row>,
+    size: crate::grid::Size,
+    cursor: Option<crate::grid::Pos>,
+    palette: &crate::Palette,
+) -> std::io::Result<()> {
+    if size.rows == 0 || size.cols == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "can't render a screen with no rows or columns",
+        ));
+    }
+
+    let cell_width = usize::from(GLYPH_WIDTH);
+    let cell_height = usize::from(GLYPH_HEIGHT);
+    let width = usize::from(size.cols) * cell_width;
+    let height = usize::from(size.rows) * cell_height;
+    let mut image = Image::new(width, height, palette.default_bgcolor());
+
+    for (i, row) in rows.enumerate() {
+        let y = i * cell_height;
+        // wide continuation cells don't have their own attributes, and are
+        // drawn as part of the cell before them
+        for (col, cell) in row.cells().enumerate() {
+            if cell.is_wide_continuation() {
+                continue;
+            }
+            let x = col * cell_width;
+            let cell_cols = if cell.is_wide() {
+                2.min(usize::from(size.cols) - col)
+            } else {
+                1
+            };
+            draw_cell(&mut image, x, y, cell_cols, cell, palette);
+        }
+    }
+
+    if let Some(pos) = cursor {
+        let col = pos.col.min(size.cols - 1);
+        let x = usize::from(col) * cell_width;
+        let y = usize::from(pos.row) * cell_height;
+        let fgcolor = palette.default_fgcolor();
+        for y in y..y + cell_height {
+            for x in x..x + cell_width {
+                let blended = blend(image.get(x, y), fgcolor);
+                image.set(x, y, blended);
+            }
+        }
+    }
+
+    // the image size is limited by the size of the grid, which is stored
+    // in u16 values
+    let mut encoder = png::Encoder::new(
+        w,
+        width.try_into().unwrap(),
+        height.try_into().unwrap(),
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&image.pixels)?;
+    writer.finish()?;
+    Ok(())
+}
+
+fn draw_cell(
+    image: &mut Image,
+    x: usize,
+    y: usize,
+    cell_cols: usize,
+    cell: &crate::Cell,
+    palette: &crate::Palette,
+) {
+    let cell_width = usize::from(GLYPH_WIDTH);
+    let (fgcolor, bgcolor) = palette.resolve(cell, false);
+    image.fill(
+        x,
+        y,
+        cell_width * cell_cols,
+        usize::from(GLYPH_HEIGHT),
+        bgcolor,
+    );
+
+    if let Some(c) = cell.contents().chars().next() {
+        let glyph = crate::font::glyph(c);
+        for (dy, bits) in glyph.iter().enumerate() {
+            for dx in 0..cell_width * cell_cols {
+                // glyphs are stretched horizontally to fill wide cells
+                let bit = dx / cell_cols;
+                let is_set = |bit: usize| bits & (0x80 >> bit) != 0;
+                // bold text is drawn by overstriking the glyph one pixel to
+                // the right
+                if is_set(bit) || (cell.bold() && bit > 0 && is_set(bit - 1))
+                {
+                    image.set(x + dx, y + dy, fgcolor);
+                }
+            }
+        }
+    }
+
+    if cell.underline() {
+        image.fill(
+            x,
+            y + usize::from(GLYPH_UNDERLINE),
+            cell_width * cell_cols,
+            1,
+            fgcolor,
+        );
+    }
+}
+
+fn blend(a: (u8, u8, u8), b: (u8, u8, u8)) -> (u8, u8, u8) {
+    let blend = |a: u8, b: u8| {
+        // the average of two u8 values always fits in a u8
+        u8::try_from((u16::from(a) + u16::from(b)) / 2).unwrap()
+    };
+    (blend(a.0, b.0), blend(a.1, b.1), blend(a.2, b.2))
+}
@@ -1,6 +1,7 @@
 use crate::term::BufWrite as _;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Row {
     cells: Vec<crate::Cell>,
     wrapped: bool,
@@ -14,6 +15,10 @@ impl Row {
         }
     }
 
+    pub fn from_cells(cells: Vec<crate::Cell>, wrapped: bool) -> Self {
+        Self { cells, wrapped }
+    }
+
     fn cols(&self) -> u16 {
         self.cells
             .len()
@@ -29,7 +34,10 @@ impl Row {
         self.wrapped = false;
     }
 
-    fn cells(&self) -> impl Iterator<Item = &crate::Cell> {
+    pub fn cells(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &crate::Cell> + ExactSizeIterator
+    {
         self.cells.iter()
     }
 
@@ -73,6 +81,12 @@ impl Row {
     pub fn resize(&mut self, len: u16, cell: crate::Cell) {
         self.cells.resize(usize::from(len), cell);
         self.wrapped = false;
+        // shrinking the row may have cut a wide character in half
+        if let Some(last_cell) = self.cells.last_mut() {
+            if last_cell.is_wide() {
+                last_cell.clear(*last_cell.attrs());
+            }
+        }
     }
 
     pub fn wrap(&mut self, wrap: bool) {
@@ -83,6 +97,78 @@ impl Row {
         self.wrapped
     }
 
+    // checks that every wide cell is followed by a continuation cell and
+    // vice versa, since the rest of the code relies on this and the row may
+    // have been loaded from arbitrary data
+    pub fn wide_cells_valid(&self) -> bool {
+        valid_wide_cells(
+            self.cells
+                .iter()
+                .map(|cell| (cell.is_wide(), cell.is_wide_continuation())),
+        )
+    }
+
+    // compares the contents of two rows, without looking at the cells of
+    // rows which are shared
+    pub fn same_contents(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+            || (self.wrapped == other.wrapped && self.cells == other.cells)
+    }
+
+    // appends the runs of cells which differ from prev, followed by
+    // whether the row wraps if that differs too
+    pub fn changes(
+        &self,
+        prev: &Self,
+        row: u16,
+        changes: &mut Vec<crate::Change>,
+    ) {
+        let mut run: Option<(u16, Vec<crate::Cell>)> = None;
+        for (col, (cell, prev_cell)) in
+            (0..).zip(self.cells().zip(prev.cells()))
+        {
+            if cell == prev_cell {
+                if let Some((col, cells)) = run.take() {
+                    changes.push(crate::Change::Cells { row, col, cells });
+                }
+            } else {
+                run.get_or_insert_with(|| (col, vec![]))
+                    .1
+                    .push(cell.clone());
+            }
+        }
+        if let Some((col, cells)) = run {
+            changes.push(crate::Change::Cells { row, col, cells });
+        }
+        if self.wrapped != prev.wrapped {
+            changes.push(crate::Change::Wrapped {
+                row,
+                wrapped: self.wrapped,
+            });
+        }
+    }
+
+    // writes printable ascii characters to consecutive cells starting at
+    // col, which must all be valid columns
+    pub fn write_ascii(
+        &mut self,
+        col: u16,
+        text: &[u8],
+        attrs: crate::attrs::Attrs,
+    ) {
+        let start = usize::from(col);
+        for (col, &c) in (start..).zip(text) {
+            // overwriting half of a wide character clears the other half
+            if self.cells[col].is_wide_continuation() {
+                self.cells[col - 1].clear(attrs);
+            }
+            if self.cells[col].is_wide() {
+                self.cells[col + 1].set(' ', attrs);
+            }
+            self.cells[col].set(char::from(c), attrs);
+        }
+    }
+
     pub fn clear_wide(&mut self, col: u16) {
         let cell = &self.cells[usize::from(col)];
         let other = if cell.is_wide() {
@@ -143,6 +229,7 @@ impl Row {
         wrapping: bool,
         prev_pos: Option<crate::grid::Pos>,
         prev_attrs: Option<crate::attrs::Attrs>,
+        color_depth: crate::ColorDepth,
     ) -> (crate::grid::Pos, crate::attrs::Attrs) {
         let mut prev_was_wide = false;
         let default_cell = crate::Cell::new();
@@ -163,7 +250,11 @@ impl Row {
         if wrapping && first_cell == &default_cell {
             let default_attrs = default_cell.attrs();
             if &prev_attrs != default_attrs {
-                default_attrs.write_escape_code_diff(contents, &prev_attrs);
+                default_attrs.write_escape_code_diff(
+                    contents,
+                    &prev_attrs,
+                    color_depth,
+                );
                 prev_attrs = *default_attrs;
             }
             contents.push(b' ');
@@ -211,7 +302,11 @@ impl Row {
                     }
                     prev_pos = new_pos;
                     if &prev_attrs != attrs {
-                        attrs.write_escape_code_diff(contents, &prev_attrs);
+                        attrs.write_escape_code_diff(
+                            contents,
+                            &prev_attrs,
+                            color_depth,
+                        );
                         prev_attrs = *attrs;
                     }
                     crate::term::EraseChar::new(pos.col - prev_col)
@@ -237,7 +332,11 @@ impl Row {
                     }
 
                     if &prev_attrs != attrs {
-                        attrs.write_escape_code_diff(contents, &prev_attrs);
+                        attrs.write_escape_code_diff(
+                            contents,
+                            &prev_attrs,
+                            color_depth,
+                        );
                         prev_attrs = *attrs;
                     }
 
@@ -269,7 +368,11 @@ impl Row {
             }
             prev_pos = new_pos;
             if &prev_attrs != attrs {
-                attrs.write_escape_code_diff(contents, &prev_attrs);
+                attrs.write_escape_code_diff(
+                    contents,
+                    &prev_attrs,
+                    color_depth,
+                );
                 prev_attrs = *attrs;
             }
             crate::term::ClearRowForward.write_buf(contents);
@@ -292,6 +395,7 @@ impl Row {
         prev_wrapping: bool,
         mut prev_pos: crate::grid::Pos,
         mut prev_attrs: crate::attrs::Attrs,
+        color_depth: crate::ColorDepth,
     ) -> (crate::grid::Pos, crate::attrs::Attrs) {
         let mut prev_was_wide = false;
 
@@ -306,8 +410,11 @@ impl Row {
         {
             let first_cell_attrs = first_cell.attrs();
             if &prev_attrs != first_cell_attrs {
-                first_cell_attrs
-                    .write_escape_code_diff(contents, &prev_attrs);
+                first_cell_attrs.write_escape_code_diff(
+                    contents,
+                    &prev_attrs,
+                    color_depth,
+                );
                 prev_attrs = *first_cell_attrs;
             }
             let mut cell_contents = prev_first_cell.contents();
@@ -368,7 +475,11 @@ impl Row {
                     }
                     prev_pos = new_pos;
                     if &prev_attrs != attrs {
-                        attrs.write_escape_code_diff(contents, &prev_attrs);
+                        attrs.write_escape_code_diff(
+                            contents,
+                            &prev_attrs,
+                            color_depth,
+                        );
                         prev_attrs = *attrs;
                     }
                     crate::term::EraseChar::new(pos.col - prev_col)
@@ -394,7 +505,11 @@ impl Row {
                     }
 
                     if &prev_attrs != attrs {
-                        attrs.write_escape_code_diff(contents, &prev_attrs);
+                        attrs.write_escape_code_diff(
+                            contents,
+                            &prev_attrs,
+                            color_depth,
+                        );
                         prev_attrs = *attrs;
                     }
 
@@ -425,7 +540,11 @@ impl Row {
             }
             prev_pos = new_pos;
             if &prev_attrs != attrs {
-                attrs.write_escape_code_diff(contents, &prev_attrs);
+                attrs.write_escape_code_diff(
+                    contents,
+                    &prev_attrs,
+                    color_depth,
+                );
                 prev_attrs = *attrs;
             }
             crate::term::ClearRowForward.write_buf(contents);
@@ -461,7 +580,11 @@ impl Row {
             if end_cell.has_contents() {
                 let attrs = end_cell.attrs();
                 if &prev_attrs != attrs {
-                    attrs.write_escape_code_diff(contents, &prev_attrs);
+                    attrs.write_escape_code_diff(
+                        contents,
+                        &prev_attrs,
+                        color_depth,
+                    );
                     prev_attrs = *attrs;
                 }
                 contents.extend(end_cell.contents().as_bytes());
@@ -472,3 +595,15 @@ impl Row {
         (prev_pos, prev_attrs)
     }
 }
+
+// takes the (is_wide, is_wide_continuation) flags of each cell in a row
+pub fn valid_wide_cells(cells: impl Iterator<Item = (bool, bool)>) -> bool {
+    let mut prev_wide = false;
+    for (wide, continuation) in cells {
+        if continuation != prev_wide || (wide && continuation) {
+            return false;
+        }
+        prev_wide = wide;
+    }
+    !prev_wide
+}
@@ -9,6 +9,7 @@ const MODE_BRACKETED_PASTE: u8 = 0b0001_0000;
 
 /// The xterm mouse handling mode currently in use.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseProtocolMode {
     /// Mouse handling is disabled.
     #[default]
@@ -37,6 +38,7 @@ pub enum MouseProtocolMode {
 
 /// The encoding to use for the enabled [`MouseProtocolMode`].
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseProtocolEncoding {
     /// Default single-printable-byte encoding.
     #[default]
@@ -51,9 +53,24 @@ pub enum MouseProtocolEncoding {
 }
 
 /// Represents the overall terminal state.
+///
+/// With the `serde` feature enabled, the full state of the screen
+/// (including the scrollback and the alternate screen) can be serialized,
+/// and a deserialized screen can be passed to
+/// [`Parser::from_screen`](crate::Parser::from_screen) to continue
+/// processing input.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Screen {
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::grid::Grid::deserialize_checked")
+    )]
     grid: crate::grid::Grid,
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::grid::Grid::deserialize_checked")
+    )]
     alternate_grid: crate::grid::Grid,
 
     attrs: crate::attrs::Attrs,
@@ -62,6 +79,8 @@ pub struct Screen {
     modes: u8,
     mouse_protocol_mode: MouseProtocolMode,
     mouse_protocol_encoding: MouseProtocolEncoding,
+
+    color_depth: crate::ColorDepth,
 }
 
 impl Screen {
@@ -81,7 +100,85 @@ impl Screen {
             modes: 0,
             mouse_protocol_mode: MouseProtocolMode::default(),
             mouse_protocol_encoding: MouseProtocolEncoding::default(),
+
+            color_depth: crate::ColorDepth::default(),
+        }
+    }
+
+    pub(crate) fn write_snapshot(&self, w: &mut crate::snapshot::Writer) {
+        self.grid.write_snapshot(w);
+        self.alternate_grid.write_snapshot(w);
+        self.attrs.write_snapshot(w);
+        self.saved_attrs.write_snapshot(w);
+        w.u8(self.modes);
+        w.u8(match self.mouse_protocol_mode {
+            MouseProtocolMode::None => 0,
+            MouseProtocolMode::Press => 1,
+            MouseProtocolMode::PressRelease => 2,
+            MouseProtocolMode::ButtonMotion => 3,
+            MouseProtocolMode::AnyMotion => 4,
+        });
+        w.u8(match self.mouse_protocol_encoding {
+            MouseProtocolEncoding::Default => 0,
+            MouseProtocolEncoding::Utf8 => 1,
+            MouseProtocolEncoding::Sgr => 2,
+        });
+        w.u8(match self.color_depth {
+            crate::ColorDepth::TrueColor => 0,
+            crate::ColorDepth::Indexed256 => 1,
+            crate::ColorDepth::Indexed16 => 2,
+            crate::ColorDepth::Monochrome => 3,
+        });
+    }
+
+    pub(crate) fn read_snapshot(
+        r: &mut crate::snapshot::Reader<'_>,
+    ) -> Result<Self, crate::SnapshotError> {
+        let grid = crate::grid::Grid::read_snapshot(r)?;
+        let alternate_grid = crate::grid::Grid::read_snapshot(r)?;
+        let attrs = crate::attrs::Attrs::read_snapshot(r)?;
+        let saved_attrs = crate::attrs::Attrs::read_snapshot(r)?;
+        let modes = r.u8()?;
+        let mouse_protocol_mode = match r.u8()? {
+            0 => MouseProtocolMode::None,
+            1 => MouseProtocolMode::Press,
+            2 => MouseProtocolMode::PressRelease,
+            3 => MouseProtocolMode::ButtonMotion,
+            4 => MouseProtocolMode::AnyMotion,
+            _ => return Err(crate::SnapshotError::Corrupt),
+        };
+        let mouse_protocol_encoding = match r.u8()? {
+            0 => MouseProtocolEncoding::Default,
+            1 => MouseProtocolEncoding::Utf8,
+            2 => MouseProtocolEncoding::Sgr,
+            _ => return Err(crate::SnapshotError::Corrupt),
+        };
+        let color_depth = match r.u8()? {
+            0 => crate::ColorDepth::TrueColor,
+            1 => crate::ColorDepth::Indexed256,
+            2 => crate::ColorDepth::Indexed16,
+            3 => crate::ColorDepth::Monochrome,
+            _ => return Err(crate::SnapshotError::Corrupt),
+        };
+
+        let screen = Self {
+            grid,
+            alternate_grid,
+            attrs,
+            saved_attrs,
+            modes,
+            mouse_protocol_mode,
+            mouse_protocol_encoding,
+            color_depth,
+        };
+        // both grids are always resized together, and the grid in use must
+        // have its rows allocated
+        if screen.grid.size() != screen.alternate_grid.size()
+            || screen.grid().drawing_row(0).is_none()
+        {
+            return Err(crate::SnapshotError::Corrupt);
         }
+        Ok(screen)
     }
 
     /// Resizes the terminal.
@@ -123,6 +220,100 @@ impl Screen {
         self.grid().scrollback()
     }
 
+    /// Limits the amount of memory used by the scrollback buffer to
+    /// approximately the given number of bytes, in addition to the limit on
+    /// the number of rows given when creating the parser. The oldest rows
+    /// are discarded first. Passing `None` removes the limit.
+    ///
+    /// Rows in the scrollback buffer are stored in a compact form, so the
+    /// memory used by a row depends on how much text it contains rather
+    /// than on the width of the terminal.
+    pub fn set_scrollback_memory_limit(&mut self, bytes: Option<usize>) {
+        self.grid.set_scrollback_max_bytes(bytes);
+    }
+
+    /// Returns the limit on the memory used by the scrollback buffer, if
+    /// any.
+    ///
+    /// See [`set_scrollback_memory_limit`](Self::set_scrollback_memory_limit).
+    #[must_use]
+    pub fn scrollback_memory_limit(&self) -> Option<usize> {
+        self.grid.scrollback_max_bytes()
+    }
+
+    /// Returns the approximate number of bytes of memory currently used by
+    /// the scrollback buffer.
+    #[must_use]
+    pub fn scrollback_memory_usage(&self) -> usize {
+        self.grid.scrollback_bytes()
+    }
+
+    /// Returns which rows of the visible screen have changed since the last
+    /// call to [`clear_damage`](Self::clear_damage).
+    ///
+    /// This is a cheaper alternative to keeping a copy of the previous
+    /// screen and calling [`contents_diff`](Self::contents_diff), intended
+    /// for renderers which redraw the screen incrementally. Rows may be
+    /// reported as changed even if their contents ended up the same as
+    /// before, and changes which don't affect the contents of any rows
+    /// (such as moving the cursor) are not tracked. Resizing the screen,
+    /// scrolling back, or switching to or from the alternate screen causes
+    /// every row to be reported.
+    #[must_use]
+    pub fn damage(&self) -> crate::Damage {
+        self.grid().damage()
+    }
+
+    /// Resets damage tracking, so that [`damage`](Self::damage) only
+    /// reports changes made after this point.
+    pub fn clear_damage(&mut self) {
+        self.grid_mut().clear_damage();
+    }
+
+    // whether the visible rows and everything else other than the contents
+    // of the inactive grid and the scrollback are the same as in the other
+    // screen. this is cheap when one screen is a clone of the other, since
+    // rows which are still shared between them are compared without
+    // looking at their cells.
+    pub(crate) fn same_visible(&self, other: &Self) -> bool {
+        self.same_state(other)
+            && self
+                .grid()
+                .visible_rows()
+                .zip(other.grid().visible_rows())
+                .all(|(row, other_row)| row.same_contents(other_row))
+    }
+
+    // whether everything other than the contents of the visible rows is the
+    // same as in the other screen
+    fn same_state(&self, other: &Self) -> bool {
+        self.modes == other.modes
+            && self.grid().same_state(other.grid())
+            && self.attrs == other.attrs
+            && self.saved_attrs == other.saved_attrs
+            && self.mouse_protocol_mode == other.mouse_protocol_mode
+            && self.mouse_protocol_encoding == other.mouse_protocol_encoding
+            && self.color_depth == other.color_depth
+    }
+
+    /// Sets the set of colors that the terminal receiving formatted output
+    /// supports.
+    ///
+    /// This affects the escape sequences generated by methods like
+    /// [`contents_formatted`](Self::contents_formatted) and
+    /// [`contents_diff`](Self::contents_diff): colors which are not
+    /// supported will be replaced by the nearest supported color. It does
+    /// not affect the colors reported for individual cells.
+    pub fn set_color_depth(&mut self, color_depth: crate::ColorDepth) {
+        self.color_depth = color_depth;
+    }
+
+    /// Returns the set of colors that formatted output is generated for.
+    #[must_use]
+    pub fn color_depth(&self) -> crate::ColorDepth {
+        self.color_depth
+    }
+
     /// Returns the text contents of the terminal.
     ///
     /// This will not include any formatting information, and will be in plain
@@ -254,8 +445,14 @@ impl Screen {
 
     fn write_contents_formatted(&self, contents: &mut Vec<u8>) {
         crate::term::HideCursor::new(self.hide_cursor()).write_buf(contents);
-        let prev_attrs = self.grid().write_contents_formatted(contents);
-        self.attrs.write_escape_code_diff(contents, &prev_attrs);
+        let prev_attrs = self
+            .grid()
+            .write_contents_formatted(contents, self.color_depth);
+        self.attrs.write_escape_code_diff(
+            contents,
+            &prev_attrs,
+            self.color_depth,
+        );
     }
 
     /// Returns the formatted visible contents of the terminal by row,
@@ -289,6 +486,7 @@ impl Screen {
                 wrapping,
                 None,
                 None,
+                self.color_depth,
             );
             if start == 0 && width == self.grid.size().cols {
                 wrapping = row.wrapped();
@@ -323,8 +521,82 @@ impl Screen {
             contents,
             prev.grid(),
             prev.attrs,
+            self.color_depth,
         );
-        self.attrs.write_escape_code_diff(contents, &prev_attrs);
+        self.attrs.write_escape_code_diff(
+            contents,
+            &prev_attrs,
+            self.color_depth,
+        );
+    }
+
+    /// Returns the changes needed to turn the visible state of `prev` into
+    /// the visible state of `self`.
+    ///
+    /// This describes the same differences as
+    /// [`contents_diff`](Self::contents_diff) and
+    /// [`input_mode_diff`](Self::input_mode_diff), but as a list of
+    /// [`Change`](crate::Change) values rather than as terminal escape
+    /// sequences, so that it can be used to update renderers which aren't
+    /// terminals. Changed cells are reported as runs of consecutive cells
+    /// within a row, followed by whether the row wraps if that changed. If
+    /// rows have moved up or down (such as when the screen scrolled), a
+    /// [`Change::Scroll`](crate::Change::Scroll) is reported first where
+    /// [`contents_diff`](Self::contents_diff) would scroll them, and the
+    /// cells are compared against the scrolled rows. If the size of the
+    /// screen differs from `prev`, a
+    /// [`Change::Resize`](crate::Change::Resize) is reported first
+    /// instead, and the cells are compared against a blank screen.
+    #[must_use]
+    pub fn changes(&self, prev: &Self) -> Vec<crate::Change> {
+        let mut changes = vec![];
+        let (rows, cols) = self.size();
+        let resized = self.size() != prev.size();
+        if resized {
+            changes.push(crate::Change::Resize { rows, cols });
+        }
+
+        if resized {
+            let mut blank = crate::grid::Grid::new(self.grid().size(), 0);
+            blank.allocate_rows();
+            self.grid().changes(&blank, &mut changes);
+        } else {
+            self.grid().changes(prev.grid(), &mut changes);
+        }
+
+        let (row, col) = self.cursor_position();
+        if resized || (row, col) != prev.cursor_position() {
+            changes.push(crate::Change::CursorPosition { row, col });
+        }
+        if self.hide_cursor() != prev.hide_cursor() {
+            changes.push(crate::Change::HideCursor(self.hide_cursor()));
+        }
+        if self.application_keypad() != prev.application_keypad() {
+            changes.push(crate::Change::ApplicationKeypad(
+                self.application_keypad(),
+            ));
+        }
+        if self.application_cursor() != prev.application_cursor() {
+            changes.push(crate::Change::ApplicationCursor(
+                self.application_cursor(),
+            ));
+        }
+        if self.bracketed_paste() != prev.bracketed_paste() {
+            changes
+                .push(crate::Change::BracketedPaste(self.bracketed_paste()));
+        }
+        if self.mouse_protocol_mode != prev.mouse_protocol_mode {
+            changes.push(crate::Change::MouseProtocolMode(
+                self.mouse_protocol_mode,
+            ));
+        }
+        if self.mouse_protocol_encoding != prev.mouse_protocol_encoding {
+            changes.push(crate::Change::MouseProtocolEncoding(
+                self.mouse_protocol_encoding,
+            ));
+        }
+
+        changes
     }
 
     /// Returns a sequence of terminal byte streams sufficient to turn the
@@ -362,11 +634,136 @@ impl Screen {
                     false,
                     crate::grid::Pos { row: i, col: start },
                     crate::attrs::Attrs::default(),
+                    self.color_depth,
                 );
                 contents
             })
     }
 
+    /// Returns the visible contents of the terminal as a standalone HTML
+    /// document.
+    ///
+    /// Colors are resolved to RGB values using `palette`, and runs of
+    /// adjacent cells with the same appearance are combined into a single
+    /// styled `<span>`. Trailing blank cells and rows are not included.
+    #[must_use]
+    pub fn contents_html(&self, palette: &crate::Palette) -> String {
+        let mut contents = String::new();
+        crate::html::write_html(
+            &mut contents,
+            self.grid().visible_rows(),
+            palette,
+        );
+        contents
+    }
+
+    /// Returns the entire contents of the terminal, including all of the
+    /// rows in the scrollback buffer, as a standalone HTML document.
+    ///
+    /// See [`contents_html`](Self::contents_html) for details about the
+    /// output format.
+    #[must_use]
+    pub fn scrollback_html(&self, palette: &crate::Palette) -> String {
+        let mut contents = String::new();
+        crate::html::write_html(
+            &mut contents,
+            self.grid().all_rows(),
+            palette,
+        );
+        contents
+    }
+
+    /// Returns a human readable text rendering of the visible contents of
+    /// the terminal, intended to be stored as a snapshot for tests (see
+    /// [`assert_screen_snapshot`](crate::assert_screen_snapshot())).
+    ///
+    /// The rendering starts with the size of the screen and the cursor
+    /// position, followed by the contents of each row inside a border. The
+    /// cursor is marked by a `v` in the top border and a `>` in the left
+    /// border, and rows which wrap onto the next row end in `\\` rather than
+    /// `|`. If any cells have colors or other attributes, a grid of the same
+    /// size follows, where each distinct set of attributes is labeled by a
+    /// letter or digit, followed by a legend describing each label. If
+    /// there are more distinct sets of attributes than letters and digits,
+    /// every label is made of the same number of characters instead, so
+    /// each column of the grid is that many characters wide.
+    ///
+    /// This method is only available when the `testing` feature is
+    /// enabled.
+    #[cfg(feature = "testing")]
+    #[must_use]
+    pub fn contents_snapshot(&self) -> String {
+        let mut contents = String::new();
+        crate::testing::write_snapshot(
+            &mut contents,
+            self.grid().visible_rows(),
+            self.grid().size(),
+            self.grid().pos(),
+            self.hide_cursor(),
+        );
+        contents
+    }
+
+    /// Returns the visible contents of the terminal rendered as an SVG
+    /// image.
+    ///
+    /// Each cell is drawn as a fixed size rectangle in a monospace font,
+    /// with colors resolved to RGB values using `palette`. The cursor is
+    /// drawn as a translucent block unless it is hidden (see
+    /// [`hide_cursor`](Self::hide_cursor)) or the screen is scrolled back.
+    /// The output only depends on the screen contents, so it is suitable
+    /// for comparing against saved snapshots.
+    #[must_use]
+    pub fn contents_svg(&self, palette: &crate::Palette) -> String {
+        let mut contents = String::new();
+        let cursor = if self.hide_cursor() || self.scrollback() > 0 {
+            None
+        } else {
+            Some(self.grid().pos())
+        };
+        crate::svg::write_svg(
+            &mut contents,
+            self.grid().visible_rows(),
+            self.grid().size(),
+            cursor,
+            palette,
+        );
+        contents
+    }
+
+    /// Writes the visible contents of the terminal to `w` as a PNG image.
+    ///
+    /// Unlike [`contents_svg`](Self::contents_svg), this doesn't depend on
+    /// any fonts being available: text is rasterized using a built-in
+    /// 8x13 pixel bitmap font, with a placeholder box drawn for characters
+    /// that the font doesn't include. Colors are resolved using `palette`,
+    /// and the cursor is drawn in the same way as for `contents_svg`.
+    ///
+    /// This method is only available when the `png` feature is enabled.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `w` fails, or if the screen has no
+    /// rows or columns.
+    #[cfg(feature = "png")]
+    pub fn write_png<W: std::io::Write>(
+        &self,
+        palette: &crate::Palette,
+        w: W,
+    ) -> std::io::Result<()> {
+        let cursor = if self.hide_cursor() || self.scrollback() > 0 {
+            None
+        } else {
+            Some(self.grid().pos())
+        };
+        crate::raster::write_png(
+            w,
+            self.grid().visible_rows(),
+            self.grid().size(),
+            cursor,
+            palette,
+        )
+    }
+
     /// Returns terminal escape sequences sufficient to set the current
     /// terminal's input modes.
     ///
@@ -479,6 +876,7 @@ impl Screen {
         self.attrs.write_escape_code_diff(
             contents,
             &crate::attrs::Attrs::default(),
+            self.color_depth,
         );
     }
 
@@ -517,8 +915,12 @@ impl Screen {
 
     fn write_cursor_state_formatted(&self, contents: &mut Vec<u8>) {
         crate::term::HideCursor::new(self.hide_cursor()).write_buf(contents);
-        self.grid()
-            .write_cursor_position_formatted(contents, None, None);
+        self.grid().write_cursor_position_formatted(
+            contents,
+            None,
+            None,
+            self.color_depth,
+        );
 
         // we don't just call write_attributes_formatted here, because that
         // would still be confusing - consider the case where the user sets
@@ -632,6 +1034,151 @@ impl Screen {
         self.attrs.inverse()
     }
 
+    /// Moves the cursor to the given position.
+    ///
+    /// The position is clamped to the size of the screen. Unlike the
+    /// equivalent escape sequence, the position is always relative to the
+    /// top left corner of the screen, even if origin mode is enabled.
+    pub fn set_cursor_position(&mut self, row: u16, col: u16) {
+        self.grid_mut().row_set(row);
+        self.grid_mut().col_set(col);
+    }
+
+    /// Sets the foreground color used for newly drawn text.
+    pub fn set_fgcolor(&mut self, fgcolor: crate::Color) {
+        self.attrs.fgcolor = fgcolor;
+    }
+
+    /// Sets the background color used for newly drawn text and erased
+    /// cells.
+    pub fn set_bgcolor(&mut self, bgcolor: crate::Color) {
+        self.attrs.bgcolor = bgcolor;
+    }
+
+    /// Sets whether newly drawn text is bold. Bold and dim are mutually
+    /// exclusive, so this also turns off dim text.
+    pub fn set_bold(&mut self, bold: bool) {
+        if bold {
+            self.attrs.set_bold();
+        } else if self.attrs.bold() {
+            self.attrs.set_normal_intensity();
+        }
+    }
+
+    /// Sets whether newly drawn text is dim. Bold and dim are mutually
+    /// exclusive, so this also turns off bold text.
+    pub fn set_dim(&mut self, dim: bool) {
+        if dim {
+            self.attrs.set_dim();
+        } else if self.attrs.dim() {
+            self.attrs.set_normal_intensity();
+        }
+    }
+
+    /// Sets whether newly drawn text is italic.
+    pub fn set_italic(&mut self, italic: bool) {
+        self.attrs.set_italic(italic);
+    }
+
+    /// Sets whether newly drawn text is underlined.
+    pub fn set_underline(&mut self, underline: bool) {
+        self.attrs.set_underline(underline);
+    }
+
+    /// Sets whether newly drawn text uses inverse colors.
+    pub fn set_inverse(&mut self, inverse: bool) {
+        self.attrs.set_inverse(inverse);
+    }
+
+    /// Resets all of the drawing attributes to their defaults.
+    pub fn reset_attributes(&mut self) {
+        self.attrs = crate::attrs::Attrs::default();
+    }
+
+    /// Draws text at the cursor position with the current drawing
+    /// attributes, advancing the cursor.
+    ///
+    /// This behaves the same as processing the text with a
+    /// [`Parser`](crate::Parser): text wraps at the end of a row, scrolling
+    /// the screen if necessary, and wide and combining characters are
+    /// handled the same way. Control characters (including newlines) are
+    /// ignored.
+    pub fn draw_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.text(c);
+        }
+    }
+
+    /// Fills a rectangle with the character `c`, using the current drawing
+    /// attributes.
+    ///
+    /// The rectangle starts at `row` and `col` and is clipped to the size
+    /// of the screen. If `c` is a wide character, any column left over at
+    /// the right edge of the rectangle is erased instead. The cursor
+    /// position is unchanged.
+    // the unwrap in this method shouldn't be reachable
+    #[allow(clippy::missing_panics_doc)]
+    pub fn fill_rect(
+        &mut self,
+        row: u16,
+        col: u16,
+        rows: u16,
+        cols: u16,
+        c: char,
+    ) {
+        let width = c.width().unwrap_or(1);
+        // width() can only return 0, 1, or 2
+        let width: u16 = width.try_into().unwrap();
+        if width == 0 {
+            return;
+        }
+        self.for_each_rect_row(row, col, rows, cols, |screen, cols| {
+            for _ in 0..cols / width {
+                screen.text(c);
+            }
+            let attrs = screen.attrs;
+            screen.grid_mut().erase_cells(cols % width, attrs);
+        });
+    }
+
+    /// Erases a rectangle, leaving its cells empty with the current
+    /// background color.
+    ///
+    /// The rectangle starts at `row` and `col` and is clipped to the size
+    /// of the screen. Wide characters which are partially inside the
+    /// rectangle are erased entirely. The cursor position is unchanged.
+    pub fn erase_rect(&mut self, row: u16, col: u16, rows: u16, cols: u16) {
+        self.for_each_rect_row(row, col, rows, cols, |screen, cols| {
+            let attrs = screen.attrs;
+            screen.grid_mut().erase_cells(cols, attrs);
+        });
+    }
+
+    // calls f with the cursor at the start of each row of the rectangle
+    // (clipped to the screen), along with the width of the clipped row,
+    // and then restores the cursor position
+    fn for_each_rect_row(
+        &mut self,
+        row: u16,
+        col: u16,
+        rows: u16,
+        cols: u16,
+        mut f: impl FnMut(&mut Self, u16),
+    ) {
+        let size = self.grid().size();
+        let pos = self.grid().pos();
+        let rows = rows.min(size.rows.saturating_sub(row));
+        let cols = cols.min(size.cols.saturating_sub(col));
+        if cols > 0 {
+            for i in 0..rows {
+                self.grid_mut().row_set(row + i);
+                self.grid_mut().col_set(col);
+                f(self, cols);
+            }
+        }
+        self.grid_mut().restore_pos(pos);
+    }
+
     pub(crate) fn grid(&self) -> &crate::grid::Grid {
         if self.mode(MODE_ALTERNATE_SCREEN) {
             &self.alternate_grid
@@ -652,10 +1199,12 @@ impl Screen {
         self.grid_mut().set_scrollback(0);
         self.set_mode(MODE_ALTERNATE_SCREEN);
         self.alternate_grid.allocate_rows();
+        self.alternate_grid.damage_all();
     }
 
     fn exit_alternate_grid(&mut self) {
         self.clear_mode(MODE_ALTERNATE_SCREEN);
+        self.grid.damage_all();
     }
 
     fn save_cursor(&mut self) {
@@ -701,7 +1250,90 @@ impl Screen {
     }
 }
 
+// used by the compositor to build up the composited screen
+impl Screen {
+    pub(crate) fn blank(rows: u16, cols: u16) -> Self {
+        Self::new(crate::grid::Size { rows, cols }, 0)
+    }
+
+    // overwrites a single cell, clearing the other half of any wide
+    // character that it overlaps. wide cells must be followed by a call to
+    // put_cell for their continuation. rows are left untouched if the cell
+    // is already the same, so that they stay shared with any clones.
+    pub(crate) fn put_cell(
+        &mut self,
+        pos: crate::grid::Pos,
+        cell: &crate::Cell,
+    ) {
+        match self
+            .grid()
+            .drawing_row(pos.row)
+            .and_then(|r| r.get(pos.col))
+        {
+            Some(current) if current != cell => {}
+            _ => return,
+        }
+        // we checked that the row exists above
+        let row = self.grid_mut().drawing_row_mut(pos.row).unwrap();
+        row.clear_wide(pos.col);
+        // we checked that the cell exists above
+        *row.get_mut(pos.col).unwrap() = cell.clone();
+    }
+
+    // copies the cursor position and visibility and the input modes from
+    // another screen, with the cursor offset by the given position
+    pub(crate) fn copy_cursor_state(
+        &mut self,
+        other: &Self,
+        pos: Option<crate::grid::Pos>,
+    ) {
+        self.modes = other.modes & !MODE_ALTERNATE_SCREEN;
+        self.mouse_protocol_mode = other.mouse_protocol_mode;
+        self.mouse_protocol_encoding = other.mouse_protocol_encoding;
+        if let Some(pos) = pos {
+            self.grid_mut().set_pos(pos);
+        } else {
+            self.set_mode(MODE_HIDE_CURSOR);
+        }
+    }
+
+    // hides the cursor and resets the input modes
+    pub(crate) fn clear_cursor_state(&mut self) {
+        self.modes = MODE_HIDE_CURSOR;
+        self.mouse_protocol_mode = MouseProtocolMode::default();
+        self.mouse_protocol_encoding = MouseProtocolEncoding::default();
+    }
+}
+
 impl Screen {
+    // equivalent to calling text() for each byte, but only valid for
+    // printable ascii characters
+    pub(crate) fn text_ascii(&mut self, mut text: &[u8]) {
+        let attrs = self.attrs;
+        while let Some((&c, rest)) = text.split_first() {
+            let pos = self.grid().pos();
+            let size = self.grid().size();
+            // wrapping is rare enough that it's not worth duplicating here
+            if pos.col >= size.cols {
+                self.text(char::from(c));
+                text = rest;
+                continue;
+            }
+
+            let len = (size.cols - pos.col).min(
+                // if the text doesn't fit in a u16, it is definitely longer
+                // than the remaining space in the row
+                text.len().try_into().unwrap_or(u16::MAX),
+            );
+            let (run, rest) = text.split_at(usize::from(len));
+            self.grid_mut()
+                .current_row_mut()
+                .write_ascii(pos.col, run, attrs);
+            self.grid_mut().col_inc(len);
+            text = rest;
+        }
+    }
+
     pub(crate) fn text(&mut self, c: char) {
         let pos = self.grid().pos();
         let size = self.grid().size();
@@ -996,7 +1628,11 @@ impl Screen {
 
     // ESC c
     pub(crate) fn ris(&mut self) {
+        let color_depth = self.color_depth;
+        let scrollback_max_bytes = self.grid.scrollback_max_bytes();
         *self = Self::new(self.grid.size(), self.grid.scrollback_len());
+        self.color_depth = color_depth;
+        self.grid.set_scrollback_max_bytes(scrollback_max_bytes);
     }
 
     // csi codes
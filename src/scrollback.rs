@@ -0,0 +1,114 @@
+// the number of rows in each chunk of the scrollback
+const CHUNK_LEN: usize = 64;
+
+// the rows in the scrollback, oldest first. rows are stored in fixed size
+// chunks which are shared between clones, so that adding a row to the
+// scrollback of a grid which was recently cloned only needs to copy the
+// last chunk rather than every row. every chunk except the last one is
+// full.
+#[derive(Clone, Debug, Default)]
+pub struct Scrollback {
+    chunks: std::collections::VecDeque<
+        std::sync::Arc<Vec<std::sync::Arc<crate::compact::CompactRow>>>,
+    >,
+    // the number of rows which have been removed from the start of the
+    // first chunk. removed rows are only freed once the rest of their
+    // chunk is also removed, so that removing a row doesn't require
+    // copying a shared chunk.
+    start: usize,
+    len: usize,
+}
+
+impl Scrollback {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn get(
+        &self,
+        i: usize,
+    ) -> Option<&std::sync::Arc<crate::compact::CompactRow>> {
+        if i >= self.len {
+            return None;
+        }
+        let i = i + self.start;
+        self.chunks[i / CHUNK_LEN].get(i % CHUNK_LEN)
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = &std::sync::Arc<crate::compact::CompactRow>>
+    {
+        self.chunks
+            .iter()
+            .flat_map(|chunk| chunk.iter())
+            .skip(self.start)
+    }
+
+    pub fn range(
+        &self,
+        range: std::ops::Range<usize>,
+    ) -> impl Iterator<Item = &std::sync::Arc<crate::compact::CompactRow>>
+    {
+        // the range is always within the scrollback
+        range.map(|i| self.get(i).unwrap())
+    }
+
+    pub fn push_back(
+        &mut self,
+        row: std::sync::Arc<crate::compact::CompactRow>,
+    ) {
+        if self
+            .chunks
+            .back()
+            .map_or(true, |chunk| chunk.len() == CHUNK_LEN)
+        {
+            self.chunks
+                .push_back(std::sync::Arc::new(Vec::with_capacity(
+                    CHUNK_LEN,
+                )));
+        }
+        // we just made sure that there is a chunk with space in it
+        std::sync::Arc::make_mut(self.chunks.back_mut().unwrap()).push(row);
+        self.len += 1;
+    }
+
+    pub fn pop_front(
+        &mut self,
+    ) -> Option<std::sync::Arc<crate::compact::CompactRow>> {
+        let row = std::sync::Arc::clone(self.get(0)?);
+        self.start += 1;
+        self.len -= 1;
+        // the row exists, so the first chunk does too
+        if self.start == self.chunks.front().unwrap().len() {
+            self.chunks.pop_front();
+            self.start = 0;
+        }
+        Some(row)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Scrollback {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Scrollback {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let rows: Vec<std::sync::Arc<crate::compact::CompactRow>> =
+            serde::Deserialize::deserialize(deserializer)?;
+        let mut scrollback = Self::default();
+        for row in rows {
+            scrollback.push_back(row);
+        }
+        Ok(scrollback)
+    }
+}
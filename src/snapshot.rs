@@ -0,0 +1,186 @@
+// the snapshot format starts with MAGIC followed by the format version.
+// VERSION must be incremented whenever the format changes, and snapshots
+// written with older versions must continue to be readable.
+const MAGIC: &[u8] = b"vt100snp";
+const VERSION: u32 = 1;
+
+/// An error encountered when loading a snapshot with
+/// [`Parser::load_snapshot`](crate::Parser::load_snapshot).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SnapshotError {
+    /// The data does not contain a snapshot.
+    NotASnapshot,
+
+    /// The snapshot was written by a newer version of this crate using a
+    /// format version which this version doesn't understand.
+    UnsupportedVersion(u32),
+
+    /// The snapshot is truncated or otherwise corrupted.
+    Corrupt,
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotASnapshot => f.write_str("data is not a snapshot"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version {version}")
+            }
+            Self::Corrupt => f.write_str("snapshot is corrupt"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+pub struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        let mut writer = Self {
+            buf: MAGIC.to_vec(),
+        };
+        writer.uint(VERSION);
+        writer
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    pub fn u8(&mut self, n: u8) {
+        self.buf.push(n);
+    }
+
+    pub fn bool(&mut self, b: bool) {
+        self.u8(u8::from(b));
+    }
+
+    // unsigned integers are stored as LEB128 varints
+    pub fn uint<N: Into<u64>>(&mut self, n: N) {
+        let mut n = n.into();
+        loop {
+            // the low seven bits always fit in a u8
+            let byte = u8::try_from(n & 0x7f).unwrap();
+            n >>= 7;
+            if n == 0 {
+                self.buf.push(byte);
+                break;
+            }
+            self.buf.push(byte | 0x80);
+        }
+    }
+
+    pub fn usize(&mut self, n: usize) {
+        // usize is never larger than 64 bits on supported platforms
+        self.uint(u64::try_from(n).unwrap());
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.usize(bytes.len());
+        self.buf.extend_from_slice(bytes);
+    }
+}
+
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, SnapshotError> {
+        let data = data
+            .strip_prefix(MAGIC)
+            .ok_or(SnapshotError::NotASnapshot)?;
+        let mut reader = Self { data };
+        let version = reader.uint()?;
+        if version != u64::from(VERSION) {
+            return Err(SnapshotError::UnsupportedVersion(
+                version.try_into().unwrap_or(u32::MAX),
+            ));
+        }
+        Ok(reader)
+    }
+
+    pub fn finish(self) -> Result<(), SnapshotError> {
+        if self.data.is_empty() {
+            Ok(())
+        } else {
+            Err(SnapshotError::Corrupt)
+        }
+    }
+
+    pub fn u8(&mut self) -> Result<u8, SnapshotError> {
+        let (&n, rest) =
+            self.data.split_first().ok_or(SnapshotError::Corrupt)?;
+        self.data = rest;
+        Ok(n)
+    }
+
+    pub fn bool(&mut self) -> Result<bool, SnapshotError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SnapshotError::Corrupt),
+        }
+    }
+
+    pub fn uint(&mut self) -> Result<u64, SnapshotError> {
+        let mut n = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(SnapshotError::Corrupt)
+    }
+
+    pub fn u16(&mut self) -> Result<u16, SnapshotError> {
+        self.uint()?.try_into().map_err(|_| SnapshotError::Corrupt)
+    }
+
+    pub fn usize(&mut self) -> Result<usize, SnapshotError> {
+        self.uint()?.try_into().map_err(|_| SnapshotError::Corrupt)
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], SnapshotError> {
+        let len = self.usize()?;
+        if len > self.data.len() {
+            return Err(SnapshotError::Corrupt);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+}
+
+// a vte::Perform implementation which ignores everything, used to restore
+// the state of a partially processed escape sequence
+pub struct Discard;
+
+impl vte::Perform for Discard {}
+
+// returns the trailing bytes of text which could be an incomplete utf8
+// encoded character
+pub fn incomplete_utf8_suffix(bytes: &[u8]) -> &[u8] {
+    for (len, &byte) in bytes.iter().rev().take(3).enumerate() {
+        let len = len + 1;
+        if byte & 0xc0 == 0x80 {
+            continue;
+        }
+        let char_len = match byte {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xff => 4,
+            _ => 1,
+        };
+        if char_len > len {
+            return &bytes[bytes.len() - len..];
+        }
+        break;
+    }
+    &[]
+}
@@ -0,0 +1,210 @@
+const CELL_WIDTH: u16 = 8;
+const CELL_HEIGHT: u16 = 16;
+const FONT_SIZE: u16 = 14;
+const BASELINE: u16 = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct TextStyle {
+    fgcolor: (u8, u8, u8),
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    wide: bool,
+}
+
+pub fn write_svg<'a>(
+    contents: &mut String,
+    rows: impl Iterator<Item = &'a crate::row::Row>,
+    size: crate::grid::Size,
+    cursor: Option<crate::grid::Pos>,
+    palette: &crate::Palette,
+) {
+    let width = u32::from(size.cols) * u32::from(CELL_WIDTH);
+    let height = u32::from(size.rows) * u32::from(CELL_HEIGHT);
+
+    contents.push_str("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"");
+    push_num(contents, width);
+    contents.push_str("\" height=\"");
+    push_num(contents, height);
+    contents.push_str("\" viewBox=\"0 0 ");
+    push_num(contents, width);
+    contents.push(' ');
+    push_num(contents, height);
+    contents.push_str("\" font-family=\"monospace\" font-size=\"");
+    push_num(contents, FONT_SIZE);
+    contents.push_str("\">\n");
+
+    contents.push_str("<rect width=\"100%\" height=\"100%\" fill=\"");
+    crate::html::push_rgb(contents, palette.default_bgcolor());
+    contents.push_str("\"/>\n");
+
+    for (i, row) in rows.enumerate() {
+        // the number of rows in a grid is stored in a u16 (see Size)
+        let i: u16 = i.try_into().unwrap();
+        write_row_backgrounds(contents, row, i, palette);
+        write_row_text(contents, row, i, palette);
+    }
+
+    // there is nowhere to draw the cursor if the screen has no columns
+    if let (Some(pos), Some(last_col)) = (cursor, size.cols.checked_sub(1)) {
+        let col = pos.col.min(last_col);
+        contents.push_str("<rect x=\"");
+        push_num(contents, u32::from(col) * u32::from(CELL_WIDTH));
+        contents.push_str("\" y=\"");
+        push_num(contents, u32::from(pos.row) * u32::from(CELL_HEIGHT));
+        contents.push_str("\" width=\"");
+        push_num(contents, CELL_WIDTH);
+        contents.push_str("\" height=\"");
+        push_num(contents, CELL_HEIGHT);
+        contents.push_str("\" fill=\"");
+        crate::html::push_rgb(contents, palette.default_fgcolor());
+        contents.push_str("\" fill-opacity=\"0.5\"/>\n");
+    }
+
+    contents.push_str("</svg>\n");
+}
+
+fn write_row_backgrounds(
+    contents: &mut String,
+    row: &crate::row::Row,
+    i: u16,
+    palette: &crate::Palette,
+) {
+    let mut run: Option<(u16, u16, (u8, u8, u8))> = None;
+    // wide continuation cells don't have their own attributes, so they
+    // should be drawn with the same background as the cell before them
+    let bgcolors = row.cells().scan(None, |prev, cell| {
+        let bgcolor = match (cell.is_wide_continuation(), *prev) {
+            (true, Some(bgcolor)) => bgcolor,
+            _ => palette.resolve(cell, false).1,
+        };
+        *prev = Some(bgcolor);
+        Some(bgcolor)
+    });
+    for (col, bgcolor) in (0..).zip(bgcolors.map(Some).chain([None])) {
+        if let Some((start, len, run_bgcolor)) = run {
+            if bgcolor == Some(run_bgcolor) {
+                run = Some((start, len + 1, run_bgcolor));
+                continue;
+            }
+            contents.push_str("<rect x=\"");
+            push_num(contents, u32::from(start) * u32::from(CELL_WIDTH));
+            contents.push_str("\" y=\"");
+            push_num(contents, u32::from(i) * u32::from(CELL_HEIGHT));
+            contents.push_str("\" width=\"");
+            push_num(contents, u32::from(len) * u32::from(CELL_WIDTH));
+            contents.push_str("\" height=\"");
+            push_num(contents, CELL_HEIGHT);
+            contents.push_str("\" fill=\"");
+            crate::html::push_rgb(contents, run_bgcolor);
+            contents.push_str("\"/>\n");
+            run = None;
+        }
+        if let Some(bgcolor) = bgcolor {
+            if bgcolor != palette.default_bgcolor() {
+                run = Some((col, 1, bgcolor));
+            }
+        }
+    }
+}
+
+fn write_row_text(
+    contents: &mut String,
+    row: &crate::row::Row,
+    i: u16,
+    palette: &crate::Palette,
+) {
+    // (start col, width in cols, style, text)
+    let mut run: Option<(u16, u16, TextStyle, String)> = None;
+    // blank cells are only included in a run if there is more text after
+    // them in the same style
+    let mut blanks = 0;
+    for (col, cell) in (0..).zip(row.cells()) {
+        if cell.is_wide_continuation() {
+            continue;
+        }
+        if matches!(cell.contents(), "" | " ") && !cell.underline() {
+            if run.is_some() {
+                blanks += 1;
+            }
+            continue;
+        }
+
+        let style = TextStyle {
+            fgcolor: palette.resolve(cell, false).0,
+            bold: cell.bold(),
+            italic: cell.italic(),
+            underline: cell.underline(),
+            wide: cell.is_wide(),
+        };
+        let width = if cell.is_wide() { 2 } else { 1 };
+        if let Some((_, len, run_style, text)) = &mut run {
+            if *run_style == style && (blanks == 0 || !style.wide) {
+                for _ in 0..blanks {
+                    text.push(' ');
+                }
+                *len += blanks + width;
+                blanks = 0;
+                push_cell_text(text, cell);
+                continue;
+            }
+        }
+        if let Some((start, len, run_style, text)) = run.take() {
+            write_text(contents, start, len, i, run_style, &text);
+        }
+        let mut text = String::new();
+        push_cell_text(&mut text, cell);
+        run = Some((col, width, style, text));
+        blanks = 0;
+    }
+    if let Some((start, len, run_style, text)) = run {
+        write_text(contents, start, len, i, run_style, &text);
+    }
+}
+
+fn write_text(
+    contents: &mut String,
+    start: u16,
+    len: u16,
+    i: u16,
+    style: TextStyle,
+    text: &str,
+) {
+    contents.push_str("<text x=\"");
+    push_num(contents, u32::from(start) * u32::from(CELL_WIDTH));
+    contents.push_str("\" y=\"");
+    push_num(
+        contents,
+        u32::from(i) * u32::from(CELL_HEIGHT) + u32::from(BASELINE),
+    );
+    contents.push_str("\" textLength=\"");
+    push_num(contents, u32::from(len) * u32::from(CELL_WIDTH));
+    contents.push_str("\" lengthAdjust=\"spacingAndGlyphs\" fill=\"");
+    crate::html::push_rgb(contents, style.fgcolor);
+    contents.push('"');
+    if style.bold {
+        contents.push_str(" font-weight=\"bold\"");
+    }
+    if style.italic {
+        contents.push_str(" font-style=\"italic\"");
+    }
+    if style.underline {
+        contents.push_str(" text-decoration=\"underline\"");
+    }
+    contents.push_str(" xml:space=\"preserve\">");
+    crate::html::push_escaped(contents, text);
+    contents.push_str("</text>\n");
+}
+
+fn push_cell_text(text: &mut String, cell: &crate::Cell) {
+    if cell.has_contents() {
+        text.push_str(cell.contents());
+    } else {
+        text.push(' ');
+    }
+}
+
+fn push_num<I: itoa::Integer>(contents: &mut String, i: I) {
+    let mut itoa_buf = itoa::Buffer::new();
+    contents.push_str(itoa_buf.format(i));
+}
@@ -120,6 +120,7 @@ pub struct Attrs {
     italic: Option<bool>,
     underline: Option<bool>,
     inverse: Option<bool>,
+    color_depth: crate::ColorDepth,
 }
 
 impl Attrs {
@@ -152,6 +153,11 @@ impl Attrs {
         self.inverse = Some(inverse);
         self
     }
+
+    pub fn color_depth(mut self, color_depth: crate::ColorDepth) -> Self {
+        self.color_depth = color_depth;
+        self
+    }
 }
 
 impl BufWrite for Attrs {
@@ -183,7 +189,7 @@ impl BufWrite for Attrs {
         }
 
         if let Some(fgcolor) = self.fgcolor {
-            match fgcolor {
+            match self.color_depth.convert(fgcolor) {
                 crate::Color::Default => {
                     write_param!(39);
                 }
@@ -209,7 +215,7 @@ impl BufWrite for Attrs {
         }
 
         if let Some(bgcolor) = self.bgcolor {
-            match bgcolor {
+            match self.color_depth.convert(bgcolor) {
                 crate::Color::Default => {
                     write_param!(49);
                 }
@@ -334,6 +340,167 @@ impl BufWrite for EraseChar {
     }
 }
 
+#[derive(Debug)]
+#[must_use = "this struct does nothing unless you call write_buf"]
+pub struct ScrollUp {
+    count: u16,
+}
+
+impl ScrollUp {
+    pub fn new(count: u16) -> Self {
+        Self { count }
+    }
+}
+
+impl Default for ScrollUp {
+    fn default() -> Self {
+        Self { count: 1 }
+    }
+}
+
+impl BufWrite for ScrollUp {
+    fn write_buf(&self, buf: &mut Vec<u8>) {
+        match self.count {
+            0 => {}
+            1 => buf.extend_from_slice(b"\x1b[S"),
+            n => {
+                buf.extend_from_slice(b"\x1b[");
+                extend_itoa(buf, n);
+                buf.push(b'S');
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "this struct does nothing unless you call write_buf"]
+pub struct ScrollDown {
+    count: u16,
+}
+
+impl ScrollDown {
+    pub fn new(count: u16) -> Self {
+        Self { count }
+    }
+}
+
+impl Default for ScrollDown {
+    fn default() -> Self {
+        Self { count: 1 }
+    }
+}
+
+impl BufWrite for ScrollDown {
+    fn write_buf(&self, buf: &mut Vec<u8>) {
+        match self.count {
+            0 => {}
+            1 => buf.extend_from_slice(b"\x1b[T"),
+            n => {
+                buf.extend_from_slice(b"\x1b[");
+                extend_itoa(buf, n);
+                buf.push(b'T');
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "this struct does nothing unless you call write_buf"]
+pub struct InsertLines {
+    count: u16,
+}
+
+impl InsertLines {
+    pub fn new(count: u16) -> Self {
+        Self { count }
+    }
+}
+
+impl Default for InsertLines {
+    fn default() -> Self {
+        Self { count: 1 }
+    }
+}
+
+impl BufWrite for InsertLines {
+    fn write_buf(&self, buf: &mut Vec<u8>) {
+        match self.count {
+            0 => {}
+            1 => buf.extend_from_slice(b"\x1b[L"),
+            n => {
+                buf.extend_from_slice(b"\x1b[");
+                extend_itoa(buf, n);
+                buf.push(b'L');
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "this struct does nothing unless you call write_buf"]
+pub struct DeleteLines {
+    count: u16,
+}
+
+impl DeleteLines {
+    pub fn new(count: u16) -> Self {
+        Self { count }
+    }
+}
+
+impl Default for DeleteLines {
+    fn default() -> Self {
+        Self { count: 1 }
+    }
+}
+
+impl BufWrite for DeleteLines {
+    fn write_buf(&self, buf: &mut Vec<u8>) {
+        match self.count {
+            0 => {}
+            1 => buf.extend_from_slice(b"\x1b[M"),
+            n => {
+                buf.extend_from_slice(b"\x1b[");
+                extend_itoa(buf, n);
+                buf.push(b'M');
+            }
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+#[must_use = "this struct does nothing unless you call write_buf"]
+pub struct SetScrollRegion {
+    top: u16,
+    bottom: u16,
+}
+
+impl SetScrollRegion {
+    pub fn new(top: u16, bottom: u16) -> Self {
+        Self { top, bottom }
+    }
+}
+
+impl BufWrite for SetScrollRegion {
+    fn write_buf(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(b"\x1b[");
+        extend_itoa(buf, self.top + 1);
+        buf.push(b';');
+        extend_itoa(buf, self.bottom + 1);
+        buf.push(b'r');
+    }
+}
+
+#[derive(Default, Debug)]
+#[must_use = "this struct does nothing unless you call write_buf"]
+pub struct ResetScrollRegion;
+
+impl BufWrite for ResetScrollRegion {
+    fn write_buf(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(b"\x1b[r");
+    }
+}
+
 #[derive(Default, Debug)]
 #[must_use = "this struct does nothing unless you call write_buf"]
 pub struct HideCursor {
@@ -0,0 +1,372 @@
+use std::fmt::Write as _;
+
+// the environment variable which causes assert_screen_snapshot to accept
+// the current screen as the new snapshot
+const ACCEPT_VAR: &str = "VT100_ACCEPT_SNAPSHOTS";
+
+// characters used to label attribute sets in the attribute grid. when
+// there are more attribute sets than characters, every label is made of
+// the same number of characters, so that the grid stays aligned.
+const LABELS: &[u8] =
+    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+pub fn write_snapshot<'a>(
+    contents: &mut String,
+    rows: impl Iterator<Item = &'a crate::row::Row>,
+    size: crate::grid::Size,
+    cursor: crate::grid::Pos,
+    hide_cursor: bool,
+) {
+    let rows: Vec<_> = rows.collect();
+    let cols = usize::from(size.cols);
+
+    // writing to a string can't fail
+    write!(
+        contents,
+        "size: {}x{}\ncursor: {},{}",
+        size.rows, size.cols, cursor.row, cursor.col
+    )
+    .unwrap();
+    if hide_cursor {
+        contents.push_str(" (hidden)");
+    }
+    contents.push('\n');
+
+    // the top border marks the cursor column, and the left border marks
+    // the cursor row. rows which wrap onto the next row have a different
+    // right border.
+    contents.push('+');
+    for col in 0..size.cols {
+        contents.push(if col == cursor.col { 'v' } else { '-' });
+    }
+    contents.push_str("+\n");
+    for (i, row) in rows.iter().enumerate() {
+        contents.push(if i == usize::from(cursor.row) {
+            '>'
+        } else {
+            '|'
+        });
+        for cell in row.cells() {
+            if cell.is_wide_continuation() {
+                continue;
+            }
+            if cell.has_contents() {
+                contents.push_str(cell.contents());
+            } else {
+                contents.push(' ');
+            }
+        }
+        contents.push_str(if row.wrapped() { "\\\n" } else { "|\n" });
+    }
+    push_border(contents, cols);
+
+    // the index into the legend of the attributes of each cell, if it has
+    // any
+    let mut legend: Vec<String> = vec![];
+    let mut grid: Vec<Vec<Option<usize>>> = vec![];
+    for row in &rows {
+        let mut prev = None;
+        let mut grid_row = vec![];
+        for cell in row.cells() {
+            // wide continuation cells use the attributes of the cell
+            // before them
+            if !cell.is_wide_continuation() {
+                let attrs = describe_attrs(cell);
+                prev = if attrs.is_empty() {
+                    None
+                } else {
+                    let i = legend.iter().position(|a| *a == attrs);
+                    Some(i.unwrap_or_else(|| {
+                        legend.push(attrs);
+                        legend.len() - 1
+                    }))
+                };
+            }
+            grid_row.push(prev);
+        }
+        grid.push(grid_row);
+    }
+    if !legend.is_empty() {
+        let width = label_width(legend.len());
+        contents.push_str("attributes:\n");
+        push_border(contents, cols * width);
+        for grid_row in grid {
+            contents.push('|');
+            for i in grid_row {
+                match i {
+                    Some(i) => push_label(contents, i, width),
+                    None => contents.push_str(&".".repeat(width)),
+                }
+            }
+            contents.push_str("|\n");
+        }
+        push_border(contents, cols * width);
+        for (i, attrs) in legend.iter().enumerate() {
+            push_label(contents, i, width);
+            // writing to a string can't fail
+            writeln!(contents, ": {attrs}").unwrap();
+        }
+    }
+}
+
+// the number of characters needed to give each of count attribute sets a
+// distinct label
+fn label_width(count: usize) -> usize {
+    let mut width = 1;
+    let mut labels = LABELS.len();
+    while labels < count {
+        width += 1;
+        labels = labels.saturating_mul(LABELS.len());
+    }
+    width
+}
+
+fn push_label(contents: &mut String, mut i: usize, width: usize) {
+    let mut label = vec![];
+    for _ in 0..width {
+        label.push(char::from(LABELS[i % LABELS.len()]));
+        i /= LABELS.len();
+    }
+    contents.extend(label.iter().rev());
+}
+
+fn push_border(contents: &mut String, cols: usize) {
+    contents.push('+');
+    contents.push_str(&"-".repeat(cols));
+    contents.push_str("+\n");
+}
+
+fn describe_attrs(cell: &crate::Cell) -> String {
+    let mut attrs = vec![];
+    for (name, color) in [("fg", cell.fgcolor()), ("bg", cell.bgcolor())] {
+        match color {
+            crate::Color::Default => {}
+            crate::Color::Idx(i) => attrs.push(format!("{name}={i}")),
+            crate::Color::Rgb(r, g, b) => {
+                attrs.push(format!("{name}=#{r:02x}{g:02x}{b:02x}"));
+            }
+        }
+    }
+    for (name, set) in [
+        ("bold", cell.bold()),
+        ("dim", cell.dim()),
+        ("italic", cell.italic()),
+        ("underline", cell.underline()),
+        ("inverse", cell.inverse()),
+    ] {
+        if set {
+            attrs.push(name.to_string());
+        }
+    }
+    attrs.join(" ")
+}
+
+/// Compares the screen against the text snapshot stored at `path`.
+///
+/// If they don't match, this panics with a description of the differing
+/// cells. See [`Screen::contents_snapshot`](crate::Screen::contents_snapshot)
+/// for the snapshot format.
+///
+/// If the `VT100_ACCEPT_SNAPSHOTS` environment variable is set to a value
+/// other than `0`, the snapshot is written to `path` instead (creating any
+/// missing parent directories), so that new or changed snapshots can be
+/// accepted by running the tests again.
+///
+/// The [`assert_screen_snapshot!`](crate::assert_screen_snapshot!) macro
+/// calls this with a path in a `snapshots` directory next to the test
+/// file.
+///
+/// This function is only available when the `testing` feature is enabled.
+///
+/// # Panics
+///
+/// Panics if the snapshot doesn't match or doesn't exist, or if it can't
+/// be written when accepting snapshots.
+#[track_caller]
+pub fn assert_screen_snapshot(
+    screen: &crate::Screen,
+    path: impl AsRef<std::path::Path>,
+) {
+    let path = path.as_ref();
+    let got = screen.contents_snapshot();
+    let accept = std::env::var_os(ACCEPT_VAR)
+        .is_some_and(|v| !v.is_empty() && v != "0");
+    if accept {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).unwrap_or_else(|e| {
+                panic!("failed to create {}: {e}", dir.display())
+            });
+        }
+        std::fs::write(path, &got).unwrap_or_else(|e| {
+            panic!("failed to write {}: {e}", path.display())
+        });
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected.replace("\r\n", "\n"),
+        Err(e) => panic!(
+            "failed to read snapshot {}: {e}\n\
+            (set {ACCEPT_VAR}=1 to create it)\n{got}",
+            path.display(),
+        ),
+    };
+    assert!(
+        expected == got,
+        "screen doesn't match snapshot {}\n\
+        (set {ACCEPT_VAR}=1 to accept the new snapshot)\n{}",
+        path.display(),
+        describe_diff(&expected, &got),
+    );
+}
+
+/// Compares a [`Screen`](crate::Screen) against a named text snapshot.
+///
+/// The snapshot is stored as `snapshots/<name>.snap`, next to the calling
+/// source file. See
+/// [`assert_screen_snapshot`](crate::assert_screen_snapshot()) for details.
+///
+/// This macro is only available when the `testing` feature is enabled.
+///
+/// ```no_run
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// parser.process(b"hello \x1b[1mworld");
+/// vt100::assert_screen_snapshot!(parser.screen(), "hello");
+/// ```
+#[macro_export]
+macro_rules! assert_screen_snapshot {
+    ($screen:expr, $name:expr $(,)?) => {{
+        // file!() is relative to the directory cargo was run from, which is
+        // the workspace root rather than the crate's manifest directory for
+        // crates in a workspace, so the source file is looked for relative
+        // to each ancestor of the manifest directory
+        let manifest_dir = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        let path = manifest_dir
+            .ancestors()
+            .map(|dir| dir.join(file!()))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| manifest_dir.join(file!()))
+            .with_file_name("snapshots")
+            .join(format!("{}.snap", $name));
+        $crate::assert_screen_snapshot($screen, path)
+    }};
+}
+
+// a snapshot parsed back into cells, for describing differences
+struct Parsed<'a> {
+    header: Vec<&'a str>,
+    rows: Vec<(Vec<(String, &'a str)>, bool)>,
+    legend: std::collections::HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Parsed<'a> {
+    fn new(snapshot: &'a str) -> Option<Self> {
+        let mut lines = snapshot.lines();
+        let header = vec![lines.next()?, lines.next()?];
+        lines.next()?;
+        let mut rows = vec![];
+        for line in lines.by_ref() {
+            if line.starts_with('+') {
+                break;
+            }
+            let wrapped = line.ends_with('\\');
+            let line = line.get(1..line.len().checked_sub(1)?)?;
+            let mut cells: Vec<(String, &str)> = vec![];
+            for c in line.chars() {
+                let width = unicode_width::UnicodeWidthChar::width(c);
+                match width {
+                    Some(0) | None => cells.last_mut()?.0.push(c),
+                    Some(width) => {
+                        cells.push((
+                            if c == ' ' { String::new() } else { c.into() },
+                            "",
+                        ));
+                        for _ in 1..width {
+                            cells.push((String::new(), ""));
+                        }
+                    }
+                }
+            }
+            rows.push((cells, wrapped));
+        }
+
+        let mut legend = std::collections::HashMap::new();
+        if lines.next() == Some("attributes:") {
+            lines.next()?;
+            // the legend comes after the grid, and says how many
+            // characters each label in the grid is made of
+            let grid: Vec<_> = lines.by_ref().take(rows.len()).collect();
+            lines.next()?;
+            for line in lines {
+                let (label, attrs) = line.split_once(": ")?;
+                legend.insert(label, attrs);
+            }
+            let width = legend.keys().next()?.len();
+            for ((cells, _), line) in rows.iter_mut().zip(grid) {
+                let line = line.get(1..line.len().checked_sub(1)?)?;
+                for (i, cell) in cells.iter_mut().enumerate() {
+                    cell.1 = line.get(i * width..(i + 1) * width)?;
+                }
+            }
+        }
+
+        Some(Self {
+            header,
+            rows,
+            legend,
+        })
+    }
+
+    fn describe(&self, (contents, label): &(String, &str)) -> String {
+        let attrs = self.legend.get(label).copied().unwrap_or("");
+        if attrs.is_empty() {
+            format!("{contents:?}")
+        } else {
+            format!("{contents:?} ({attrs})")
+        }
+    }
+}
+
+fn describe_diff(expected: &str, got: &str) -> String {
+    let mut diff = String::new();
+    if let (Some(e), Some(g)) = (Parsed::new(expected), Parsed::new(got)) {
+        for (e, g) in e.header.iter().zip(&g.header) {
+            if e != g {
+                // writing to a string can't fail
+                writeln!(diff, "expected {e}, got {g}").unwrap();
+            }
+        }
+        for (row, ((e_cells, e_wrapped), (g_cells, g_wrapped))) in
+            e.rows.iter().zip(&g.rows).enumerate()
+        {
+            for (col, (e_cell, g_cell)) in
+                e_cells.iter().zip(g_cells).enumerate()
+            {
+                let e_cell = e.describe(e_cell);
+                let g_cell = g.describe(g_cell);
+                if e_cell != g_cell {
+                    // writing to a string can't fail
+                    writeln!(
+                        diff,
+                        "cell ({row}, {col}): expected {e_cell}, got {g_cell}"
+                    )
+                    .unwrap();
+                }
+            }
+            if e_wrapped != g_wrapped {
+                // writing to a string can't fail
+                writeln!(
+                    diff,
+                    "row {row}: expected wrapped={e_wrapped}, \
+                    got wrapped={g_wrapped}"
+                )
+                .unwrap();
+            }
+        }
+    }
+    diff.push_str("expected:\n");
+    diff.push_str(expected);
+    diff.push_str("got:\n");
+    diff.push_str(got);
+    diff
+}
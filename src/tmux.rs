@@ -0,0 +1,592 @@
+use std::fmt::Write as _;
+
+// sent by tmux -CC before the first line of output, and after %exit
+const DCS_START: &[u8] = b"\x1bP1000p";
+const DCS_END: &[u8] = b"\x1b\\";
+
+/// The arrangement of the panes in a tmux window, as reported by
+/// `%layout-change` notifications.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TmuxLayout {
+    /// The area of the window covered by this part of the layout.
+    pub rect: crate::Rect,
+    /// What this part of the layout contains.
+    pub kind: TmuxLayoutKind,
+}
+
+/// The contents of a [`TmuxLayout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TmuxLayoutKind {
+    /// A single pane, with the given pane id.
+    Pane(u32),
+    /// Several layouts arranged from left to right.
+    LeftRight(Vec<TmuxLayout>),
+    /// Several layouts arranged from top to bottom.
+    TopBottom(Vec<TmuxLayout>),
+}
+
+impl TmuxLayout {
+    /// Returns the ids and areas of every pane in the layout.
+    #[must_use]
+    pub fn panes(&self) -> Vec<(u32, crate::Rect)> {
+        let mut panes = vec![];
+        self.push_panes(&mut panes);
+        panes
+    }
+
+    fn push_panes(&self, panes: &mut Vec<(u32, crate::Rect)>) {
+        match &self.kind {
+            TmuxLayoutKind::Pane(id) => panes.push((*id, self.rect)),
+            TmuxLayoutKind::LeftRight(children)
+            | TmuxLayoutKind::TopBottom(children) => {
+                for child in children {
+                    child.push_panes(panes);
+                }
+            }
+        }
+    }
+
+    // layouts look like "b25f,80x24,0,0,0" (a checksum followed by a
+    // single pane with id 0), or
+    // "bb62,159x48,0,0{79x48,0,0,79,79x48,80,0,80}" (a checksum followed
+    // by two panes side by side, with ids 79 and 80)
+    fn parse(s: &str) -> Option<Self> {
+        let (_checksum, s) = s.split_once(',')?;
+        let (layout, rest) = Self::parse_cell(s)?;
+        rest.is_empty().then_some(layout)
+    }
+
+    fn parse_cell(s: &str) -> Option<(Self, &str)> {
+        let (cols, s) = s.split_once('x')?;
+        let (rows, s) = s.split_once(',')?;
+        let (col, s) = s.split_once(',')?;
+        let end = s.find([',', '{', '[', '}', ']']).unwrap_or(s.len());
+        let (row, s) = s.split_at(end);
+        let rect = crate::Rect::new(
+            row.parse().ok()?,
+            col.parse().ok()?,
+            rows.parse().ok()?,
+            cols.parse().ok()?,
+        );
+
+        let (kind, s) = if let Some(s) = s.strip_prefix('{') {
+            let (children, s) = Self::parse_children(s, '}')?;
+            (TmuxLayoutKind::LeftRight(children), s)
+        } else if let Some(s) = s.strip_prefix('[') {
+            let (children, s) = Self::parse_children(s, ']')?;
+            (TmuxLayoutKind::TopBottom(children), s)
+        } else {
+            let s = s.strip_prefix(',')?;
+            let end = s.find([',', '}', ']']).unwrap_or(s.len());
+            let (id, s) = s.split_at(end);
+            (TmuxLayoutKind::Pane(id.parse().ok()?), s)
+        };
+        Some((Self { rect, kind }, s))
+    }
+
+    fn parse_children(mut s: &str, close: char) -> Option<(Vec<Self>, &str)> {
+        let mut children = vec![];
+        loop {
+            let (child, rest) = Self::parse_cell(s)?;
+            children.push(child);
+            if let Some(rest) = rest.strip_prefix(',') {
+                s = rest;
+            } else {
+                return Some((children, rest.strip_prefix(close)?));
+            }
+        }
+    }
+}
+
+/// A notification from a tmux server in control mode, as returned by
+/// [`TmuxClient::next_event`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TmuxEvent {
+    /// Output for the given pane was processed by its parser.
+    Output(u32),
+
+    /// The response to a command, either sent with
+    /// [`TmuxClient::send_command`] or run by tmux on startup. Responses
+    /// arrive in the same order that the commands were sent.
+    Response {
+        /// The lines of output from the command.
+        output: Vec<String>,
+        /// Whether the command failed.
+        error: bool,
+    },
+
+    /// The layout of the given window changed. The parsers for the panes
+    /// in the window have already been resized to match.
+    LayoutChange {
+        /// The id of the window.
+        window: u32,
+        /// The new layout of the window.
+        layout: TmuxLayout,
+    },
+
+    /// A window was added to the attached session.
+    WindowAdd(u32),
+
+    /// A window was closed. Its panes have already been removed.
+    WindowClose(u32),
+
+    /// A window was renamed.
+    WindowRenamed {
+        /// The id of the window.
+        window: u32,
+        /// The new name of the window.
+        name: String,
+    },
+
+    /// The active pane in a window changed.
+    WindowPaneChanged {
+        /// The id of the window.
+        window: u32,
+        /// The id of the newly active pane.
+        pane: u32,
+    },
+
+    /// The client is now attached to a different session.
+    SessionChanged {
+        /// The id of the session.
+        session: u32,
+        /// The name of the session.
+        name: String,
+    },
+
+    /// The current window in a session changed.
+    SessionWindowChanged {
+        /// The id of the session.
+        session: u32,
+        /// The id of the newly current window.
+        window: u32,
+    },
+
+    /// The client is exiting, with the given reason if tmux gave one. No
+    /// more notifications will be sent.
+    Exit(Option<String>),
+
+    /// A notification which isn't otherwise handled, such as
+    /// `%sessions-changed`. Contains the entire line.
+    Other(String),
+}
+
+/// An error returned by [`TmuxClient::next_event`].
+#[derive(Debug)]
+pub enum TmuxError {
+    /// A line of output from tmux couldn't be parsed.
+    InvalidLine(String),
+
+    /// A window layout couldn't be parsed.
+    InvalidLayout(String),
+}
+
+impl std::fmt::Display for TmuxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLine(line) => {
+                write!(f, "invalid control mode line: {line:?}")
+            }
+            Self::InvalidLayout(layout) => {
+                write!(f, "invalid window layout: {layout:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TmuxError {}
+
+/// A client for the tmux control mode protocol (`tmux -C` or `tmux -CC`),
+/// which mirrors the panes of a tmux server into one
+/// [`Parser`](crate::Parser) per pane.
+///
+/// Output from tmux is passed to [`process`](Self::process), and the
+/// resulting notifications are then read with
+/// [`next_event`](Self::next_event). `%output` notifications are processed
+/// by the parser for the corresponding pane as they are read, and panes
+/// are resized and removed according to the `%layout-change` and
+/// `%window-close` notifications. Commands are sent to tmux by writing
+/// them to `W`.
+///
+/// Panes which produce output before their window's layout is known are
+/// given the size set by [`set_size`](Self::set_size). The layouts of the
+/// windows are also available from [`layout`](Self::layout), and their
+/// areas can be passed to a [`Compositor`](crate::Compositor) to draw a
+/// whole window.
+pub struct TmuxClient<W: std::io::Write> {
+    writer: W,
+    buf: Vec<u8>,
+    // the offset of the first unread byte in buf. read lines are only
+    // removed from buf once per call to process or next_event, so that
+    // reading many short lines doesn't move the rest of the buffer each
+    // time.
+    read: usize,
+    rows: u16,
+    cols: u16,
+    scrollback_len: usize,
+    panes: std::collections::BTreeMap<u32, crate::Parser>,
+    layouts: std::collections::BTreeMap<u32, TmuxLayout>,
+    // the arguments of the %begin line for the response being read, and
+    // the output read so far
+    response: Option<(Vec<u8>, Vec<String>)>,
+    // whether %exit has been read, after which nothing else is
+    exited: bool,
+}
+
+impl<W: std::io::Write> TmuxClient<W> {
+    /// Creates a new client which sends commands to `writer`, with a
+    /// default pane size of `rows` by `cols` and the given amount of
+    /// scrollback for each pane.
+    #[must_use]
+    pub fn new(
+        writer: W,
+        rows: u16,
+        cols: u16,
+        scrollback_len: usize,
+    ) -> Self {
+        Self {
+            writer,
+            buf: vec![],
+            read: 0,
+            rows,
+            cols,
+            scrollback_len,
+            panes: std::collections::BTreeMap::new(),
+            layouts: std::collections::BTreeMap::new(),
+            response: None,
+            exited: false,
+        }
+    }
+
+    /// Adds output from tmux to the buffer read by
+    /// [`next_event`](Self::next_event). Output after the `%exit`
+    /// notification has been read is ignored, since it no longer comes
+    /// from tmux.
+    pub fn process(&mut self, bytes: &[u8]) {
+        if self.exited {
+            return;
+        }
+        self.buf.drain(..self.read);
+        self.read = 0;
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Reads the next complete notification from the processed output, or
+    /// returns `None` if there isn't a complete one yet. After
+    /// [`TmuxEvent::Exit`] has been returned, this always returns `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line of output couldn't be parsed. That line
+    /// is skipped, so reading can continue afterwards.
+    pub fn next_event(&mut self) -> Option<Result<TmuxEvent, TmuxError>> {
+        // lines are handled while borrowed from the buffer, which is
+        // moved out of self so that the handlers can still modify self
+        let buf = std::mem::take(&mut self.buf);
+        let event = self.next_event_from(&buf);
+        self.buf = buf;
+        if self.exited {
+            self.buf.clear();
+            self.read = 0;
+        } else if event.is_none() {
+            self.buf.drain(..self.read);
+            self.read = 0;
+        }
+        event
+    }
+
+    fn next_event_from(
+        &mut self,
+        buf: &[u8],
+    ) -> Option<Result<TmuxEvent, TmuxError>> {
+        while !self.exited {
+            let len = buf[self.read..].iter().position(|&c| c == b'\n')?;
+            let mut line = &buf[self.read..self.read + len];
+            self.read += len + 1;
+            if let Some(rest) = line.strip_suffix(b"\r") {
+                line = rest;
+            }
+            if let Some(rest) = line.strip_prefix(DCS_START) {
+                line = rest;
+            }
+            if let Some(rest) = line.strip_prefix(DCS_END) {
+                line = rest;
+            }
+            if let Some(event) = self.handle_line(line) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    fn handle_line(
+        &mut self,
+        line: &[u8],
+    ) -> Option<Result<TmuxEvent, TmuxError>> {
+        if let Some((begin, output)) = &mut self.response {
+            // the block only ends at a line with the same time, command
+            // number and flags as the %begin line, so output which happens
+            // to look like the end of a block doesn't end it early
+            let end = |prefix: &[u8]| {
+                line.strip_prefix(prefix) == Some(begin.as_slice())
+            };
+            let error = end(b"%error ");
+            if error || end(b"%end ") {
+                let output = std::mem::take(output);
+                self.response = None;
+                return Some(Ok(TmuxEvent::Response { output, error }));
+            }
+            output.push(String::from_utf8_lossy(line).into_owned());
+            return None;
+        }
+
+        if let Some(rest) = line.strip_prefix(b"%output ") {
+            let mut parts = rest.splitn(2, |&c| c == b' ');
+            return Some(self.output(
+                line,
+                parts.next().unwrap_or_default(),
+                parts.next().unwrap_or_default(),
+            ));
+        }
+        if let Some(rest) = line.strip_prefix(b"%extended-output ") {
+            let pane = rest.split(|&c| c == b' ').next().unwrap_or_default();
+            let data = rest
+                .windows(3)
+                .position(|w| w == b" : ")
+                .map_or(&b""[..], |i| &rest[i + 3..]);
+            return Some(self.output(line, pane, data));
+        }
+
+        let line = String::from_utf8_lossy(line);
+        if line.is_empty() {
+            return None;
+        }
+        let invalid = || Err(TmuxError::InvalidLine(line.to_string()));
+        let mut args = line.split(' ');
+        let event = match args.next().unwrap_or_default() {
+            "%begin" => {
+                let begin = args.collect::<Vec<_>>().join(" ");
+                self.response = Some((begin.into_bytes(), vec![]));
+                return None;
+            }
+            "%layout-change" => {
+                let (Some(window), Some(layout)) =
+                    (args.next().and_then(parse_id('@')), args.next())
+                else {
+                    return Some(invalid());
+                };
+                let Some(layout) = TmuxLayout::parse(layout) else {
+                    return Some(Err(TmuxError::InvalidLayout(
+                        layout.to_string(),
+                    )));
+                };
+                self.set_layout(window, layout.clone());
+                TmuxEvent::LayoutChange { window, layout }
+            }
+            "%window-add" => {
+                let Some(window) = args.next().and_then(parse_id('@')) else {
+                    return Some(invalid());
+                };
+                TmuxEvent::WindowAdd(window)
+            }
+            "%window-close" => {
+                let Some(window) = args.next().and_then(parse_id('@')) else {
+                    return Some(invalid());
+                };
+                if let Some(layout) = self.layouts.remove(&window) {
+                    for (pane, _) in layout.panes() {
+                        self.panes.remove(&pane);
+                    }
+                }
+                TmuxEvent::WindowClose(window)
+            }
+            "%window-renamed" => {
+                let Some(window) = args.next().and_then(parse_id('@')) else {
+                    return Some(invalid());
+                };
+                let name = args.collect::<Vec<_>>().join(" ");
+                TmuxEvent::WindowRenamed { window, name }
+            }
+            "%window-pane-changed" => {
+                let (Some(window), Some(pane)) = (
+                    args.next().and_then(parse_id('@')),
+                    args.next().and_then(parse_id('%')),
+                ) else {
+                    return Some(invalid());
+                };
+                TmuxEvent::WindowPaneChanged { window, pane }
+            }
+            "%session-changed" => {
+                let Some(session) = args.next().and_then(parse_id('$'))
+                else {
+                    return Some(invalid());
+                };
+                let name = args.collect::<Vec<_>>().join(" ");
+                TmuxEvent::SessionChanged { session, name }
+            }
+            "%session-window-changed" => {
+                let (Some(session), Some(window)) = (
+                    args.next().and_then(parse_id('$')),
+                    args.next().and_then(parse_id('@')),
+                ) else {
+                    return Some(invalid());
+                };
+                TmuxEvent::SessionWindowChanged { session, window }
+            }
+            "%exit" => {
+                self.exited = true;
+                let reason = args.collect::<Vec<_>>().join(" ");
+                TmuxEvent::Exit((!reason.is_empty()).then_some(reason))
+            }
+            _ => TmuxEvent::Other(line.into_owned()),
+        };
+        Some(Ok(event))
+    }
+
+    fn output(
+        &mut self,
+        line: &[u8],
+        pane: &[u8],
+        data: &[u8],
+    ) -> Result<TmuxEvent, TmuxError> {
+        let (Some(pane), Some(data)) = (
+            std::str::from_utf8(pane).ok().and_then(parse_id('%')),
+            unescape(data),
+        ) else {
+            return Err(TmuxError::InvalidLine(
+                String::from_utf8_lossy(line).into_owned(),
+            ));
+        };
+        let (rows, cols, scrollback_len) =
+            (self.rows, self.cols, self.scrollback_len);
+        self.panes
+            .entry(pane)
+            .or_insert_with(|| crate::Parser::new(rows, cols, scrollback_len))
+            .process(&data);
+        Ok(TmuxEvent::Output(pane))
+    }
+
+    fn set_layout(&mut self, window: u32, layout: TmuxLayout) {
+        let panes = layout.panes();
+        if let Some(prev) = self.layouts.get(&window) {
+            for (pane, _) in prev.panes() {
+                if !panes.iter().any(|(id, _)| *id == pane) {
+                    self.panes.remove(&pane);
+                }
+            }
+        }
+        for (pane, rect) in panes {
+            self.panes
+                .entry(pane)
+                .or_insert_with(|| {
+                    crate::Parser::new(
+                        rect.rows,
+                        rect.cols,
+                        self.scrollback_len,
+                    )
+                })
+                .screen_mut()
+                .set_size(rect.rows, rect.cols);
+        }
+        self.layouts.insert(window, layout);
+    }
+
+    /// Sends a command to tmux. Its output will be returned as a
+    /// [`TmuxEvent::Response`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the command fails.
+    pub fn send_command(&mut self, command: &str) -> std::io::Result<()> {
+        self.writer.write_all(command.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    /// Sends input to the given pane, as if it was typed into it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the command fails.
+    pub fn send_input(
+        &mut self,
+        pane: u32,
+        input: &[u8],
+    ) -> std::io::Result<()> {
+        if input.is_empty() {
+            return Ok(());
+        }
+        let mut command = format!("send-keys -H -t %{pane}");
+        for byte in input {
+            // writing to a string can't fail
+            write!(command, " {byte:02x}").unwrap();
+        }
+        self.send_command(&command)
+    }
+
+    /// Tells tmux the size of the client, and uses it as the size of any
+    /// new panes whose size isn't known yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the command fails.
+    pub fn set_size(&mut self, rows: u16, cols: u16) -> std::io::Result<()> {
+        self.rows = rows;
+        self.cols = cols;
+        self.send_command(&format!("refresh-client -C {cols}x{rows}"))
+    }
+
+    /// Returns the parser for the given pane, if any output has been
+    /// received for it or it has appeared in a layout.
+    #[must_use]
+    pub fn pane(&self, pane: u32) -> Option<&crate::Parser> {
+        self.panes.get(&pane)
+    }
+
+    /// Returns the ids of all of the known panes, in increasing order.
+    pub fn panes(&self) -> impl Iterator<Item = u32> + '_ {
+        self.panes.keys().copied()
+    }
+
+    /// Returns the most recent layout of the given window, if it is known.
+    #[must_use]
+    pub fn layout(&self, window: u32) -> Option<&TmuxLayout> {
+        self.layouts.get(&window)
+    }
+
+    /// Returns a reference to the writer that commands are sent to.
+    #[must_use]
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+}
+
+// returns a function which parses ids like "%3" (for panes), "@3" (for
+// windows) or "$3" (for sessions)
+fn parse_id(prefix: char) -> impl Fn(&str) -> Option<u32> {
+    move |s| s.strip_prefix(prefix)?.parse().ok()
+}
+
+// tmux escapes control characters and backslashes in output as octal
+// escapes like \015. returns None if an escape is out of range for a
+// byte, which tmux never sends.
+fn unescape(data: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        let escape = data.get(i + 1..i + 4).filter(|digits| {
+            data[i] == b'\\'
+                && digits.iter().all(|c| (b'0'..=b'7').contains(c))
+        });
+        if let Some(digits) = escape {
+            let byte =
+                digits.iter().fold(0u16, |n, c| n * 8 + u16::from(c - b'0'));
+            bytes.push(u8::try_from(byte).ok()?);
+            i += 4;
+        } else {
+            bytes.push(data[i]);
+            i += 1;
+        }
+    }
+    Some(bytes)
+}
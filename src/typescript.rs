@@ -0,0 +1,239 @@
+/// An error encountered when reading a typescript with
+/// [`TypescriptPlayer::new`].
+#[derive(Debug)]
+pub enum TypescriptError {
+    /// Reading the typescript or timing file failed.
+    Io(std::io::Error),
+
+    /// The entry on the given (1-based) line of the timing file is
+    /// malformed, or refers to data past the end of the typescript.
+    InvalidTiming(usize),
+}
+
+impl std::fmt::Display for TypescriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read typescript: {e}"),
+            Self::InvalidTiming(line) => {
+                write!(f, "invalid timing entry on line {line}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TypescriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::InvalidTiming(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TypescriptError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Plays back a typescript recorded by the util-linux `script` command,
+/// using the timing file written by `script --log-timing` (or `--timing`).
+///
+/// Both the classic timing format (`<delay> <bytes>` on each line) and the
+/// advanced format used when input or output are logged explicitly (`O`,
+/// `I`, `S`, and `H` entries) are supported. In the advanced format, the
+/// terminal size is read from the `LINES` and `COLUMNS` entries and
+/// `SIGWINCH` entries resize the terminal, and input is only read from the
+/// typescript if it was logged to the same file as the output (with
+/// `script --log-io`).
+///
+/// Seeking replays the recording from the nearest keyframe before the
+/// target time. Keyframes are [snapshots](crate::Parser::save_snapshot) of
+/// the full parser state, so seeking produces the same state as stepping
+/// through the recording from the start.
+pub struct TypescriptPlayer {
+    playback: crate::playback::Playback,
+}
+
+impl TypescriptPlayer {
+    /// Reads a typescript and its timing file. `rows` and `cols` give the
+    /// size of the terminal, unless the timing file records it. The player
+    /// starts at the beginning of the recording, before any events have
+    /// been applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the timing file is malformed.
+    pub fn new<R: std::io::Read, T: std::io::BufRead>(
+        mut typescript: R,
+        timing: T,
+        rows: u16,
+        cols: u16,
+    ) -> Result<Self, TypescriptError> {
+        let mut data = vec![];
+        typescript.read_to_end(&mut data)?;
+        // the timing file only covers the data after the header line
+        let mut data = &data[..];
+        if data.starts_with(b"Script started on ") {
+            data = data
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(&[], |i| &data[i + 1..]);
+        }
+
+        let mut initial_size = (rows, cols);
+        let mut size = initial_size;
+        let mut events = vec![];
+        let mut time = std::time::Duration::ZERO;
+        let mut output_log = None;
+        let mut input_log = None;
+        for (i, line) in timing.lines().enumerate() {
+            let line = line?;
+            let line_number = i + 1;
+            let invalid = || TypescriptError::InvalidTiming(line_number);
+            let mut fields = line.split_whitespace();
+            let Some(first) = fields.next() else {
+                continue;
+            };
+            let (kind, delay) = if first.starts_with(char::is_alphabetic) {
+                (first, fields.next().ok_or_else(invalid)?)
+            } else {
+                ("O", first)
+            };
+            time += delay
+                .parse()
+                .ok()
+                .and_then(|delay| {
+                    std::time::Duration::try_from_secs_f64(delay).ok()
+                })
+                .ok_or_else(invalid)?;
+
+            match kind {
+                "O" | "I" => {
+                    let len: usize = fields
+                        .next()
+                        .and_then(|len| len.parse().ok())
+                        .ok_or_else(invalid)?;
+                    if kind == "I"
+                        && (input_log.is_none() || input_log != output_log)
+                    {
+                        continue;
+                    }
+                    if len > data.len() {
+                        return Err(invalid());
+                    }
+                    let (bytes, rest) = data.split_at(len);
+                    data = rest;
+                    if kind == "O" {
+                        events.push((
+                            time,
+                            crate::playback::Event::Output(bytes.to_vec()),
+                        ));
+                    }
+                }
+                "S" => {
+                    if fields.next() != Some("SIGWINCH") {
+                        continue;
+                    }
+                    let mut new_size = size;
+                    for field in fields {
+                        match field.split_once('=') {
+                            Some(("ROWS", rows)) => {
+                                new_size.0 = parse_dimension(rows)
+                                    .ok_or_else(invalid)?;
+                            }
+                            Some(("COLS", cols)) => {
+                                new_size.1 = parse_dimension(cols)
+                                    .ok_or_else(invalid)?;
+                            }
+                            _ => {}
+                        }
+                    }
+                    size = new_size;
+                    events.push((
+                        time,
+                        crate::playback::Event::Resize(size.0, size.1),
+                    ));
+                }
+                "H" => {
+                    let name = fields.next().ok_or_else(invalid)?;
+                    let value = fields.collect::<Vec<_>>().join(" ");
+                    match name {
+                        "LINES" | "COLUMNS" => {
+                            let n = parse_dimension(&value)
+                                .ok_or_else(invalid)?;
+                            if name == "LINES" {
+                                size.0 = n;
+                            } else {
+                                size.1 = n;
+                            }
+                            // the header entries are written before any
+                            // output, so they normally just give the
+                            // initial size
+                            if events.is_empty() {
+                                initial_size = size;
+                            } else {
+                                events.push((
+                                    time,
+                                    crate::playback::Event::Resize(
+                                        size.0, size.1,
+                                    ),
+                                ));
+                            }
+                        }
+                        "OUTPUT_LOG" => output_log = Some(value),
+                        "INPUT_LOG" => input_log = Some(value),
+                        _ => {}
+                    }
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(Self {
+            playback: crate::playback::Playback::new(initial_size, events),
+        })
+    }
+
+    /// Applies the next event in the recording, and returns its time. Returns
+    /// `None` if every event has already been applied.
+    pub fn step(&mut self) -> Option<std::time::Duration> {
+        self.playback.step()
+    }
+
+    /// Returns the time of the last event in the recording.
+    #[must_use]
+    pub fn duration(&self) -> std::time::Duration {
+        self.playback.duration()
+    }
+
+    /// Returns the current playback position.
+    #[must_use]
+    pub fn position(&self) -> std::time::Duration {
+        self.playback.position()
+    }
+
+    /// Moves the playback position to the given time, applying every event
+    /// which occurred at or before it.
+    pub fn seek(&mut self, time: std::time::Duration) {
+        self.playback.seek(time);
+    }
+
+    /// Returns a reference to the [`Parser`](crate::Parser) containing the
+    /// terminal state at the current playback position.
+    #[must_use]
+    pub fn parser(&self) -> &crate::Parser {
+        self.playback.parser()
+    }
+
+    /// Returns a reference to a [`Screen`](crate::Screen) object containing
+    /// the terminal state at the current playback position.
+    #[must_use]
+    pub fn screen(&self) -> &crate::Screen {
+        self.playback.parser().screen()
+    }
+}
+
+fn parse_dimension(s: &str) -> Option<u16> {
+    s.parse().ok().filter(|&n| n > 0)
+}
@@ -0,0 +1,194 @@
+#![cfg(feature = "asciicast")]
+
+fn lines(cast: &[u8]) -> Vec<serde_json::Value> {
+    std::str::from_utf8(cast)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn secs(secs: f64) -> std::time::Duration {
+    std::time::Duration::from_secs_f64(secs)
+}
+
+#[test]
+fn record() {
+    let mut recorder =
+        vt100::AsciicastRecorder::new(vec![], 24, 80, 0).unwrap();
+    recorder.process_at(secs(0.5), b"foo\x1b[31m").unwrap();
+    // incomplete characters are held back until they are complete
+    recorder.process_at(secs(1.0), b"bar\xe3\x81").unwrap();
+    recorder.process_at(secs(1.5), b"\x82").unwrap();
+    recorder.set_size_at(secs(2.0), 30, 100).unwrap();
+    // times never go backwards
+    recorder.process_at(secs(1.0), b"baz\xff\xe3").unwrap();
+    assert_eq!(recorder.screen().size(), (30, 100));
+    assert_eq!(recorder.screen().contents(), "foobar\u{3042}baz");
+    let cast = recorder.finish().unwrap();
+
+    let lines = lines(&cast);
+    assert_eq!(lines[0]["version"], 2);
+    assert_eq!(lines[0]["width"], 80);
+    assert_eq!(lines[0]["height"], 24);
+    assert_eq!(
+        &lines[1..],
+        &[
+            serde_json::json!([0.5, "o", "foo\x1b[31m"]),
+            serde_json::json!([1.0, "o", "bar"]),
+            serde_json::json!([1.5, "o", "\u{3042}"]),
+            serde_json::json!([2.0, "r", "100x30"]),
+            serde_json::json!([2.0, "o", "baz\u{fffd}"]),
+            serde_json::json!([2.0, "o", "\u{fffd}"]),
+        ]
+    );
+}
+
+#[test]
+fn play() {
+    let cast = br#"{"version": 2, "width": 10, "height": 5, "timestamp": 1}
+[0.5, "o", "foo\r\n"]
+[1.0, "i", "ignored"]
+[1.0, "o", "\u001b[31mbar"]
+[2.0, "r", "20x3"]
+
+[2.0, "m", "marker"]
+[3.5, "o", "\u001b[2Jbaz"]
+"#;
+    let mut player = vt100::AsciicastPlayer::new(&cast[..]).unwrap();
+    assert_eq!(player.duration(), secs(3.5));
+    assert_eq!(player.screen().size(), (5, 10));
+    assert_eq!(player.screen().contents(), "");
+
+    player.seek(secs(1.0));
+    assert_eq!(player.position(), secs(1.0));
+    assert_eq!(player.screen().contents(), "foo\nbar");
+    assert_eq!(
+        player.screen().cell(1, 0).unwrap().fgcolor(),
+        vt100::Color::Idx(1)
+    );
+
+    player.seek(secs(2.5));
+    assert_eq!(player.screen().size(), (3, 20));
+    assert_eq!(player.screen().contents(), "foo\nbar");
+
+    player.seek(secs(10.0));
+    assert_eq!(player.screen().contents(), "\n   baz");
+
+    player.seek(secs(0.75));
+    assert_eq!(player.screen().size(), (5, 10));
+    assert_eq!(player.screen().contents(), "foo");
+
+    player.seek(secs(0.0));
+    assert_eq!(player.screen().contents(), "");
+}
+
+#[test]
+fn seek() {
+    let mut recorder =
+        vt100::AsciicastRecorder::new(vec![], 24, 80, 0).unwrap();
+    let mut expected = vec![];
+    for i in 0..10000_u16 {
+        let line = format!("\x1b[3{}mline {i:05} \u{3042}\x1b[m\r\n", i % 8);
+        // split the writes in the middle of escape sequences and
+        // characters
+        let (a, b) = line.as_bytes().split_at(usize::from(i % 9));
+        let time = std::time::Duration::from_millis(i.into());
+        recorder.process_at(time, a).unwrap();
+        recorder.process_at(time, b).unwrap();
+        if i % 1000 == 500 {
+            recorder.set_size_at(time, 24 + i / 1000, 80).unwrap();
+        }
+        expected.push(recorder.screen().contents_formatted());
+    }
+    let cast = recorder.finish().unwrap();
+
+    let mut player = vt100::AsciicastPlayer::new(&cast[..]).unwrap();
+    for i in [9999_u16, 10, 7500, 7499, 1200, 0, 5600, 5601, 8000, 9999] {
+        player.seek(std::time::Duration::from_millis(i.into()));
+        assert_eq!(
+            player.screen().contents_formatted(),
+            expected[usize::from(i)],
+            "{i}"
+        );
+    }
+}
+
+#[test]
+fn seek_alternate_screen() {
+    let mut recorder =
+        vt100::AsciicastRecorder::new(vec![], 24, 80, 0).unwrap();
+    for i in 0..5000_u16 {
+        let mut output =
+            format!("\x1b[3{}m{i:05} {}\x1b[m\r\n", i % 8, "x".repeat(40));
+        // switch to the alternate screen, with a scrolling region and a
+        // hidden cursor, for part of every second
+        if i % 1000 == 400 {
+            output.insert_str(0, "\x1b[?1049h\x1b[?25l\x1b[5;10r");
+        }
+        if i % 1000 == 900 {
+            output.insert_str(0, "\x1b[r\x1b[?25h\x1b[?1049l");
+        }
+        let time = std::time::Duration::from_millis(i.into());
+        recorder.process_at(time, output.as_bytes()).unwrap();
+    }
+    let cast = recorder.finish().unwrap();
+
+    let mut stepping = vt100::AsciicastPlayer::new(&cast[..]).unwrap();
+    let mut seeking = vt100::AsciicastPlayer::new(&cast[..]).unwrap();
+    while let Some(time) = stepping.step() {
+        if time.as_millis() % 50 != 0 {
+            continue;
+        }
+        // seek from the start so that the nearest keyframe is loaded
+        seeking.seek(std::time::Duration::ZERO);
+        seeking.seek(time);
+        assert_eq!(
+            seeking.parser().save_snapshot(),
+            stepping.parser().save_snapshot(),
+            "{time:?}"
+        );
+    }
+}
+
+#[test]
+fn invalid() {
+    let invalid = |cast: &[u8]| vt100::AsciicastPlayer::new(cast).err();
+    assert!(matches!(
+        invalid(b""),
+        Some(vt100::AsciicastError::InvalidHeader)
+    ));
+    assert!(matches!(
+        invalid(b"{\"version\": 1, \"width\": 80, \"height\": 24}\n"),
+        Some(vt100::AsciicastError::InvalidHeader)
+    ));
+    assert!(matches!(
+        invalid(b"{\"version\": 2, \"width\": 0, \"height\": 24}\n"),
+        Some(vt100::AsciicastError::InvalidHeader)
+    ));
+
+    let header = "{\"version\": 2, \"width\": 80, \"height\": 24}\n";
+    for event in [
+        "[1.0, \"o\"]",
+        "[-1.0, \"o\", \"foo\"]",
+        "[1.0, \"r\", \"80\"]",
+        "[1.0, \"r\", \"0x24\"]",
+        "not json",
+    ] {
+        let cast = format!("{header}[0.5, \"o\", \"foo\"]\n{event}\n");
+        assert!(
+            matches!(
+                invalid(cast.as_bytes()),
+                Some(vt100::AsciicastError::InvalidEvent(3))
+            ),
+            "{event}"
+        );
+    }
+
+    let cast =
+        format!("{header}[2.0, \"o\", \"foo\"]\n[1.0, \"o\", \"bar\"]\n");
+    assert!(matches!(
+        invalid(cast.as_bytes()),
+        Some(vt100::AsciicastError::InvalidEvent(3))
+    ));
+}
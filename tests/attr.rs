@@ -21,3 +21,51 @@ fn attributes_formatted() {
     parser.process(b"\x1b[m");
     assert_eq!(parser.screen().attributes_formatted(), b"\x1b[m");
 }
+
+#[test]
+fn color_depth() {
+    let mut parser = vt100::Parser::default();
+    parser.process(
+        b"\x1b[38;2;255;0;0mr\x1b[48;5;21mb\x1b[1;38;5;244mg\x1b[m\x1b[33;7mi",
+    );
+    let prev = parser.screen().clone();
+    parser.process(b"\x1b[m\x1b[H\x1b[38;2;250;10;10mR");
+
+    let screen = parser.screen_mut();
+    assert_eq!(screen.color_depth(), vt100::ColorDepth::TrueColor);
+    assert_eq!(
+        screen.contents_formatted(),
+        &b"\x1b[?25h\x1b[m\x1b[H\x1b[J\x1b[38;2;250;10;10mR\x1b[38;2;255;0;0;48;5;21mb\x1b[38;5;244;1mg\x1b[33;49;22;7mi\x1b[1;2H\x1b[38;2;250;10;10;27m"[..]
+    );
+
+    screen.set_color_depth(vt100::ColorDepth::Indexed256);
+    assert_eq!(
+        screen.contents_formatted(),
+        &b"\x1b[?25h\x1b[m\x1b[H\x1b[J\x1b[38;5;196mR\x1b[48;5;21mb\x1b[38;5;244;1mg\x1b[33;49;22;7mi\x1b[1;2H\x1b[38;5;196;27m"[..]
+    );
+    assert_eq!(screen.contents_diff(&prev), b"\x1b[H\x1b[38;5;196;27mR");
+
+    screen.set_color_depth(vt100::ColorDepth::Indexed16);
+    assert_eq!(
+        screen.contents_formatted(),
+        &b"\x1b[?25h\x1b[m\x1b[H\x1b[J\x1b[91mR\x1b[44mb\x1b[90;1mg\x1b[33;49;22;7mi\x1b[1;2H\x1b[91;27m"[..]
+    );
+    assert_eq!(screen.attributes_formatted(), b"\x1b[m\x1b[91m");
+
+    screen.set_color_depth(vt100::ColorDepth::Monochrome);
+    assert_eq!(
+        screen.contents_formatted(),
+        &b"\x1b[?25h\x1b[m\x1b[H\x1b[JRb\x1b[1mg\x1b[22;7mi\x1b[1;2H\x1b[m"[..]
+    );
+    assert_eq!(screen.contents_diff(&prev), b"\x1b[H\x1b[mR");
+    assert_eq!(screen.attributes_formatted(), b"\x1b[m");
+
+    let mut new_parser = vt100::Parser::default();
+    new_parser.process(&screen.contents_formatted());
+    assert_eq!(new_parser.screen().contents(), "Rbgi");
+    assert_eq!(
+        new_parser.screen().cell(0, 0).unwrap().fgcolor(),
+        vt100::Color::Default
+    );
+    assert!(new_parser.screen().cell(0, 3).unwrap().inverse());
+}
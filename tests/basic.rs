@@ -76,6 +76,25 @@ fn set_size() {
     );
 }
 
+#[test]
+fn set_size_wide_char() {
+    let mut parser = vt100::Parser::new(2, 4, 0);
+    parser.process("\x1b[2;2H\u{3042}".as_bytes());
+    assert!(parser.screen().cell(1, 1).unwrap().is_wide());
+    assert!(parser.screen().cell(1, 2).unwrap().is_wide_continuation());
+
+    // shrinking the screen cuts the wide character in half, which would
+    // leave a wide cell in the last column with no continuation cell
+    parser.screen_mut().set_size(2, 2);
+    assert!(!parser.screen().cell(1, 1).unwrap().is_wide());
+    assert_eq!(parser.screen().cell(1, 1).unwrap().contents(), "");
+    assert_eq!(parser.screen().contents(), "");
+
+    // overwriting the cell used to panic looking for the continuation
+    parser.process(b"\x1b[2;2Hx");
+    assert_eq!(parser.screen().contents(), "\n x");
+}
+
 #[test]
 fn cell_contents() {
     let mut parser = vt100::Parser::default();
@@ -122,3 +141,55 @@ fn cell_attrs() {
 
     assert!(parser.screen().cell(0, 4).unwrap().italic());
 }
+
+#[test]
+fn clone_independence() {
+    let mut parser = vt100::Parser::new(3, 10, 10);
+    parser.process(b"1\r\n2\r\n3\r\n4\x1b[1;31m");
+    let screen = parser.screen().clone();
+
+    parser.process(b"\x1b[Hx\x1b[2;1H\x1b[K\r\n\r\n5\r\n6");
+    parser.screen_mut().set_size(3, 5);
+    assert_eq!(parser.screen().contents(), "4\n5\n6");
+    assert_eq!(screen.contents(), "2\n3\n4");
+
+    let mut screen = screen;
+    screen.set_scrollback(1);
+    assert_eq!(screen.contents(), "1\n2\n3");
+    parser.screen_mut().set_scrollback(3);
+    assert_eq!(parser.screen().contents(), "1\nx");
+}
+
+#[test]
+fn clone_scrollback() {
+    let mut parser = vt100::Parser::new(2, 10, 150);
+    let mut screens = vec![];
+    for i in 0_usize..400 {
+        parser.process(format!("{i}\r\n").as_bytes());
+        if i % 50 == 49 {
+            screens.push((i, parser.screen().clone()));
+        }
+    }
+
+    // each clone keeps the scrollback it had when it was cloned, even
+    // after rows have been added to and trimmed from the original
+    for (i, mut screen) in screens {
+        screen.set_scrollback(usize::MAX);
+        let first = i.saturating_sub(150);
+        assert_eq!(screen.scrollback(), i - first);
+        assert_eq!(screen.contents(), format!("{first}\n{}", first + 1));
+    }
+    parser.screen_mut().set_scrollback(usize::MAX);
+    assert_eq!(parser.screen().scrollback(), 150);
+    assert_eq!(parser.screen().contents(), "249\n250");
+
+    // damage is tracked separately for each clone
+    parser.screen_mut().set_scrollback(0);
+    parser.screen_mut().clear_damage();
+    let mut screen = parser.screen().clone();
+    parser.process(b"x");
+    assert_eq!(parser.screen().damage().dirty_rows(), &[1]);
+    assert!(screen.damage().is_empty());
+    screen.clear_damage();
+    assert_eq!(parser.screen().damage().dirty_rows(), &[1]);
+}
@@ -0,0 +1,134 @@
+#[test]
+fn broadcast() {
+    let mut parser = vt100::Parser::new(24, 80, 0);
+    parser.process(b"foo");
+    let mut broadcaster = vt100::Broadcaster::new(parser.screen());
+
+    let fast = broadcaster.add_client();
+    let mut fast_term = vt100::Parser::new(24, 80, 0);
+    let frame = broadcaster.next_frame(fast).unwrap();
+    assert!(frame.is_full());
+    assert_eq!(frame.version(), 0);
+    fast_term.process(frame.data());
+    broadcaster.ack(fast, frame.version());
+    assert!(broadcaster.next_frame(fast).is_none());
+
+    let slow = broadcaster.add_client();
+    let mut slow_term = vt100::Parser::new(24, 80, 0);
+    let slow_frame = broadcaster.next_frame(slow).unwrap();
+    assert!(slow_frame.is_full());
+
+    for update in [&b"\r\nbar"[..], b"\x1b[31mbaz", b"\x1b[H\x1b[Kquux"] {
+        parser.process(update);
+        broadcaster.update(parser.screen());
+        let frame = broadcaster.next_frame(fast).unwrap();
+        assert!(!frame.is_full());
+        fast_term.process(frame.data());
+        broadcaster.ack(fast, frame.version());
+        assert_eq!(fast_term.screen().contents(), parser.screen().contents());
+
+        // the slow client hasn't acknowledged its first frame yet
+        assert!(broadcaster.next_frame(slow).is_none());
+    }
+    assert_eq!(broadcaster.version(), 3);
+
+    // once the slow client catches up, it gets a single frame covering all
+    // of the intermediate updates
+    slow_term.process(slow_frame.data());
+    broadcaster.ack(slow, slow_frame.version());
+    let frame = broadcaster.next_frame(slow).unwrap();
+    assert!(!frame.is_full());
+    assert_eq!(frame.version(), 3);
+    slow_term.process(frame.data());
+    broadcaster.ack(slow, frame.version());
+    assert!(broadcaster.next_frame(slow).is_none());
+    assert_eq!(slow_term.screen().contents(), "quux\nbarbaz");
+    assert_eq!(
+        slow_term.screen().state_formatted(),
+        parser.screen().state_formatted()
+    );
+
+    // stale acknowledgements are ignored
+    parser.process(b"!");
+    broadcaster.update(parser.screen());
+    let frame = broadcaster.next_frame(slow).unwrap();
+    broadcaster.ack(slow, frame.version() - 1);
+    assert!(broadcaster.next_frame(slow).is_none());
+    broadcaster.ack(slow, frame.version());
+    assert!(broadcaster.next_frame(slow).is_none());
+
+    broadcaster.reset_client(slow);
+    assert!(broadcaster.next_frame(slow).unwrap().is_full());
+
+    assert_eq!(broadcaster.client_count(), 2);
+    assert!(broadcaster.remove_client(fast));
+    assert!(!broadcaster.remove_client(fast));
+    assert!(broadcaster.next_frame(fast).is_none());
+    assert_eq!(broadcaster.client_count(), 1);
+}
+
+#[test]
+fn resize() {
+    let mut parser = vt100::Parser::new(24, 80, 0);
+    parser.process(b"foo\r\n\x1b[31mbar");
+    let mut broadcaster = vt100::Broadcaster::new(parser.screen());
+    let client = broadcaster.add_client();
+    let mut term = vt100::Parser::new(24, 80, 0);
+
+    let mut receive = |broadcaster: &mut vt100::Broadcaster| {
+        let frame = broadcaster.next_frame(client).unwrap();
+        if frame.is_full() {
+            let (rows, cols) = frame.size();
+            term.process(b"\x1bc");
+            term.screen_mut().set_size(rows, cols);
+        }
+        term.process(frame.data());
+        broadcaster.ack(client, frame.version());
+        (frame.is_full(), term.screen().clone())
+    };
+    let (full, screen) = receive(&mut broadcaster);
+    assert!(full);
+    assert_eq!(screen.state_formatted(), parser.screen().state_formatted());
+
+    // the diff between screens of different sizes assumes that the client
+    // is already the new size, so the client is sent everything instead
+    parser.screen_mut().set_size(10, 20);
+    parser.process(b"\x1b[10;15Hbaz");
+    broadcaster.update(parser.screen());
+    let (full, screen) = receive(&mut broadcaster);
+    assert!(full);
+    assert_eq!(screen.size(), (10, 20));
+    assert_eq!(screen.state_formatted(), parser.screen().state_formatted());
+
+    parser.process(b"\x1b[Hquux");
+    broadcaster.update(parser.screen());
+    let (full, screen) = receive(&mut broadcaster);
+    assert!(!full);
+    assert_eq!(
+        screen.contents(),
+        "quux\nbar\n\n\n\n\n\n\n\n              baz"
+    );
+    assert_eq!(screen.state_formatted(), parser.screen().state_formatted());
+}
+
+#[test]
+fn unchanged() {
+    let mut parser = vt100::Parser::new(24, 80, 0);
+    let mut broadcaster = vt100::Broadcaster::new(parser.screen());
+    let client = broadcaster.add_client();
+    let frame = broadcaster.next_frame(client).unwrap();
+    broadcaster.ack(client, frame.version());
+
+    // updates which don't change anything that is sent to the clients
+    // don't create new versions
+    parser.process(b"\x1b]2;title\x07");
+    broadcaster.update(parser.screen());
+    broadcaster.update(parser.screen());
+    assert_eq!(broadcaster.version(), 0);
+    assert!(broadcaster.next_frame(client).is_none());
+
+    parser.process(b"\x1b[?1h");
+    broadcaster.update(parser.screen());
+    assert_eq!(broadcaster.version(), 1);
+    assert!(!broadcaster.next_frame(client).unwrap().is_full());
+}
@@ -0,0 +1,237 @@
+#[test]
+fn cells() {
+    let mut parser = vt100::Parser::new(4, 10, 0);
+    let screen = parser.screen().clone();
+    assert_eq!(parser.screen().changes(&screen), vec![]);
+
+    parser.process(b"foo\x1b[3;5H\x1b[31mbar\x1b[2;1H");
+    let changes = parser.screen().changes(&screen);
+    assert_eq!(changes.len(), 3);
+    let vt100::Change::Cells { row, col, cells } = &changes[0] else {
+        panic!("unexpected change {:?}", changes[0]);
+    };
+    assert_eq!((*row, *col), (0, 0));
+    assert_eq!(
+        cells.iter().map(vt100::Cell::contents).collect::<Vec<_>>(),
+        vec!["f", "o", "o"]
+    );
+    let vt100::Change::Cells { row, col, cells } = &changes[1] else {
+        panic!("unexpected change {:?}", changes[1]);
+    };
+    assert_eq!((*row, *col), (2, 4));
+    assert_eq!(cells.len(), 3);
+    assert_eq!(cells[0].fgcolor(), vt100::Color::Idx(1));
+    assert_eq!(changes[2], vt100::Change::CursorPosition { row: 1, col: 0 });
+
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[1;2Ha\x1b[2;1H");
+    assert_eq!(
+        parser.screen().changes(&screen),
+        vec![vt100::Change::Cells {
+            row: 0,
+            col: 1,
+            cells: vec![parser.screen().cell(0, 1).unwrap().clone()],
+        }]
+    );
+}
+
+#[test]
+fn modes() {
+    let mut parser = vt100::Parser::default();
+    let screen = parser.screen().clone();
+    parser
+        .process(b"\x1b[?25l\x1b=\x1b[?1h\x1b[?2004h\x1b[?1000h\x1b[?1006h");
+    assert_eq!(
+        parser.screen().changes(&screen),
+        vec![
+            vt100::Change::HideCursor(true),
+            vt100::Change::ApplicationKeypad(true),
+            vt100::Change::ApplicationCursor(true),
+            vt100::Change::BracketedPaste(true),
+            vt100::Change::MouseProtocolMode(
+                vt100::MouseProtocolMode::PressRelease
+            ),
+            vt100::Change::MouseProtocolEncoding(
+                vt100::MouseProtocolEncoding::Sgr
+            ),
+        ]
+    );
+}
+
+#[test]
+fn resize() {
+    let mut parser = vt100::Parser::new(4, 10, 0);
+    parser.process(b"foo");
+    let screen = parser.screen().clone();
+    parser.screen_mut().set_size(3, 5);
+    let changes = parser.screen().changes(&screen);
+    assert_eq!(changes[0], vt100::Change::Resize { rows: 3, cols: 5 });
+    assert!(matches!(
+        changes[1],
+        vt100::Change::Cells { row: 0, col: 0, ref cells } if cells.len() == 3
+    ));
+    assert_eq!(
+        changes[2..],
+        [vt100::Change::CursorPosition { row: 0, col: 3 }]
+    );
+}
+
+#[test]
+fn wrapped() {
+    // a row can start or stop wrapping without any of its cells changing
+    let mut parser = vt100::Parser::new(3, 4, 0);
+    parser.process(b"abcd\x1b[H");
+    let screen = parser.screen().clone();
+    // the space wraps the first row, and is then deleted again
+    parser.process(b"\x1b[1;4Hd \x1b[2;1H\x1b[P\x1b[H");
+    assert!(parser.screen().row_wrapped(0));
+    assert_eq!(
+        parser.screen().changes(&screen),
+        vec![vt100::Change::Wrapped {
+            row: 0,
+            wrapped: true,
+        }]
+    );
+
+    // erasing the last character unwraps the row, even if it is then
+    // written again
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[1;4H\x1b[Xd\x1b[H");
+    assert!(!parser.screen().row_wrapped(0));
+    assert_eq!(
+        parser.screen().changes(&screen),
+        vec![vt100::Change::Wrapped {
+            row: 0,
+            wrapped: false,
+        }]
+    );
+
+    // changes to the cells come first
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[1;4Hxy\x1b[H");
+    assert_eq!(
+        parser.screen().changes(&screen),
+        vec![
+            vt100::Change::Cells {
+                row: 0,
+                col: 3,
+                cells: vec![parser.screen().cell(0, 3).unwrap().clone()],
+            },
+            vt100::Change::Wrapped {
+                row: 0,
+                wrapped: true,
+            },
+            vt100::Change::Cells {
+                row: 1,
+                col: 0,
+                cells: vec![parser.screen().cell(1, 0).unwrap().clone()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn scroll() {
+    let mut parser = vt100::Parser::new(5, 10, 0);
+    parser.process(b"one\r\ntwo\r\nthree\r\nfour\r\nfive");
+    let screen = parser.screen().clone();
+    parser.process(b"\r\nsix");
+    assert_eq!(
+        parser.screen().changes(&screen),
+        vec![
+            vt100::Change::Scroll {
+                top: 0,
+                bottom: 4,
+                count: 1,
+                up: true,
+            },
+            vt100::Change::Cells {
+                row: 4,
+                col: 0,
+                cells: (0..3)
+                    .map(|col| parser.screen().cell(4, col).unwrap().clone())
+                    .collect(),
+            },
+            vt100::Change::CursorPosition { row: 4, col: 3 },
+        ]
+    );
+
+    // scrolling a region down
+    let mut parser = vt100::Parser::new(6, 10, 0);
+    parser.process(b"one\r\ntwo\r\nthree\r\nfour\r\nfive\r\nsix");
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[2;5r\x1b[2;1H\x1b[L\x1b[r\x1b[6;4H");
+    assert_eq!(parser.screen().contents(), "one\n\ntwo\nthree\nfour\nsix");
+    assert_eq!(
+        parser.screen().changes(&screen),
+        vec![vt100::Change::Scroll {
+            top: 1,
+            bottom: 4,
+            count: 1,
+            up: false,
+        }]
+    );
+}
+
+#[test]
+fn apply() {
+    let mut parser = vt100::Parser::new(6, 20, 0);
+    let blank = parser.screen().cell(0, 0).unwrap().clone();
+    let mut rendered = vec![(vec![blank.clone(); 20], false); 6];
+    let mut screen = parser.screen().clone();
+    for input in [
+        &b"hello\r\nworld"[..],
+        b"\x1b[32m\xe3\x81\x82\xe3\x81\x84\x1b[m",
+        b"\r\n\r\n\r\n\r\n\r\nscrolled",
+        b"\x1b[1;3H\x1b[2K\x1b[5;1H\x1b[1P",
+        b"\x1b[6;1H0123456789012345678901234",
+        b"\x1b[2;5r\x1b[2;1H\x1b[2L\x1b[r\x1b[H",
+        b"\x1b[4;1Hxxxxxxxxxxxxxxxxxxxxyyy",
+    ] {
+        parser.process(input);
+        for change in parser.screen().changes(&screen) {
+            match change {
+                vt100::Change::Scroll {
+                    top,
+                    bottom,
+                    count,
+                    up,
+                } => {
+                    let region =
+                        &mut rendered[usize::from(top)..=usize::from(bottom)];
+                    let count = usize::from(count);
+                    if up {
+                        region.rotate_left(count);
+                    } else {
+                        region.rotate_right(count);
+                    }
+                    let scrolled_in = if up {
+                        region.len() - count..region.len()
+                    } else {
+                        0..count
+                    };
+                    for row in &mut region[scrolled_in] {
+                        *row = (vec![blank.clone(); 20], false);
+                    }
+                }
+                vt100::Change::Cells { row, col, cells } => {
+                    let row = &mut rendered[usize::from(row)].0;
+                    for (i, cell) in cells.into_iter().enumerate() {
+                        row[usize::from(col) + i] = cell;
+                    }
+                }
+                vt100::Change::Wrapped { row, wrapped } => {
+                    rendered[usize::from(row)].1 = wrapped;
+                }
+                _ => {}
+            }
+        }
+        for (row, (cells, wrapped)) in (0..).zip(&rendered) {
+            for (col, cell) in (0..).zip(cells) {
+                assert_eq!(Some(cell), parser.screen().cell(row, col));
+            }
+            assert_eq!(*wrapped, parser.screen().row_wrapped(row));
+        }
+        screen = parser.screen().clone();
+    }
+}
@@ -0,0 +1,241 @@
+#[test]
+fn panes() {
+    let mut left = vt100::Parser::new(3, 4, 0);
+    left.process(b"abcdef\x1b[31mgh\x1b[mij");
+    let mut right = vt100::Parser::new(3, 6, 0);
+    right.process(b"\x1b[?1hxy\xe3\x81\x82\xe3\x81\x84\r\n12");
+
+    let mut compositor = vt100::Compositor::new(5, 12);
+    let mut term = vt100::Parser::new(5, 12, 0);
+    let render = |compositor: &mut vt100::Compositor,
+                  term: &mut vt100::Parser,
+                  left: &vt100::Parser,
+                  right: &vt100::Parser,
+                  focus_left: bool| {
+        let output = compositor.render(&[
+            vt100::Pane::new(left.screen(), vt100::Rect::new(1, 1, 3, 4))
+                .border(
+                    vt100::Border::new()
+                        .title("l")
+                        .fgcolor(vt100::Color::Idx(4)),
+                )
+                .focused(focus_left),
+            // clipped on the right, which cuts off half of a wide char
+            vt100::Pane::new(right.screen(), vt100::Rect::new(1, 7, 3, 5))
+                .border(vt100::Border::new())
+                .focused(!focus_left),
+        ]);
+        term.process(&output);
+        output
+    };
+
+    render(&mut compositor, &mut term, &left, &right, false);
+    assert_eq!(
+        term.screen().contents(),
+        "┌─l──┐┌─────\n\
+        │abcd││xyあ\n\
+        │efgh││12\n\
+        │ij  ││\n\
+        └────┘└─────"
+    );
+    assert_eq!(
+        term.screen().cell(0, 1).unwrap().fgcolor(),
+        vt100::Color::Idx(4)
+    );
+    assert_eq!(
+        term.screen().cell(2, 3).unwrap().fgcolor(),
+        vt100::Color::Idx(1)
+    );
+    assert!(!term.screen().cell(1, 11).unwrap().is_wide());
+    assert_eq!(term.screen().cursor_position(), (2, 9));
+    assert!(!term.screen().hide_cursor());
+    assert!(term.screen().application_cursor());
+    assert_eq!(
+        term.screen().state_formatted(),
+        compositor.screen().unwrap().state_formatted()
+    );
+
+    // only the changed cells and the new cursor state are redrawn
+    left.process(b"\x1b[Hx");
+    let output = render(&mut compositor, &mut term, &left, &right, true);
+    assert!(!output.windows(4).any(|w| w == b"12\xe3\x81"));
+    assert!(output.len() < 20, "{output:?}");
+    assert_eq!(term.screen().cell(1, 1).unwrap().contents(), "x");
+    assert_eq!(term.screen().cursor_position(), (1, 2));
+    assert!(!term.screen().application_cursor());
+
+    let output = render(&mut compositor, &mut term, &left, &right, true);
+    assert!(output.is_empty(), "{output:?}");
+
+    // a full redraw after a reset
+    compositor.reset();
+    let output = render(&mut compositor, &mut term, &left, &right, true);
+    assert_eq!(output, compositor.screen().unwrap().state_formatted());
+}
+
+#[test]
+fn overlays() {
+    let mut base = vt100::Parser::new(4, 10, 0);
+    base.process(b"0123456789\xe3\x81\x82\xe3\x81\x82\xe3\x81\x82");
+    let mut popup = vt100::Parser::new(1, 3, 0);
+    popup.process(b"\x1b[7mpop");
+
+    let mut compositor = vt100::Compositor::new(4, 10);
+    let mut term = vt100::Parser::new(4, 10, 0);
+    term.process(
+        &compositor.render(&[
+            vt100::Pane::new(base.screen(), vt100::Rect::new(0, 0, 4, 10))
+                .focused(true),
+            // overlaps half of a wide character, and hides the base cursor
+            vt100::Pane::new(popup.screen(), vt100::Rect::new(1, 3, 1, 4)),
+        ]),
+    );
+    assert_eq!(
+        term.screen().contents(),
+        "0123456789\n\
+        あ pop"
+    );
+    assert!(term.screen().cell(1, 3).unwrap().inverse());
+    assert!(!term.screen().cell(1, 6).unwrap().inverse());
+    assert!(term.screen().hide_cursor());
+
+    term.process(
+        &compositor.render(&[vt100::Pane::new(
+            base.screen(),
+            vt100::Rect::new(0, 0, 4, 10),
+        )
+        .focused(true)]),
+    );
+    assert_eq!(
+        term.screen().contents(),
+        "0123456789\n\
+        あああ"
+    );
+    assert!(!term.screen().hide_cursor());
+    assert_eq!(term.screen().cursor_position(), (1, 6));
+
+    compositor.set_size(2, 5);
+    assert_eq!(compositor.size(), (2, 5));
+    let mut term = vt100::Parser::new(2, 5, 0);
+    term.process(&compositor.render(&[vt100::Pane::new(
+        base.screen(),
+        vt100::Rect::new(0, 0, 4, 10),
+    )]));
+    assert_eq!(
+        term.screen().contents(),
+        "01234\n\
+        ああ"
+    );
+    assert!(term.screen().hide_cursor());
+}
+
+#[test]
+fn cursor_pending_wrap() {
+    // the cursor is waiting to wrap after filling the last column, which
+    // is drawn in the last column rather than on the border
+    let mut left = vt100::Parser::new(2, 4, 0);
+    left.process(b"abcd");
+    assert_eq!(left.screen().cursor_position(), (0, 4));
+    let right = vt100::Parser::new(2, 4, 0);
+
+    let mut compositor = vt100::Compositor::new(4, 11);
+    let mut term = vt100::Parser::new(4, 11, 0);
+    term.process(
+        &compositor.render(&[
+            vt100::Pane::new(left.screen(), vt100::Rect::new(1, 1, 2, 4))
+                .border(vt100::Border::new())
+                .focused(true),
+            vt100::Pane::new(right.screen(), vt100::Rect::new(1, 6, 2, 4))
+                .border(vt100::Border::new()),
+        ]),
+    );
+    assert_eq!(term.screen().cursor_position(), (1, 4));
+    assert!(!term.screen().hide_cursor());
+
+    // the same goes for panes which are wider than their screen
+    term.process(
+        &compositor.render(&[vt100::Pane::new(
+            left.screen(),
+            vt100::Rect::new(0, 0, 2, 8),
+        )
+        .focused(true)]),
+    );
+    assert_eq!(term.screen().cursor_position(), (0, 3));
+    assert!(!term.screen().hide_cursor());
+
+    // but not for panes which are clipped before the last column
+    term.process(
+        &compositor.render(&[vt100::Pane::new(
+            left.screen(),
+            vt100::Rect::new(0, 0, 2, 3),
+        )
+        .focused(true)]),
+    );
+    assert!(term.screen().hide_cursor());
+}
+
+#[test]
+fn state_reset() {
+    let mut pane = vt100::Parser::new(2, 4, 0);
+    pane.process(b"\x1b[?1h\x1b[?1000hab");
+    let mut compositor = vt100::Compositor::new(2, 4);
+    let mut term = vt100::Parser::new(2, 4, 0);
+    term.process(
+        &compositor.render(&[vt100::Pane::new(
+            pane.screen(),
+            vt100::Rect::new(0, 0, 2, 4),
+        )
+        .focused(true)]),
+    );
+    assert!(term.screen().application_cursor());
+    assert_eq!(
+        term.screen().mouse_protocol_mode(),
+        vt100::MouseProtocolMode::PressRelease
+    );
+
+    // without a focused pane, the input modes go back to their defaults
+    // and the uncovered cells are cleared
+    term.process(&compositor.render(&[vt100::Pane::new(
+        pane.screen(),
+        vt100::Rect::new(0, 0, 1, 1),
+    )]));
+    assert!(!term.screen().application_cursor());
+    assert_eq!(
+        term.screen().mouse_protocol_mode(),
+        vt100::MouseProtocolMode::None
+    );
+    assert!(term.screen().hide_cursor());
+    assert_eq!(term.screen().contents(), "a");
+    assert_eq!(
+        term.screen().state_formatted(),
+        compositor.screen().unwrap().state_formatted()
+    );
+}
+
+#[test]
+fn color_depth() {
+    let mut pane = vt100::Parser::new(1, 4, 0);
+    pane.process(b"\x1b[38;2;255;0;0mred");
+    let panes = [vt100::Pane::new(
+        pane.screen(),
+        vt100::Rect::new(0, 0, 1, 4),
+    )];
+    let mut compositor = vt100::Compositor::new(1, 4);
+    assert_eq!(compositor.color_depth(), vt100::ColorDepth::TrueColor);
+    let output = compositor.render(&panes);
+    assert!(output.windows(6).any(|w| w == b"38;2;2"), "{output:?}");
+
+    // changing the color depth redraws everything in the new colors
+    compositor.set_color_depth(vt100::ColorDepth::Indexed16);
+    assert_eq!(compositor.color_depth(), vt100::ColorDepth::Indexed16);
+    let output = compositor.render(&panes);
+    let mut term = vt100::Parser::new(1, 4, 0);
+    term.process(&output);
+    assert_eq!(term.screen().contents(), "red");
+    assert!(matches!(
+        term.screen().cell(0, 0).unwrap().fgcolor(),
+        vt100::Color::Idx(_)
+    ));
+    compositor.set_size(1, 3);
+    assert_eq!(compositor.color_depth(), vt100::ColorDepth::Indexed16);
+}
@@ -0,0 +1,91 @@
+#[test]
+fn dirty_rows() {
+    let mut parser = vt100::Parser::new(4, 10, 0);
+    assert_eq!(parser.screen().damage().dirty_rows(), &[0, 1, 2, 3]);
+
+    parser.screen_mut().clear_damage();
+    assert!(parser.screen().damage().is_empty());
+
+    parser.process(b"\x1b[2;3Hfoo\x1b[4H");
+    let damage = parser.screen().damage();
+    assert_eq!(damage.scrolled(), 0);
+    assert_eq!(damage.dirty_rows(), &[1]);
+    assert!(damage.is_dirty(1));
+    assert!(!damage.is_dirty(0));
+
+    parser.process(b"\x1b[3;1H\x1b[K\x1b[H\x1b[1@");
+    assert_eq!(parser.screen().damage().dirty_rows(), &[0, 1, 2]);
+
+    parser.screen_mut().clear_damage();
+    parser.process(b"\x1b[1;1H\x1b[?25l\x1b[31m");
+    assert!(parser.screen().damage().is_empty());
+}
+
+#[test]
+fn scrolling() {
+    let mut parser = vt100::Parser::new(4, 10, 0);
+    parser.process(b"1\r\n2\r\n3\r\n4");
+    parser.screen_mut().clear_damage();
+
+    parser.process(b"\r\n5\r\n6");
+    let damage = parser.screen().damage();
+    assert_eq!(damage.scrolled(), 2);
+    assert_eq!(damage.dirty_rows(), &[2, 3]);
+
+    parser.screen_mut().clear_damage();
+    parser.process(b"\x1b[H\x1bM");
+    let damage = parser.screen().damage();
+    assert_eq!(damage.scrolled(), -1);
+    assert_eq!(damage.dirty_rows(), &[0]);
+
+    // scrolling a region doesn't scroll the whole screen
+    parser.screen_mut().clear_damage();
+    parser.process(b"\x1b[2;3r\x1b[3H\n");
+    let damage = parser.screen().damage();
+    assert_eq!(damage.scrolled(), 0);
+    assert_eq!(damage.dirty_rows(), &[1, 2]);
+
+    // scrolling past the whole screen redraws everything
+    parser.screen_mut().clear_damage();
+    parser.process(b"\x1b[r\x1b[10S");
+    let damage = parser.screen().damage();
+    assert_eq!(damage.scrolled(), 0);
+    assert_eq!(damage.dirty_rows(), &[0, 1, 2, 3]);
+}
+
+#[test]
+fn scrollback() {
+    let mut parser = vt100::Parser::new(4, 10, 10);
+    parser.process(b"1\r\n2\r\n3\r\n4\r\n5\r\n6");
+    parser.screen_mut().clear_damage();
+
+    parser.process(b"\r\n7");
+    let damage = parser.screen().damage();
+    assert_eq!(damage.scrolled(), 1);
+    assert_eq!(damage.dirty_rows(), &[3]);
+
+    parser.screen_mut().set_scrollback(2);
+    assert_eq!(parser.screen().damage().dirty_rows(), &[0, 1, 2, 3]);
+    parser.screen_mut().clear_damage();
+    parser.process(b"8");
+    assert_eq!(parser.screen().damage().dirty_rows(), &[0, 1, 2, 3]);
+
+    parser.screen_mut().set_scrollback(0);
+    parser.screen_mut().clear_damage();
+    assert!(parser.screen().damage().is_empty());
+}
+
+#[test]
+fn full_redraw() {
+    let mut parser = vt100::Parser::new(4, 10, 0);
+    parser.screen_mut().clear_damage();
+    parser.process(b"\x1b[?1049h");
+    assert_eq!(parser.screen().damage().dirty_rows(), &[0, 1, 2, 3]);
+    parser.screen_mut().clear_damage();
+    parser.process(b"\x1b[?1049l");
+    assert_eq!(parser.screen().damage().dirty_rows(), &[0, 1, 2, 3]);
+
+    parser.screen_mut().clear_damage();
+    parser.screen_mut().set_size(3, 10);
+    assert_eq!(parser.screen().damage().dirty_rows(), &[0, 1, 2]);
+}
@@ -0,0 +1,193 @@
+type Sequence = (Option<u8>, Option<u8>, Vec<Vec<u16>>, char, Vec<u8>);
+
+#[derive(Default)]
+struct Dcs {
+    sequences: Vec<Sequence>,
+    escapes: Vec<u8>,
+}
+
+impl vt100::Callbacks for Dcs {
+    fn unhandled_dcs(
+        &mut self,
+        _: &mut vt100::Screen,
+        i1: Option<u8>,
+        i2: Option<u8>,
+        params: &[&[u16]],
+        c: char,
+        data: &[u8],
+    ) {
+        self.sequences.push((
+            i1,
+            i2,
+            params.iter().map(|p| p.to_vec()).collect(),
+            c,
+            data.to_vec(),
+        ));
+    }
+
+    fn unhandled_escape(
+        &mut self,
+        _: &mut vt100::Screen,
+        _i1: Option<u8>,
+        _i2: Option<u8>,
+        b: u8,
+    ) {
+        self.escapes.push(b);
+    }
+}
+
+#[test]
+fn sequences() {
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    parser.process(b"a\x1bP1;2:3$qfoo bar\x1b\\b");
+    parser.process(b"\x1bP+q544e\x9c");
+    // sequences which are cancelled, or interrupted by another escape
+    // sequence, aren't delivered
+    parser.process(b"\x1bPqabc\x18c\x1bPqdef\x1b[31m\x1bPqghi\x1b\x1ad");
+    assert_eq!(parser.screen().contents(), "abcd");
+    assert_eq!(
+        parser.callbacks().sequences,
+        [
+            (
+                Some(b'$'),
+                None,
+                vec![vec![1], vec![2, 3]],
+                'q',
+                b"foo bar".to_vec()
+            ),
+            (Some(b'+'), None, vec![vec![0]], 'q', b"544e".to_vec()),
+        ]
+    );
+    assert_eq!(parser.callbacks().escapes, b"\\");
+
+    // sequences split across calls to process are delivered whole
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    for b in b"\x1bP2|some data\x1b\\x" {
+        parser.process(&[*b]);
+    }
+    assert_eq!(parser.screen().contents(), "x");
+    assert_eq!(
+        parser.callbacks().sequences,
+        [(None, None, vec![vec![2]], '|', b"some data".to_vec())]
+    );
+
+    // a c1 st in the middle of the input, next to utf8 characters which
+    // contain the same byte
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    let cross = "\u{271c}".as_bytes();
+    parser.process(&[cross, b"\x1bP+q544e\x9c", cross, cross].concat());
+    assert_eq!(parser.screen().contents(), "\u{271c}\u{271c}\u{271c}");
+    assert_eq!(
+        parser.callbacks().sequences,
+        [(Some(b'+'), None, vec![vec![0]], 'q', b"544e".to_vec())]
+    );
+}
+
+#[test]
+fn size_limit() {
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    parser.process(b"\x1bPq");
+    parser.process(&vec![b'x'; 2 * 1024 * 1024]);
+    // the payload isn't kept around once it is too long
+    assert!(parser.save_snapshot().len() < 64 * 1024);
+    parser.process(b"\x1b\\\x1bPqsmall\x1b\\ok");
+    assert_eq!(parser.screen().contents(), "ok");
+    assert_eq!(
+        parser.callbacks().sequences,
+        [(None, None, vec![vec![0]], 'q', b"small".to_vec())]
+    );
+}
+
+#[test]
+fn tmux_passthrough() {
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    parser.process(
+        b"\x1bPtmux;ab\r\n\x1b\x1b[1mfoo\x1b\x1b]2;title\x07\x1b\\bar",
+    );
+    let mut expected = vt100::Parser::new(24, 80, 0);
+    expected.process(b"ab\r\n\x1b[1mfoo\x1b]2;title\x07bar");
+    assert_eq!(
+        parser.screen().contents_formatted(),
+        expected.screen().contents_formatted()
+    );
+    assert!(parser.callbacks().sequences.is_empty());
+    assert!(parser.callbacks().escapes.is_empty());
+
+    // byte at a time, with a regular dcs sequence wrapped inside
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    for b in
+        b"\x1bPtmux;\x1b\x1bP$qm\x1b\x1b\\\x1b\x1b]2;t\x1b\x1b\\\x1b\\\x1b\\"
+    {
+        parser.process(&[*b]);
+    }
+    assert_eq!(
+        parser.callbacks().sequences,
+        [(Some(b'$'), None, vec![vec![0]], 'q', b"m".to_vec())]
+    );
+    // only the st ending the passthrough sequence is swallowed, the st
+    // ending the wrapped sequences is handled as it would be normally
+    assert_eq!(parser.callbacks().escapes, b"\\\\\\");
+
+    // the wrapped data is only printed once the passthrough sequence ends
+    // with an st
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    parser.process(b"\x1bPtmux;secret\x18after ");
+    parser.process(b"\x1bPtmux;a\x1b\\\x1bPtmux;b\x9c");
+    assert_eq!(parser.screen().contents(), "after ab");
+    assert!(parser.callbacks().sequences.is_empty());
+    assert!(parser.callbacks().escapes.is_empty());
+}
+
+#[test]
+fn snapshot() {
+    let mut parser =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+    parser.process(b"\x1bPtmux;\x1b\x1b[31mred");
+    let snapshot = parser.save_snapshot();
+    let mut restored = vt100::Parser::load_snapshot_with_callbacks(
+        &snapshot,
+        Dcs::default(),
+    )
+    .unwrap();
+    restored.process(b"\x1b\\\x1bP1$qx\x1b\\");
+    assert_eq!(restored.screen().contents(), "red");
+    assert_eq!(
+        restored.screen().cell(0, 0).unwrap().fgcolor(),
+        vt100::Color::Idx(1)
+    );
+    assert_eq!(
+        restored.callbacks().sequences,
+        [(Some(b'$'), None, vec![vec![1]], 'q', b"x".to_vec())]
+    );
+    // the st ending the passthrough sequence was recognized, so only the
+    // st ending the regular dcs sequence is reported
+    assert_eq!(restored.callbacks().escapes, b"\\");
+
+    // sequences which are in progress, or waiting to see whether they
+    // were ended by an st, are restored too
+    for (before, after) in [
+        (&b"\x1bP1$qab"[..], &b"c\x1b\\"[..]),
+        (b"\x1bP1$qabc\x1b", b"\\"),
+    ] {
+        let mut parser =
+            vt100::Parser::new_with_callbacks(24, 80, 0, Dcs::default());
+        parser.process(before);
+        let mut restored = vt100::Parser::load_snapshot_with_callbacks(
+            &parser.save_snapshot(),
+            Dcs::default(),
+        )
+        .unwrap();
+        restored.process(after);
+        assert_eq!(
+            restored.callbacks().sequences,
+            [(Some(b'$'), None, vec![vec![1]], 'q', b"abc".to_vec())]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+#[test]
+fn text() {
+    let mut parser = vt100::Parser::new(3, 10, 0);
+    let screen = parser.screen_mut();
+    screen.set_cursor_position(1, 2);
+    screen.set_fgcolor(vt100::Color::Idx(2));
+    screen.set_bold(true);
+    screen.draw_text("foo\n");
+    screen.set_dim(true);
+    screen.set_inverse(true);
+    screen.draw_text("\u{3042}e\u{301}");
+    assert!(!screen.bold());
+    assert!(screen.dim());
+    screen.reset_attributes();
+    screen.set_underline(true);
+    screen.set_italic(true);
+    screen.draw_text("wrap");
+    assert_eq!(screen.contents(), "\n  foo\u{3042}e\u{301}wrap");
+    assert_eq!(screen.cursor_position(), (2, 2));
+    assert!(screen.row_wrapped(1));
+
+    let cell = screen.cell(1, 2).unwrap();
+    assert_eq!(cell.fgcolor(), vt100::Color::Idx(2));
+    assert!(cell.bold());
+    let cell = screen.cell(1, 5).unwrap();
+    assert!(cell.is_wide());
+    assert!(cell.dim());
+    assert!(cell.inverse());
+    assert_eq!(screen.cell(1, 7).unwrap().contents(), "e\u{301}");
+    let cell = screen.cell(2, 0).unwrap();
+    assert_eq!(cell.fgcolor(), vt100::Color::Default);
+    assert!(cell.italic());
+    assert!(cell.underline());
+
+    // drawing goes through the same code paths as processing output, so
+    // it can be mixed freely with it
+    let mut expected = vt100::Parser::new(3, 10, 0);
+    expected.process(
+        b"\x1b[2;3H\x1b[1;32mfoo\x1b[2;7m\xe3\x81\x82e\xcc\x81\
+        \x1b[m\x1b[3;4mwrap",
+    );
+    assert_eq!(
+        parser.screen().contents_formatted(),
+        expected.screen().contents_formatted()
+    );
+
+    parser.screen_mut().set_cursor_position(10, 20);
+    assert_eq!(parser.screen().cursor_position(), (2, 9));
+    parser.process(b"\x1b[3;5r\x1b[?6h");
+    parser.screen_mut().set_cursor_position(1, 1);
+    assert_eq!(parser.screen().cursor_position(), (1, 1));
+}
+
+#[test]
+fn rect() {
+    let mut parser = vt100::Parser::new(4, 8, 0);
+    parser.process(b"\xe3\x81\x82\xe3\x81\x82\xe3\x81\x82\xe3\x81\x82");
+    parser.process(b"abcdefgh\x1b[2;3H");
+    let screen = parser.screen_mut();
+
+    // partially overlaps wide characters on both edges
+    screen.set_bgcolor(vt100::Color::Idx(4));
+    screen.fill_rect(0, 1, 2, 4, '-');
+    assert_eq!(
+        screen.rows(0, 8).collect::<Vec<_>>(),
+        [" ---- \u{3042}", "a----fgh", "", ""]
+    );
+    assert_eq!(screen.cursor_position(), (1, 2));
+    assert_eq!(screen.cell(0, 1).unwrap().bgcolor(), vt100::Color::Idx(4));
+    assert!(!screen.cell(0, 0).unwrap().has_contents());
+
+    screen.fill_rect(2, 5, 10, 10, '\u{3042}');
+    assert_eq!(screen.cell(2, 5).unwrap().contents(), "\u{3042}");
+    assert!(screen.cell(2, 6).unwrap().is_wide_continuation());
+    assert!(!screen.cell(2, 7).unwrap().has_contents());
+    assert_eq!(screen.cell(3, 7).unwrap().bgcolor(), vt100::Color::Idx(4));
+    assert!(!screen.row_wrapped(2));
+
+    screen.set_bgcolor(vt100::Color::Default);
+    screen.erase_rect(0, 3, 3, 3);
+    assert_eq!(
+        screen.rows(0, 8).collect::<Vec<_>>(),
+        [" --   \u{3042}", "a--   gh", "", "     \u{3042}"]
+    );
+    assert_eq!(screen.cursor_position(), (1, 2));
+    assert_eq!(screen.cell(1, 3).unwrap().bgcolor(), vt100::Color::Default);
+    assert_eq!(screen.cell(1, 2).unwrap().bgcolor(), vt100::Color::Idx(4));
+
+    screen.erase_rect(4, 0, 1, 1);
+    screen.fill_rect(0, 8, 1, 1, 'x');
+    screen.fill_rect(0, 0, 1, 1, '\u{301}');
+    assert_eq!(screen.cursor_position(), (1, 2));
+}
@@ -0,0 +1,175 @@
+use vt100::Expect as _;
+
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+// a parser whose output has all been sent already
+fn parser(output: &[u8]) -> vt100::ChannelParser {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    sender.send(output.to_vec()).unwrap();
+    vt100::ChannelParser::new(vt100::Parser::new(4, 10, 0), receiver)
+}
+
+#[test]
+fn ended() {
+    let mut parser = parser(b"foo\r\n  bar\x1b[31mbaz\x1b[2;4H");
+
+    for expectation in [
+        vt100::Expectation::TextAt {
+            row: 1,
+            col: 2,
+            text: "barb".to_string(),
+        },
+        vt100::Expectation::TextAt {
+            row: 0,
+            col: 0,
+            text: "foo".to_string(),
+        },
+        vt100::Expectation::Contains("o\n  bar".to_string()),
+        vt100::Expectation::CursorAt { row: 1, col: 3 },
+        vt100::Expectation::Stable(TIMEOUT),
+        vt100::Expectation::Predicate(Box::new(|screen| {
+            screen.cell(1, 5).unwrap().fgcolor() == vt100::Color::Idx(1)
+        })),
+    ] {
+        parser.expect(&expectation, TIMEOUT).unwrap();
+    }
+
+    for expectation in [
+        vt100::Expectation::TextAt {
+            row: 1,
+            col: 1,
+            text: "bar".to_string(),
+        },
+        vt100::Expectation::TextAt {
+            row: 4,
+            col: 0,
+            text: String::new(),
+        },
+        vt100::Expectation::Contains("quux".to_string()),
+        vt100::Expectation::CursorAt { row: 0, col: 0 },
+    ] {
+        assert!(matches!(
+            parser.expect(&expectation, TIMEOUT),
+            Err(vt100::ExpectError::Eof { .. })
+        ));
+    }
+
+    let err = parser
+        .expect(&vt100::Expectation::Contains("quux".to_string()), TIMEOUT)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "output ended while waiting for text \"quux\"\n\
+        screen (4x10, cursor at (1, 3)):\n\
+        +----------+\n\
+        |foo       |\n\
+        |  barbaz  |\n\
+        |          |\n\
+        |          |\n\
+        +----------+\n"
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex() {
+    let mut parser = parser(b"foo\r\n  bar");
+    parser
+        .expect(
+            &vt100::Expectation::Regex(
+                regex::Regex::new(r"(?m)^\s+bar$").unwrap(),
+            ),
+            TIMEOUT,
+        )
+        .unwrap();
+    assert!(parser
+        .expect(
+            &vt100::Expectation::Regex(regex::Regex::new("^bar").unwrap()),
+            TIMEOUT,
+        )
+        .is_err());
+}
+
+#[test]
+fn channel() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let (done_sender, done_receiver) = std::sync::mpsc::channel::<()>();
+    let mut parser =
+        vt100::ChannelParser::new(vt100::Parser::new(4, 10, 0), receiver);
+    let thread = std::thread::spawn(move || {
+        sender.send(b"foo".to_vec()).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        sender.send(b"bar".to_vec()).unwrap();
+        for _ in 0..5 {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            sender.send(b".".to_vec()).unwrap();
+        }
+        // keep the output open until the main thread is done waiting
+        done_receiver.recv().unwrap();
+    });
+    parser
+        .expect(&vt100::Expectation::Contains("foobar".to_string()), TIMEOUT)
+        .unwrap();
+
+    // the screen keeps changing for a while after that
+    let start = std::time::Instant::now();
+    let stable = std::time::Duration::from_millis(200);
+    parser
+        .expect(&vt100::Expectation::Stable(stable), TIMEOUT)
+        .unwrap();
+    assert!(start.elapsed() >= stable);
+    assert_eq!(parser.screen().contents(), "foobar.....");
+
+    done_sender.send(()).unwrap();
+    thread.join().unwrap();
+    assert!(matches!(
+        parser.expect(
+            &vt100::Expectation::Contains("quux".to_string()),
+            TIMEOUT
+        ),
+        Err(vt100::ExpectError::Eof { .. })
+    ));
+    assert_eq!(parser.into_parser().screen().contents(), "foobar.....");
+}
+
+#[cfg(all(feature = "pty", unix))]
+#[test]
+fn session() {
+    let mut cmd = std::process::Command::new("sh");
+    cmd.args([
+        "-c",
+        "printf foo; sleep 0.2; printf bar; read x; printf baz",
+    ]);
+    let mut session = vt100::Session::spawn(cmd, 24, 80, 0).unwrap();
+    session
+        .expect(&vt100::Expectation::Contains("foobar".to_string()), TIMEOUT)
+        .unwrap();
+
+    let start = std::time::Instant::now();
+    let stable = std::time::Duration::from_millis(200);
+    session
+        .expect(&vt100::Expectation::Stable(stable), TIMEOUT)
+        .unwrap();
+    assert!(start.elapsed() >= stable);
+
+    let err = session
+        .expect(
+            &vt100::Expectation::Contains("baz".to_string()),
+            std::time::Duration::from_millis(100),
+        )
+        .unwrap_err();
+    assert!(matches!(err, vt100::ExpectError::Timeout { .. }));
+    assert!(err.to_string().contains("|foobar   "));
+
+    session.write_input(b"\n").unwrap();
+    session
+        .expect(&vt100::Expectation::CursorAt { row: 1, col: 3 }, TIMEOUT)
+        .unwrap();
+    assert!(matches!(
+        session.expect(
+            &vt100::Expectation::Contains("quux".to_string()),
+            TIMEOUT
+        ),
+        Err(vt100::ExpectError::Eof { .. })
+    ));
+}
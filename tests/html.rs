@@ -0,0 +1,75 @@
+const HEADER: &str = "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>\n<pre style=\"color:#e5e5e5;background-color:#000000\">";
+const FOOTER: &str = "</pre>\n</body>\n</html>\n";
+
+#[test]
+fn contents_html() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(4, 20, 0);
+    assert_eq!(
+        parser.screen().contents_html(&palette),
+        format!("{HEADER}{FOOTER}")
+    );
+
+    parser.process(b"a <b> & \"c\"\r\n\r\n\x1b[31mred\x1b[1mbold\x1b[22;3mit\x1b[m \x1b[4;7munder\x1b[m\r\n\x1b[2;44mdim\x1b[m");
+    assert_eq!(
+        parser.screen().contents_html(&palette),
+        format!(
+            "{HEADER}\
+a &lt;b&gt; &amp; &quot;c&quot;\n\
+\n\
+<span style=\"color:#cd0000\">red</span><span style=\"color:#cd0000;font-weight:bold\">bold</span><span style=\"color:#cd0000;font-style:italic\">it</span> <span style=\"color:#000000;background-color:#e5e5e5;text-decoration:underline\">under</span>\n\
+<span style=\"color:#9898e8;background-color:#0000ee\">dim</span>\n\
+{FOOTER}"
+        )
+    );
+}
+
+#[test]
+fn merge_and_trim() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(3, 10, 0);
+    parser.process(b"\x1b[32mgr\x1b[38;5;2meen\x1b[m  \r\n\x1b[41m  \x1b[m");
+    assert_eq!(
+        parser.screen().contents_html(&palette),
+        format!(
+            "{HEADER}\
+<span style=\"color:#00cd00\">green</span>\n\
+<span style=\"background-color:#cd0000\">  </span>\n\
+{FOOTER}"
+        )
+    );
+}
+
+#[test]
+fn wide() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(2, 10, 0);
+    parser.process("\x1b[31m日本\x1b[m語".as_bytes());
+    assert_eq!(
+        parser.screen().contents_html(&palette),
+        format!(
+            "{HEADER}<span style=\"color:#cd0000\">日本</span>語\n{FOOTER}"
+        )
+    );
+}
+
+#[test]
+fn scrollback_html() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(2, 10, 10);
+    parser.process(b"1\r\n2\r\n3\r\n4");
+    assert_eq!(
+        parser.screen().contents_html(&palette),
+        format!("{HEADER}3\n4\n{FOOTER}")
+    );
+    assert_eq!(
+        parser.screen().scrollback_html(&palette),
+        format!("{HEADER}1\n2\n3\n4\n{FOOTER}")
+    );
+
+    parser.screen_mut().set_scrollback(1);
+    assert_eq!(
+        parser.screen().contents_html(&palette),
+        format!("{HEADER}2\n3\n{FOOTER}")
+    );
+}
@@ -0,0 +1,55 @@
+#[test]
+fn default_colors() {
+    let palette = vt100::Palette::default();
+    assert_eq!(palette.indexed(1), (0xcd, 0x00, 0x00));
+    assert_eq!(palette.indexed(12), (0x5c, 0x5c, 0xff));
+    assert_eq!(palette.indexed(16), (0x00, 0x00, 0x00));
+    assert_eq!(palette.indexed(21), (0x00, 0x00, 0xff));
+    assert_eq!(palette.indexed(196), (0xff, 0x00, 0x00));
+    assert_eq!(palette.indexed(231), (0xff, 0xff, 0xff));
+    assert_eq!(palette.indexed(232), (0x08, 0x08, 0x08));
+    assert_eq!(palette.indexed(255), (0xee, 0xee, 0xee));
+    assert_eq!(palette.fgcolor(vt100::Color::Default), (0xe5, 0xe5, 0xe5));
+    assert_eq!(palette.bgcolor(vt100::Color::Default), (0x00, 0x00, 0x00));
+    assert_eq!(
+        palette.fgcolor(vt100::Color::Rgb(1, 2, 3)),
+        palette.bgcolor(vt100::Color::Rgb(1, 2, 3)),
+    );
+}
+
+#[test]
+fn resolve() {
+    let mut palette = vt100::Palette::default();
+    palette.set_default_fgcolor((0xff, 0xff, 0xff));
+    palette.set_default_bgcolor((0x30, 0x30, 0x30));
+    palette.set_indexed(4, (0x00, 0x00, 0x80));
+
+    let mut parser = vt100::Parser::default();
+    parser.process(b"a\x1b[1;31mb\x1b[7mc\x1b[m\x1b[2;44md\x1b[7me");
+    let screen = parser.screen();
+
+    assert_eq!(
+        palette.resolve(screen.cell(0, 0).unwrap(), false),
+        ((0xff, 0xff, 0xff), (0x30, 0x30, 0x30)),
+    );
+    assert_eq!(
+        palette.resolve(screen.cell(0, 1).unwrap(), false),
+        ((0xcd, 0x00, 0x00), (0x30, 0x30, 0x30)),
+    );
+    assert_eq!(
+        palette.resolve(screen.cell(0, 1).unwrap(), true),
+        ((0xff, 0x00, 0x00), (0x30, 0x30, 0x30)),
+    );
+    assert_eq!(
+        palette.resolve(screen.cell(0, 2).unwrap(), true),
+        ((0x30, 0x30, 0x30), (0xff, 0x00, 0x00)),
+    );
+    assert_eq!(
+        palette.resolve(screen.cell(0, 3).unwrap(), false),
+        ((0xaa, 0xaa, 0xd4), (0x00, 0x00, 0x80)),
+    );
+    assert_eq!(
+        palette.resolve(screen.cell(0, 4).unwrap(), false),
+        ((0x55, 0x55, 0xaa), (0xff, 0xff, 0xff)),
+    );
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "png")]
+
+fn render(
+    screen: &vt100::Screen,
+    palette: &vt100::Palette,
+) -> (u32, u32, Vec<u8>) {
+    let mut out = vec![];
+    screen.write_png(palette, &mut out).unwrap();
+    let decoder = png::Decoder::new(out.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+    pixels.truncate(info.buffer_size());
+    (info.width, info.height, pixels)
+}
+
+fn pixel(width: u32, pixels: &[u8], x: u32, y: u32) -> (u8, u8, u8) {
+    let idx = usize::try_from((y * width + x) * 3).unwrap();
+    (pixels[idx], pixels[idx + 1], pixels[idx + 2])
+}
+
+#[test]
+fn write_png() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(2, 4, 0);
+    parser.process(b"\x1b[?25l\x1b[41m \x1b[m\x1b[7m \x1b[m\x1b[4m \x1b[m|");
+    let (width, height, pixels) = render(parser.screen(), &palette);
+    assert_eq!((width, height), (32, 26));
+
+    // background colors, including inverse
+    assert_eq!(pixel(width, &pixels, 0, 0), (0xcd, 0x00, 0x00));
+    assert_eq!(pixel(width, &pixels, 8, 0), (0xe5, 0xe5, 0xe5));
+    assert_eq!(pixel(width, &pixels, 16, 0), (0x00, 0x00, 0x00));
+
+    // underline
+    assert_eq!(pixel(width, &pixels, 16, 12), (0xe5, 0xe5, 0xe5));
+    assert_eq!(pixel(width, &pixels, 16, 11), (0x00, 0x00, 0x00));
+
+    // glyphs
+    assert_eq!(pixel(width, &pixels, 27, 5), (0xe5, 0xe5, 0xe5));
+    assert_eq!(pixel(width, &pixels, 26, 5), (0x00, 0x00, 0x00));
+
+    // the second row is empty
+    assert!(pixels[usize::try_from(width * 13 * 3).unwrap()..]
+        .iter()
+        .all(|&c| c == 0));
+}
+
+#[test]
+fn wide() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(1, 3, 0);
+    parser.process("\x1b[?25l\x1b[44m日\x1b[m".as_bytes());
+    let (width, _, pixels) = render(parser.screen(), &palette);
+
+    // the background and the fallback glyph span both cells
+    assert_eq!(pixel(width, &pixels, 15, 0), (0x00, 0x00, 0xee));
+    assert_eq!(pixel(width, &pixels, 16, 0), (0x00, 0x00, 0x00));
+    assert_eq!(pixel(width, &pixels, 2, 2), (0xe5, 0xe5, 0xe5));
+    assert_eq!(pixel(width, &pixels, 13, 2), (0xe5, 0xe5, 0xe5));
+    assert_eq!(pixel(width, &pixels, 7, 5), (0x00, 0x00, 0xee));
+}
+
+#[test]
+fn cursor() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(1, 2, 0);
+    parser.process(b"\x1b[C");
+    let (width, _, pixels) = render(parser.screen(), &palette);
+    assert_eq!(pixel(width, &pixels, 0, 0), (0x00, 0x00, 0x00));
+    assert_eq!(pixel(width, &pixels, 8, 0), (0x72, 0x72, 0x72));
+}
+
+#[test]
+fn empty() {
+    let palette = vt100::Palette::default();
+    let parser = vt100::Parser::new(2, 0, 0);
+    let err = parser.screen().write_png(&palette, vec![]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
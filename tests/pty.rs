@@ -0,0 +1,228 @@
+#![cfg(all(feature = "pty", unix))]
+
+fn sh(script: &str) -> std::process::Command {
+    let mut cmd = std::process::Command::new("sh");
+    cmd.args(["-c", script]);
+    cmd
+}
+
+// processes output until the screen matches, or the child exits
+fn read_until<CB: vt100::Callbacks>(
+    session: &mut vt100::Session<CB>,
+    mut done: impl FnMut(&vt100::Screen) -> bool,
+) {
+    let deadline =
+        std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while !done(session.screen()) {
+        assert!(std::time::Instant::now() < deadline, "timed out");
+        match session.read() {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                let mut fds = [nix::poll::PollFd::new(
+                    std::os::fd::AsFd::as_fd(session),
+                    nix::poll::PollFlags::POLLIN,
+                )];
+                nix::poll::poll(&mut fds, 100_u16).unwrap();
+            }
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
+
+#[test]
+fn output() {
+    let mut session = vt100::Session::spawn(
+        sh("printf 'foo\\033[31mbar %s' \"$TERM\"; exit 3"),
+        24,
+        80,
+        0,
+    )
+    .unwrap();
+    read_until(&mut session, |_| false);
+    assert_eq!(session.screen().contents(), "foobar xterm-256color");
+    assert_eq!(
+        session.screen().cell(0, 3).unwrap().fgcolor(),
+        vt100::Color::Idx(1)
+    );
+    assert_eq!(session.wait().unwrap().code(), Some(3));
+    assert_eq!(session.read().unwrap(), 0);
+
+    let mut cmd = sh("printf %s \"$TERM\"");
+    cmd.env("TERM", "screen");
+    let mut session = vt100::Session::spawn(cmd, 24, 80, 0).unwrap();
+    read_until(&mut session, |_| false);
+    assert_eq!(session.screen().contents(), "screen");
+}
+
+#[test]
+fn size() {
+    let mut session =
+        vt100::Session::spawn(sh("stty size; read x; stty size"), 24, 80, 0)
+            .unwrap();
+    read_until(&mut session, |screen| screen.contents() == "24 80");
+    session.set_size(30, 100).unwrap();
+    assert_eq!(session.screen().size(), (30, 100));
+    session.write_input(b"\n").unwrap();
+    read_until(&mut session, |_| false);
+    assert_eq!(session.screen().contents(), "24 80\n\n30 100");
+    assert!(session.wait().unwrap().success());
+}
+
+#[test]
+fn requests() {
+    let mut session = vt100::Session::spawn(
+        sh("printf 'ab\\033[8;10;20t'; read x; stty size"),
+        24,
+        80,
+        0,
+    )
+    .unwrap();
+    read_until(&mut session, |screen| screen.contents() == "ab");
+    read_until(&mut session, |screen| screen.size() == (10, 20));
+    session.write_input(b"\n").unwrap();
+    read_until(&mut session, |_| false);
+    assert_eq!(session.screen().contents(), "ab\n10 20");
+
+    let mut session = vt100::Session::spawn(
+        sh("stty raw -echo; printf 'ab\\033[6n\\033[c'; \
+            head -c 13 | od -An -c"),
+        24,
+        80,
+        0,
+    )
+    .unwrap();
+    read_until(&mut session, |_| false);
+    let contents = session.screen().contents();
+    assert!(
+        contents
+            .contains("033   [   1   ;   3   R 033   [   ?   1   ;   2   c"),
+        "{contents}"
+    );
+}
+
+#[test]
+fn cursor_position_wrap() {
+    // the cursor is still in the last column while a wrap is pending
+    let mut session = vt100::Session::spawn(
+        sh("stty raw -echo; printf '%10s\\033[6n' ''; \
+            head -c 7 | od -An -c"),
+        24,
+        10,
+        0,
+    )
+    .unwrap();
+    read_until(&mut session, |_| false);
+    let contents = session.screen().contents();
+    assert!(
+        contents.contains("033   [   1   ;   1   0   R"),
+        "{contents}"
+    );
+}
+
+#[test]
+fn cursor_position_origin_mode() {
+    // the position is relative to the scrolling region in origin mode
+    let mut session = vt100::Session::spawn(
+        sh("stty raw -echo; printf '\\033[5;10r\\033[?6h\\033[2;3H\\033[6n'; \
+            head -c 6 | od -An -c"),
+        24,
+        80,
+        0,
+    )
+    .unwrap();
+    read_until(&mut session, |_| false);
+    let contents = session.screen().contents();
+    assert!(contents.contains("033   [   2   ;   3   R"), "{contents}");
+}
+
+#[derive(Default)]
+struct Queries {
+    csi: Vec<(Vec<Vec<u16>>, char)>,
+}
+
+impl vt100::Callbacks for Queries {
+    fn unhandled_csi(
+        &mut self,
+        _: &mut vt100::Screen,
+        _i1: Option<u8>,
+        _i2: Option<u8>,
+        params: &[&[u16]],
+        c: char,
+    ) {
+        self.csi
+            .push((params.iter().map(|p| p.to_vec()).collect(), c));
+    }
+}
+
+#[test]
+fn queries_passed_on() {
+    // queries are answered, but the callbacks still see them
+    let mut session = vt100::Session::spawn_with_callbacks(
+        sh("stty raw -echo; printf 'ab\\033[5n\\033[c'; \
+            head -c 11 | od -An -c"),
+        24,
+        80,
+        0,
+        Queries::default(),
+    )
+    .unwrap();
+    read_until(&mut session, |_| false);
+    let contents = session.screen().contents();
+    assert!(
+        contents.contains("033   [   0   n 033   [   ?   1   ;   2   c"),
+        "{contents}"
+    );
+    assert_eq!(
+        session.callbacks().csi,
+        [(vec![vec![5]], 'n'), (vec![vec![0]], 'c')]
+    );
+    assert_eq!(session.parser().screen().contents(), contents);
+    assert_eq!(
+        session.parser().callbacks().callbacks().csi,
+        session.callbacks().csi
+    );
+}
+
+#[test]
+fn read_limit() {
+    // a single read doesn't process all of the output at once, even if it
+    // is all available
+    let mut session =
+        vt100::Session::spawn(sh("yes | head -c 1000000"), 24, 80, 0)
+            .unwrap();
+    let mut total = 0;
+    let deadline =
+        std::time::Instant::now() + std::time::Duration::from_secs(10);
+    loop {
+        assert!(std::time::Instant::now() < deadline, "timed out");
+        match session.read() {
+            Ok(0) => break,
+            Ok(n) => {
+                assert!(n <= 128 * 1024, "{n}");
+                total += n;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            Err(e) => panic!("{e}"),
+        }
+    }
+    assert!(total >= 1_000_000, "{total}");
+}
+
+#[test]
+fn drop_reaps_child() {
+    let session = vt100::Session::spawn(sh("sleep 60"), 24, 80, 0).unwrap();
+    let pid = nix::unistd::Pid::from_raw(session.id().try_into().unwrap());
+    drop(session);
+    // the child has been killed and waited for, so it is no longer our
+    // child (not even as a zombie)
+    assert_eq!(
+        nix::sys::wait::waitpid(
+            pid,
+            Some(nix::sys::wait::WaitPidFlag::WNOHANG)
+        ),
+        Err(nix::errno::Errno::ECHILD)
+    );
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "testing")]
+
+fn screen(name: &str) -> vt100::Screen {
+    let mut parser = vt100::Parser::new(4, 8, 0);
+    match name {
+        "screen" => parser.process(
+            b"fo\x1b[1;31mo\x1b[m \xe3\x81\x82\x1b[44mba\r\n\
+            \x1b[48;2;1;2;3mx\x1b[mwrapped!!\x1b[4;3H",
+        ),
+        "changed" => parser.process(
+            b"fo\x1b[1;32mx\x1b[m \xe3\x81\x82\x1b[44mba\r\n\
+            \x1b[48;2;1;2;3mx\x1b[mwrappe\x1b[4;3H",
+        ),
+        // more distinct sets of attributes than there are single character
+        // labels
+        "many" | "many-changed" => {
+            parser.screen_mut().set_size(1, 70);
+            for i in 0..70 {
+                let i = if name == "many-changed" && i == 65 {
+                    1
+                } else {
+                    i
+                };
+                parser.process(format!("\x1b[38;5;{i}mx").as_bytes());
+            }
+        }
+        _ => unreachable!("{name}"),
+    }
+    parser.screen().clone()
+}
+
+#[test]
+fn format() {
+    assert_eq!(
+        screen("screen").contents_snapshot(),
+        "size: 4x8\n\
+        cursor: 3,2\n\
+        +--v-----+\n\
+        |foo \u{3042}ba|\n\
+        |xwrapped\\\n\
+        |!!      |\n\
+        >        |\n\
+        +--------+\n\
+        attributes:\n\
+        +--------+\n\
+        |..a...bb|\n\
+        |c.......|\n\
+        |........|\n\
+        |........|\n\
+        +--------+\n\
+        a: fg=1 bold\n\
+        b: bg=4\n\
+        c: bg=#010203\n"
+    );
+
+    let mut parser = vt100::Parser::new(2, 4, 0);
+    parser.process(b"ab\x1b[?25l");
+    assert_eq!(
+        parser.screen().contents_snapshot(),
+        "size: 2x4\n\
+        cursor: 0,2 (hidden)\n\
+        +--v-+\n\
+        >ab  |\n\
+        |    |\n\
+        +----+\n"
+    );
+}
+
+#[test]
+fn format_many_attributes() {
+    let snapshot = screen("many").contents_snapshot();
+    let mut lines = snapshot.lines().skip(5);
+    assert_eq!(lines.next(), Some("attributes:"));
+    lines.next();
+    let grid = lines.next().unwrap();
+    assert_eq!(grid.len(), 70 * 2 + 2);
+    assert!(grid.starts_with("|aaabacad"), "{grid}");
+    assert!(grid.ends_with("a8a9babbbcbdbebfbgbh|"), "{grid}");
+    lines.next();
+    let legend: Vec<_> = lines.collect();
+    assert_eq!(legend.len(), 70);
+    assert_eq!(legend[0], "aa: fg=0");
+    assert_eq!(legend[61], "a9: fg=61");
+    assert_eq!(legend[62], "ba: fg=62");
+    assert_eq!(legend[69], "bh: fg=69");
+}
+
+// the environment variables used to pass arguments to the child test
+const PATH_VAR: &str = "VT100_TEST_SNAPSHOT_PATH";
+const SCREEN_VAR: &str = "VT100_TEST_SNAPSHOT_SCREEN";
+
+// run in a separate process by the tests below, so that each call can set
+// the environment variable which controls whether snapshots are accepted
+#[test]
+fn child() {
+    let (Some(path), Ok(name)) =
+        (std::env::var_os(PATH_VAR), std::env::var(SCREEN_VAR))
+    else {
+        return;
+    };
+    vt100::assert_screen_snapshot(&screen(&name), path);
+}
+
+fn run_child(
+    path: &std::path::Path,
+    name: &str,
+    accept: Option<&str>,
+) -> (bool, String) {
+    let mut cmd =
+        std::process::Command::new(std::env::current_exe().unwrap());
+    cmd.args(["--exact", "child", "--nocapture"])
+        .env(PATH_VAR, path)
+        .env(SCREEN_VAR, name);
+    match accept {
+        Some(accept) => cmd.env("VT100_ACCEPT_SNAPSHOTS", accept),
+        None => cmd.env_remove("VT100_ACCEPT_SNAPSHOTS"),
+    };
+    let output = cmd.output().unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn assert() {
+    vt100::assert_screen_snapshot!(&screen("screen"), "screen");
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots/screen.snap");
+    let (success, stderr) = run_child(&path, "screen", None);
+    assert!(success, "{stderr}");
+    let (success, stderr) = run_child(&path, "changed", None);
+    assert!(!success);
+    assert!(
+        stderr.contains(
+            "cell (0, 2): expected \"o\" (fg=1 bold), got \"x\" (fg=2 bold)\n\
+            cell (1, 7): expected \"d\", got \"\"\n\
+            row 1: expected wrapped=true, got wrapped=false\n\
+            cell (2, 0): expected \"!\", got \"\"\n\
+            cell (2, 1): expected \"!\", got \"\"\n\
+            expected:\n"
+        ),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn accept() {
+    let dir = std::env::temp_dir()
+        .join(format!("vt100-screen-snapshot-{}", std::process::id()));
+    let path = dir.join("new/new.snap");
+
+    // missing snapshots are only created when accepting snapshots
+    for accept in [None, Some(""), Some("0")] {
+        let (success, stderr) = run_child(&path, "screen", accept);
+        assert!(!success, "{accept:?}");
+        assert!(stderr.contains("failed to read snapshot"), "{stderr}");
+    }
+    let (success, stderr) = run_child(&path, "screen", Some("1"));
+    assert!(success, "{stderr}");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        screen("screen").contents_snapshot()
+    );
+    let (success, stderr) = run_child(&path, "screen", None);
+    assert!(success, "{stderr}");
+
+    // changed snapshots are overwritten, including when the labels in the
+    // attribute grid are more than one character long
+    let (success, stderr) = run_child(&path, "many", None);
+    assert!(!success, "{stderr}");
+    let (success, stderr) = run_child(&path, "many", Some("1"));
+    assert!(success, "{stderr}");
+    let (success, stderr) = run_child(&path, "many-changed", None);
+    assert!(!success);
+    assert!(
+        stderr.contains(
+            "cell (0, 65): expected \"x\" (fg=65), got \"x\" (fg=1)\n\
+            expected:\n"
+        ),
+        "{stderr}"
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -198,3 +198,107 @@ fn gen_nums(range: RangeInclusive<u8>, join: &str) -> String {
         .collect::<Vec<String>>()
         .join(join)
 }
+
+#[test]
+fn lines_outside_scroll_region() {
+    let mut parser = vt100::Parser::new(6, 10, 0);
+    parser.process(gen_nums(1..=6, "\r\n").as_bytes());
+    parser.process(b"\x1b[2;3r\x1b[5H\x1b[L");
+    assert_eq!(parser.screen().contents(), "1\n2\n4\n5\n\n6");
+    parser.process(b"\x1b[M");
+    assert_eq!(parser.screen().contents(), "1\n2\n4\n\n\n6");
+    parser.process(b"\x1b[4H7\x1b[6H\x1b[2M");
+    assert_eq!(parser.screen().contents(), "1\n2\n4\n\n7\n6");
+}
+
+#[test]
+fn large_scroll_counts() {
+    let mut parser = vt100::Parser::new(6, 10, 0);
+    parser.process(gen_nums(1..=6, "\r\n").as_bytes());
+    parser.process(b"\x1b[2;5r\x1b[3H\x1b[100L");
+    assert_eq!(parser.screen().contents(), "1\n2\n\n\n\n6");
+
+    parser.process(b"\x1b[r");
+    parser.process(gen_nums(1..=6, "\r\n").as_bytes());
+    parser.process(b"\x1b[2;5r\x1b[100S");
+    assert_eq!(parser.screen().contents(), "1\n\n\n\n\n6");
+
+    parser.process(b"\x1b[r");
+    parser.process(gen_nums(1..=6, "\r\n").as_bytes());
+    parser.process(b"\x1b[100T");
+    assert_eq!(parser.screen().contents(), "");
+}
+
+#[test]
+fn scrollback_cells() {
+    let mut parser = vt100::Parser::new(3, 20, 10);
+    parser.process(gen_nums(1..=10, "\r\n").as_bytes());
+    parser.screen_mut().set_scrollback(2);
+    assert_eq!(parser.screen().cell(0, 0).unwrap().contents(), "6");
+    assert_eq!(parser.screen().cell(1, 0).unwrap().contents(), "7");
+    assert_eq!(parser.screen().cell(2, 0).unwrap().contents(), "8");
+    assert!(parser.screen().cell(3, 0).is_none());
+}
+
+#[test]
+fn scrollback_attributes() {
+    let mut parser = vt100::Parser::new(2, 10, 10);
+    parser
+        .process("\x1b[31mab\x1b[44m \x1b[m日e\u{301}\x1b[1m\r\n".as_bytes());
+    parser.process(b"0123456789xyz\r\n\r\n");
+    let (rows, cols) = parser.screen().size();
+    let screen = parser.screen().clone();
+
+    parser.screen_mut().set_scrollback(3);
+    assert_eq!(parser.screen().contents(), "ab 日e\u{301}\n0123456789");
+    assert!(parser.screen().row_wrapped(1));
+    let cell = parser.screen().cell(0, 2).unwrap();
+    assert_eq!(cell.fgcolor(), vt100::Color::Idx(1));
+    assert_eq!(cell.bgcolor(), vt100::Color::Idx(4));
+    assert!(parser.screen().cell(0, 3).unwrap().is_wide());
+    assert!(parser.screen().cell(0, 4).unwrap().is_wide_continuation());
+    assert_eq!(parser.screen().cell(0, 5).unwrap().contents(), "e\u{301}");
+    assert_eq!(parser.screen().cell(0, 9).unwrap().contents(), "");
+    assert_eq!(
+        parser.screen().rows_formatted(0, cols).next().unwrap(),
+        b"\x1b[31mab\x1b[44m \x1b[m\xe6\x97\xa5e\xcc\x81"
+    );
+
+    parser.screen_mut().set_scrollback(0);
+    assert_eq!(parser.screen().contents_diff(&screen), b"");
+    assert_eq!(parser.screen().size(), (rows, cols));
+}
+
+#[test]
+fn scrollback_memory_limit() {
+    let mut parser = vt100::Parser::new(24, 200, 1000);
+    assert_eq!(parser.screen().scrollback_memory_limit(), None);
+    parser.process(gen_nums(1..=200, "\r\n").as_bytes());
+    parser.screen_mut().set_scrollback(1000);
+    assert_eq!(parser.screen().scrollback(), 176);
+
+    // rows in the scrollback shouldn't take up space for blank cells
+    let usage = parser.screen().scrollback_memory_usage();
+    assert!(usage > 0);
+    assert!(usage < 176 * 200);
+
+    let limit = usage / 2;
+    parser.screen_mut().set_scrollback_memory_limit(Some(limit));
+    assert_eq!(parser.screen().scrollback_memory_limit(), Some(limit));
+    assert!(parser.screen().scrollback_memory_usage() <= limit);
+    let scrollback = parser.screen().scrollback();
+    assert!(scrollback < 176);
+    assert!(scrollback > 0);
+    assert_eq!(
+        parser.screen().contents().lines().next().unwrap(),
+        (177 - scrollback).to_string()
+    );
+
+    parser.screen_mut().set_scrollback(0);
+    parser.process(gen_nums(1..=200, "\r\n").as_bytes());
+    assert!(parser.screen().scrollback_memory_usage() <= limit);
+
+    parser.screen_mut().set_scrollback_memory_limit(None);
+    parser.process(gen_nums(1..=200, "\r\n").as_bytes());
+    assert!(parser.screen().scrollback_memory_usage() > limit);
+}
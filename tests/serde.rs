@@ -0,0 +1,118 @@
+#![cfg(feature = "serde")]
+
+mod helpers;
+
+fn round_trip(screen: &vt100::Screen) -> vt100::Screen {
+    let json = serde_json::to_string(screen).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn cell() {
+    let mut parser = vt100::Parser::default();
+    parser.process(b"\x1b[1;4;31;48;2;1;2;3m\xe3\x81\x82e\xcc\x81");
+    for col in 0..3 {
+        let cell = parser.screen().cell(0, col).unwrap();
+        let json = serde_json::to_string(cell).unwrap();
+        let got: vt100::Cell = serde_json::from_str(&json).unwrap();
+        assert_eq!(&got, cell);
+    }
+
+    let json =
+        serde_json::to_value(parser.screen().cell(0, 0).unwrap()).unwrap();
+    assert_eq!(json["contents"], "\u{3042}");
+    assert_eq!(json["is_wide"], true);
+    assert_eq!(json["attrs"]["bold"], true);
+
+    let mut bad = json.clone();
+    bad["attrs"]["dim"] = true.into();
+    assert!(serde_json::from_value::<vt100::Cell>(bad).is_err());
+    let mut bad = json;
+    bad["contents"] = "a\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}".into();
+    assert!(serde_json::from_value::<vt100::Cell>(bad).is_err());
+}
+
+#[test]
+fn screen() {
+    let mut parser = vt100::Parser::new(10, 20, 50);
+    for i in 0..30 {
+        parser.process(format!("\x1b[3{}mline {i}\r\n", i % 8).as_bytes());
+    }
+    parser.process(b"\x1b[m\x1b[?1h\x1b=\x1b[?2004h\x1b[?1002h\x1b[?1006h");
+    parser.process(b"\x1b[3;8r\x1b[5;5H\x1b7\x1b[1;3m\xe3\x81\x82");
+    parser.screen_mut().set_scrollback(3);
+
+    let screen = round_trip(parser.screen());
+    assert!(helpers::compare_screens(&screen, parser.screen()));
+    assert_eq!(screen.scrollback(), 3);
+    assert_eq!(
+        screen.scrollback_memory_usage(),
+        parser.screen().scrollback_memory_usage()
+    );
+
+    // the scroll region, saved cursor and current attributes should all
+    // carry over
+    let mut restored = vt100::Parser::from_screen(screen);
+    restored.screen_mut().set_scrollback(0);
+    parser.screen_mut().set_scrollback(0);
+    let input = b"\x1b8foo\x1b[8H\r\n\r\nbar\x1b[?1049h\x1b[Hbaz";
+    parser.process(input);
+    restored.process(input);
+    assert!(helpers::compare_screens(restored.screen(), parser.screen()));
+
+    let screen = round_trip(parser.screen());
+    assert!(screen.alternate_screen());
+    assert!(helpers::compare_screens(&screen, parser.screen()));
+    let mut restored = vt100::Parser::from_screen(screen);
+    let input = b"\x1b[?1049l\x1b[r\x1b[10H\r\nqux";
+    parser.process(input);
+    restored.process(input);
+    assert!(helpers::compare_screens(restored.screen(), parser.screen()));
+}
+
+#[test]
+fn invalid_screen() {
+    let parser = vt100::Parser::new(4, 10, 0);
+    let json = serde_json::to_value(parser.screen()).unwrap();
+
+    let mut bad = json.clone();
+    bad["grid"]["pos"]["row"] = 4.into();
+    assert!(serde_json::from_value::<vt100::Screen>(bad).is_err());
+
+    let mut bad = json;
+    bad["grid"]["rows"][0]["cells"]
+        .as_array_mut()
+        .unwrap()
+        .pop();
+    assert!(serde_json::from_value::<vt100::Screen>(bad).is_err());
+}
+
+#[test]
+fn invalid_wide_cells() {
+    let mut parser = vt100::Parser::new(2, 4, 0);
+    parser.process("\u{3042}".as_bytes());
+    let json = serde_json::to_value(parser.screen()).unwrap();
+    assert!(serde_json::from_value::<vt100::Screen>(json.clone()).is_ok());
+
+    let wide = json["grid"]["rows"][0]["cells"][0].clone();
+    let continuation = json["grid"]["rows"][0]["cells"][1].clone();
+    let blank = json["grid"]["rows"][0]["cells"][2].clone();
+    let mut both = wide.clone();
+    both["is_wide_continuation"] = true.into();
+    for cells in [
+        // a wide cell in the last column
+        [&blank, &blank, &blank, &wide],
+        // a wide cell without a continuation
+        [&wide, &blank, &blank, &blank],
+        // a continuation without a wide cell
+        [&blank, &continuation, &blank, &blank],
+        [&continuation, &blank, &blank, &blank],
+        // a cell which is both
+        [&wide, &both, &continuation, &blank],
+    ] {
+        let mut bad = json.clone();
+        bad["grid"]["rows"][0]["cells"] =
+            cells.into_iter().cloned().collect();
+        assert!(serde_json::from_value::<vt100::Screen>(bad).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+mod helpers;
+
+const INPUT: &[u8] = b"\x1b[31mred\x1b[m \xe3\x81\x82 e\xcc\x81\r\n\
+    \x1b]2;title\x07\x1b]1;icon\x1b\\\x1b[?1049h\x1b[2;3Halt\x1b[?1049l\
+    \x1b[1\n;4mcan\x1b[3\x18celled\x1bP1$qm\x1b\\\x1b[?25l\x1b[5;10r\
+    \x1b[10H\r\n\r\nscrolled\x1b7\x1b[48;2;1;2;3m\x1b[H\x1b[2Kdone";
+
+#[derive(Default)]
+struct Titles {
+    titles: Vec<Vec<u8>>,
+}
+
+impl vt100::Callbacks for Titles {
+    fn set_window_title(&mut self, _: &mut vt100::Screen, title: &[u8]) {
+        self.titles.push(title.to_vec());
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mut parser = vt100::Parser::new(24, 80, 100);
+    for i in 0..50 {
+        parser.process(format!("line {i}\r\n").as_bytes());
+    }
+    parser.process(INPUT);
+    parser.screen_mut().set_scrollback(10);
+    parser.screen_mut().set_scrollback_memory_limit(Some(10000));
+
+    let snapshot = parser.save_snapshot();
+    let mut restored = vt100::Parser::load_snapshot(&snapshot).unwrap();
+    assert!(helpers::compare_screens(restored.screen(), parser.screen()));
+    assert_eq!(restored.screen().scrollback(), 10);
+    assert_eq!(restored.screen().scrollback_memory_limit(), Some(10000));
+    assert_eq!(
+        restored.screen().scrollback_memory_usage(),
+        parser.screen().scrollback_memory_usage()
+    );
+    assert_eq!(restored.save_snapshot(), snapshot);
+
+    parser.process(b"\x1b8more\r\n\r\n\r\n\r\n");
+    restored.process(b"\x1b8more\r\n\r\n\r\n\r\n");
+    assert!(helpers::compare_screens(restored.screen(), parser.screen()));
+}
+
+#[test]
+fn partial_sequences() {
+    let mut expected =
+        vt100::Parser::new_with_callbacks(24, 80, 0, Titles::default());
+    expected.process(INPUT);
+
+    let title_end =
+        INPUT.windows(6).position(|w| w == b"title\x07").unwrap() + 6;
+    for i in 0..=INPUT.len() {
+        let mut parser = vt100::Parser::default();
+        parser.process(&INPUT[..i]);
+        let mut restored = vt100::Parser::load_snapshot_with_callbacks(
+            &parser.save_snapshot(),
+            Titles::default(),
+        )
+        .unwrap();
+        restored.process(&INPUT[i..]);
+        assert!(
+            helpers::compare_screens(restored.screen(), expected.screen()),
+            "split at {i}"
+        );
+        // the title should be set exactly once, unless it was already set
+        // before the snapshot
+        if i < title_end {
+            assert_eq!(restored.callbacks().titles, vec![b"title".to_vec()]);
+        } else {
+            assert!(restored.callbacks().titles.is_empty());
+        }
+    }
+
+    // the same, but with the input split into single bytes before the
+    // snapshot is taken
+    for i in 0..=INPUT.len() {
+        let mut parser = vt100::Parser::default();
+        for b in &INPUT[..i] {
+            parser.process(std::slice::from_ref(b));
+        }
+        let mut restored =
+            vt100::Parser::load_snapshot(&parser.save_snapshot()).unwrap();
+        restored.process(&INPUT[i..]);
+        assert!(
+            helpers::compare_screens(restored.screen(), expected.screen()),
+            "split at {i}"
+        );
+    }
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(
+        vt100::Parser::load_snapshot(b"not a snapshot").err(),
+        Some(vt100::SnapshotError::NotASnapshot)
+    );
+    assert_eq!(
+        vt100::Parser::load_snapshot(b"vt100snp\x7f").err(),
+        Some(vt100::SnapshotError::UnsupportedVersion(127))
+    );
+
+    let mut parser = vt100::Parser::new(5, 10, 10);
+    parser.process(b"\x1b[31mfoo\r\n\r\n\r\n\r\n\r\n\r\nbar\x1b[3");
+    let snapshot = parser.save_snapshot();
+    for len in 0..snapshot.len() {
+        assert!(vt100::Parser::load_snapshot(&snapshot[..len]).is_err());
+    }
+    let mut long = snapshot.clone();
+    long.push(0);
+    assert_eq!(
+        vt100::Parser::load_snapshot(&long).err(),
+        Some(vt100::SnapshotError::Corrupt)
+    );
+    // corrupted snapshots shouldn't cause panics, either when loading or
+    // when used afterwards
+    for i in 8..snapshot.len() {
+        for bit in 0..8 {
+            let mut corrupt = snapshot.clone();
+            corrupt[i] ^= 1 << bit;
+            if let Ok(mut parser) = vt100::Parser::load_snapshot(&corrupt) {
+                parser.process(b"\x1b[H\x1b[Mfoo\x1b[10;10H\r\n\x1b[2J");
+                let _ = parser.screen().contents_formatted();
+            }
+        }
+    }
+}
+
+#[test]
+fn invalid_wide_cells() {
+    let mut parser = vt100::Parser::new(2, 4, 0);
+    parser.process("ab\u{3042}\r\n".as_bytes());
+    let snapshot = parser.save_snapshot();
+    assert!(vt100::Parser::load_snapshot(&snapshot).is_ok());
+
+    // the raw length bytes of the cells in the first row
+    let lens = [1, 1, 0x83, 0x40];
+    let i = snapshot.windows(4).position(|w| w == lens).unwrap();
+    for (offset, len) in [
+        // a wide cell without a continuation
+        (3, 0x00),
+        // a continuation without a wide cell
+        (2, 0x03),
+        // a cell which is both
+        (2, 0xc3),
+    ] {
+        let mut corrupt = snapshot.clone();
+        corrupt[i + offset] = len;
+        assert_eq!(
+            vt100::Parser::load_snapshot(&corrupt).err(),
+            Some(vt100::SnapshotError::Corrupt)
+        );
+    }
+}
+
+#[test]
+fn long_sequences() {
+    let payload = vec![b'x'; 4 * 1024 * 1024];
+    for start in [&b"\x1bPq"[..], b"\x1b]2;", b"\x1b_"] {
+        let mut parser =
+            vt100::Parser::new_with_callbacks(24, 80, 0, Titles::default());
+        parser.process(start);
+        parser.process(&payload);
+        parser.process(&payload[..100]);
+        let snapshot = parser.save_snapshot();
+        if start[1] == b']' {
+            // the title is delivered in full when the sequence ends
+            assert!(snapshot.len() > payload.len(), "{}", snapshot.len());
+        } else {
+            // the payload will be discarded, so it isn't saved
+            assert!(
+                snapshot.len() < 1024 * 1024 + 1024,
+                "{}",
+                snapshot.len()
+            );
+        }
+
+        let mut restored = vt100::Parser::load_snapshot_with_callbacks(
+            &snapshot,
+            Titles::default(),
+        )
+        .unwrap();
+        let input = b"\x07\x1b\\\x1b]2;short\x07foo";
+        parser.process(input);
+        restored.process(input);
+        assert!(helpers::compare_screens(restored.screen(), parser.screen()));
+        assert_eq!(parser.screen().contents(), "foo");
+        assert_eq!(parser.callbacks().titles, restored.callbacks().titles);
+        assert_eq!(
+            parser.callbacks().titles.last().map(Vec::as_slice),
+            Some(&b"short"[..])
+        );
+    }
+}
@@ -0,0 +1,63 @@
+#[test]
+fn contents_svg() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(2, 6, 0);
+    parser.process(b"a <b>\r\n\x1b[1;31;42mx\x1b[m y");
+    assert_eq!(
+        parser.screen().contents_svg(&palette),
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"48\" height=\"32\" viewBox=\"0 0 48 32\" font-family=\"monospace\" font-size=\"14\">\n\
+<rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>\n\
+<text x=\"0\" y=\"12\" textLength=\"40\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#e5e5e5\" xml:space=\"preserve\">a &lt;b&gt;</text>\n\
+<rect x=\"0\" y=\"16\" width=\"8\" height=\"16\" fill=\"#00cd00\"/>\n\
+<text x=\"0\" y=\"28\" textLength=\"8\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#cd0000\" font-weight=\"bold\" xml:space=\"preserve\">x</text>\n\
+<text x=\"16\" y=\"28\" textLength=\"8\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#e5e5e5\" xml:space=\"preserve\">y</text>\n\
+<rect x=\"24\" y=\"16\" width=\"8\" height=\"16\" fill=\"#e5e5e5\" fill-opacity=\"0.5\"/>\n\
+</svg>\n"
+    );
+
+    parser.process(b"\x1b[?25l");
+    assert!(!parser
+        .screen()
+        .contents_svg(&palette)
+        .contains("fill-opacity"));
+}
+
+#[test]
+fn wide() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::new(1, 8, 0);
+    parser.process("\x1b[4;44m日本\x1b[mab\x1b[?25l".as_bytes());
+    assert_eq!(
+        parser.screen().contents_svg(&palette),
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"64\" height=\"16\" viewBox=\"0 0 64 16\" font-family=\"monospace\" font-size=\"14\">\n\
+<rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>\n\
+<rect x=\"0\" y=\"0\" width=\"32\" height=\"16\" fill=\"#0000ee\"/>\n\
+<text x=\"0\" y=\"12\" textLength=\"32\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#e5e5e5\" text-decoration=\"underline\" xml:space=\"preserve\">日本</text>\n\
+<text x=\"32\" y=\"12\" textLength=\"16\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#e5e5e5\" xml:space=\"preserve\">ab</text>\n\
+</svg>\n"
+    );
+}
+
+#[test]
+fn deterministic() {
+    let palette = vt100::Palette::default();
+    let mut parser = vt100::Parser::default();
+    parser.process(b"\x1b[31mfoo\x1b[m\r\n\x1b[7mbar");
+    let svg = parser.screen().contents_svg(&palette);
+    let screen = parser.screen().clone();
+    assert_eq!(screen.contents_svg(&palette), svg);
+
+    let mut new_parser = vt100::Parser::default();
+    new_parser.process(&parser.screen().state_formatted());
+    assert_eq!(new_parser.screen().contents_svg(&palette), svg);
+}
+
+#[test]
+fn empty() {
+    let palette = vt100::Palette::default();
+    let parser = vt100::Parser::new(2, 0, 0);
+    assert!(!parser
+        .screen()
+        .contents_svg(&palette)
+        .contains("fill-opacity"));
+}
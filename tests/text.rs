@@ -34,3 +34,17 @@ fn wrap() {
 fn wrap_weird() {
     helpers::fixture("wrap_weird");
 }
+
+#[test]
+fn ascii_over_wide() {
+    let mut parser = vt100::Parser::new(2, 6, 0);
+    parser.process("日本語\x1b[2Gab\x1b[6Gc".as_bytes());
+    assert_eq!(parser.screen().contents(), " ab  c");
+    assert!(!parser.screen().cell(0, 0).unwrap().is_wide());
+    assert!(!parser.screen().cell(0, 5).unwrap().is_wide_continuation());
+
+    parser.process(b"defg");
+    assert_eq!(parser.screen().contents(), " ab  cdefg");
+    assert!(parser.screen().row_wrapped(0));
+    assert_eq!(parser.screen().cursor_position(), (1, 4));
+}
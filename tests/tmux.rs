@@ -0,0 +1,256 @@
+fn read_events(
+    client: &mut vt100::TmuxClient<Vec<u8>>,
+) -> Vec<vt100::TmuxEvent> {
+    std::iter::from_fn(|| client.next_event())
+        .map(Result::unwrap)
+        .collect()
+}
+
+#[test]
+fn transcript() {
+    // recorded from tmux 3.3a with:
+    // tmux -C new-session -x 40 -y 10 sh
+    let transcript = std::fs::read("tests/data/tmux-control.txt").unwrap();
+    let mut client = vt100::TmuxClient::new(vec![], 10, 40, 0);
+
+    // feed the transcript in small chunks, to make sure that lines split
+    // across reads are handled
+    let mut events = vec![];
+    for chunk in transcript.chunks(7) {
+        client.process(chunk);
+        events.extend(read_events(&mut client));
+    }
+
+    let pane_0_layout = vt100::TmuxLayout {
+        rect: vt100::Rect::new(0, 0, 10, 20),
+        kind: vt100::TmuxLayoutKind::Pane(0),
+    };
+    let pane_1_layout = vt100::TmuxLayout {
+        rect: vt100::Rect::new(0, 21, 10, 19),
+        kind: vt100::TmuxLayoutKind::Pane(1),
+    };
+    let response = vt100::TmuxEvent::Response {
+        output: vec![],
+        error: false,
+    };
+    assert_eq!(
+        events,
+        [
+            response.clone(),
+            vt100::TmuxEvent::WindowAdd(0),
+            vt100::TmuxEvent::Other("%sessions-changed".to_string()),
+            vt100::TmuxEvent::SessionChanged {
+                session: 0,
+                name: "0".to_string(),
+            },
+            vt100::TmuxEvent::Output(0),
+            response.clone(),
+            vt100::TmuxEvent::LayoutChange {
+                window: 0,
+                layout: vt100::TmuxLayout {
+                    rect: vt100::Rect::new(0, 0, 10, 40),
+                    kind: vt100::TmuxLayoutKind::Pane(0),
+                },
+            },
+            response.clone(),
+            vt100::TmuxEvent::Output(0),
+            response.clone(),
+            vt100::TmuxEvent::WindowPaneChanged { window: 0, pane: 1 },
+            vt100::TmuxEvent::LayoutChange {
+                window: 0,
+                layout: vt100::TmuxLayout {
+                    rect: vt100::Rect::new(0, 0, 10, 40),
+                    kind: vt100::TmuxLayoutKind::LeftRight(vec![
+                        pane_0_layout.clone(),
+                        pane_1_layout.clone(),
+                    ]),
+                },
+            },
+            vt100::TmuxEvent::Output(1),
+            vt100::TmuxEvent::Output(1),
+            vt100::TmuxEvent::WindowRenamed {
+                window: 0,
+                name: "bash".to_string(),
+            },
+            response.clone(),
+            vt100::TmuxEvent::Output(1),
+            response.clone(),
+            vt100::TmuxEvent::WindowRenamed {
+                window: 0,
+                name: "foo".to_string(),
+            },
+            response.clone(),
+            vt100::TmuxEvent::SessionWindowChanged {
+                session: 0,
+                window: 1,
+            },
+            vt100::TmuxEvent::WindowAdd(1),
+            vt100::TmuxEvent::Output(2),
+            vt100::TmuxEvent::Output(2),
+            vt100::TmuxEvent::Response {
+                output: vec![
+                    "parse error: unknown command: bogus-command".to_string()
+                ],
+                error: true,
+            },
+            response,
+            vt100::TmuxEvent::Other("%sessions-changed".to_string()),
+            vt100::TmuxEvent::Exit(None),
+        ]
+    );
+
+    assert_eq!(client.panes().collect::<Vec<_>>(), [0, 1, 2]);
+    let pane = client.pane(0).unwrap().screen();
+    assert_eq!(pane.size(), (10, 20));
+    assert_eq!(
+        pane.rows(0, 20).take(3).collect::<Vec<_>>(),
+        // the pane was split after this was drawn, which truncated it
+        ["# printf \"\\\\033[31mh", "hello", "# "]
+    );
+    assert_eq!(pane.cell(1, 0).unwrap().fgcolor(), vt100::Color::Idx(1));
+    let pane = client.pane(1).unwrap().screen();
+    assert_eq!(pane.size(), (10, 19));
+    assert_eq!(pane.contents(), "root@vm:~# echo world\nworld\nroot@vm:~# ");
+    assert!(pane.bracketed_paste());
+    // pane 2's window has no layout yet, so it uses the default size
+    assert_eq!(client.pane(2).unwrap().screen().size(), (10, 40));
+    assert_eq!(
+        client.layout(0).unwrap().panes(),
+        [(0, pane_0_layout.rect), (1, pane_1_layout.rect)]
+    );
+    assert!(client.layout(1).is_none());
+}
+
+#[test]
+fn layouts() {
+    let mut client = vt100::TmuxClient::new(vec![], 24, 80, 0);
+    client.process(
+        b"\x1bP1000p%layout-change @1 \
+        c5b1,80x24,0,0[80x12,0,0,3,80x11,0,13{40x11,0,13,4,39x11,41,13,5}] \
+        c5b1,80x24,0,0[80x12,0,0,3,80x11,0,13{40x11,0,13,4,39x11,41,13,5}] \
+        *\r\n",
+    );
+    let events = read_events(&mut client);
+    let [vt100::TmuxEvent::LayoutChange { window: 1, layout }] = &events[..]
+    else {
+        panic!("unexpected events {events:?}");
+    };
+    assert_eq!(
+        layout.panes(),
+        [
+            (3, vt100::Rect::new(0, 0, 12, 80)),
+            (4, vt100::Rect::new(13, 0, 11, 40)),
+            (5, vt100::Rect::new(13, 41, 11, 39)),
+        ]
+    );
+    assert!(matches!(
+        &layout.kind,
+        vt100::TmuxLayoutKind::TopBottom(children)
+            if matches!(children[1].kind, vt100::TmuxLayoutKind::LeftRight(_))
+    ));
+    assert_eq!(client.panes().collect::<Vec<_>>(), [3, 4, 5]);
+    assert_eq!(client.pane(5).unwrap().screen().size(), (11, 39));
+
+    // panes which are no longer in a window's layout are removed
+    client
+        .process(b"%layout-change @1 b25f,80x24,0,0,4 b25f,80x24,0,0,4 *\n");
+    read_events(&mut client);
+    assert_eq!(client.panes().collect::<Vec<_>>(), [4]);
+    assert_eq!(client.pane(4).unwrap().screen().size(), (24, 80));
+
+    client.process(b"%output %4 a\\134b\\015\\012c\n");
+    client.process(b"%extended-output %4 10 : \\033[1md\n");
+    client.process(b"%window-close @1\n%exit detached\n\x1b\\");
+    let events = read_events(&mut client);
+    assert_eq!(
+        events,
+        [
+            vt100::TmuxEvent::Output(4),
+            vt100::TmuxEvent::Output(4),
+            vt100::TmuxEvent::WindowClose(1),
+            vt100::TmuxEvent::Exit(Some("detached".to_string())),
+        ]
+    );
+    assert!(client.pane(4).is_none());
+
+    // nothing after %exit comes from tmux, even if it looks like it does
+    client.process(b"%output %4 x\n%window-add @3\n");
+    assert!(client.next_event().is_none());
+    assert!(client.pane(4).is_none());
+}
+
+#[test]
+fn invalid() {
+    let mut client = vt100::TmuxClient::new(vec![], 24, 80, 0);
+    client.process(b"%layout-change @2 xyz,80x24 b\n%output 4 x\n");
+    let err = client.next_event().unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "invalid window layout: \"xyz,80x24\"");
+    assert!(matches!(
+        client.next_event(),
+        Some(Err(vt100::TmuxError::InvalidLine(_)))
+    ));
+    assert!(client.next_event().is_none());
+
+    // octal escapes which don't fit in a byte
+    client.process(b"%output %4 a\\400b\n%output %4 \\101\n");
+    assert!(matches!(
+        client.next_event(),
+        Some(Err(vt100::TmuxError::InvalidLine(_)))
+    ));
+    assert_eq!(
+        client.next_event().unwrap().unwrap(),
+        vt100::TmuxEvent::Output(4)
+    );
+    assert_eq!(client.pane(4).unwrap().screen().contents(), "A");
+}
+
+#[test]
+fn commands() {
+    let mut client = vt100::TmuxClient::new(vec![], 24, 80, 0);
+    client.send_command("list-windows").unwrap();
+    client.send_input(3, b"ls\r").unwrap();
+    client.send_input(3, b"").unwrap();
+    client.set_size(30, 100).unwrap();
+    assert_eq!(
+        String::from_utf8(client.writer().clone()).unwrap(),
+        "list-windows\n\
+        send-keys -H -t %3 6c 73 0d\n\
+        refresh-client -C 100x30\n"
+    );
+
+    client.process(b"%begin 1 2 1\n0: sh* (1 panes)\n%end 1 2 1\n");
+    client.process(b"%output %7 x\n");
+    assert_eq!(
+        read_events(&mut client),
+        [
+            vt100::TmuxEvent::Response {
+                output: vec!["0: sh* (1 panes)".to_string()],
+                error: false,
+            },
+            vt100::TmuxEvent::Output(7),
+        ]
+    );
+    assert_eq!(client.pane(7).unwrap().screen().size(), (30, 100));
+
+    // only the line matching the %begin line ends the response
+    client.process(
+        b"%begin 1 3 1\n%end 1 2 1\n%error 1 3 0\n%end 1 3 1\n\
+        %begin 1 4 1\nno such window\n%error 1 4 1\n",
+    );
+    assert_eq!(
+        read_events(&mut client),
+        [
+            vt100::TmuxEvent::Response {
+                output: vec![
+                    "%end 1 2 1".to_string(),
+                    "%error 1 3 0".to_string()
+                ],
+                error: false,
+            },
+            vt100::TmuxEvent::Response {
+                output: vec!["no such window".to_string()],
+                error: true,
+            },
+        ]
+    );
+}
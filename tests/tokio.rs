@@ -0,0 +1,104 @@
+#![cfg(feature = "tokio")]
+
+use tokio::io::AsyncWriteExt as _;
+
+#[tokio::test]
+async fn run() {
+    let mut parser = vt100::AsyncParser::new(vt100::Parser::new(24, 80, 0));
+    let mut viewers: Vec<_> = (0..2)
+        .map(|_| {
+            let rx = parser.subscribe();
+            let viewer = vt100::Parser::new(24, 80, 0);
+            (rx, viewer, parser.parser().screen().clone())
+        })
+        .collect();
+
+    let (mut w, r) = tokio::io::duplex(64);
+    let writer = async move {
+        w.write_all(b"foo\r\n\x1b[31mbar").await.unwrap();
+        w.write_all(b"\x1b[H\x1b[2Kbaz").await.unwrap();
+    };
+    let (res, ()) = tokio::join!(parser.run(r), writer);
+    res.unwrap();
+    assert_eq!(parser.parser().screen().contents(), "baz\nbar");
+
+    // each viewer renders only what changed since its last render
+    for (rx, viewer, prev) in &mut viewers {
+        assert!(rx.has_changed().unwrap());
+        let screen = rx.borrow_and_update().clone();
+        viewer.process(&screen.contents_diff(prev));
+        *prev = screen;
+        assert_eq!(viewer.screen().contents(), "baz\nbar");
+        assert_eq!(
+            viewer.screen().cell(1, 0).unwrap().fgcolor(),
+            vt100::Color::Idx(1)
+        );
+    }
+
+    parser.process(b"\x1b[3Hquux");
+    let (rx, viewer, prev) = &mut viewers[0];
+    assert!(rx.has_changed().unwrap());
+    let screen = rx.borrow_and_update().clone();
+    viewer.process(&screen.contents_diff(prev));
+    assert_eq!(viewer.screen().contents(), "baz\nbar\nquux");
+    assert!(!rx.has_changed().unwrap());
+}
+
+#[tokio::test]
+async fn write() {
+    let mut parser = vt100::AsyncParser::new(vt100::Parser::new(24, 80, 0));
+    let mut rx = parser.subscribe();
+    let mut input: &[u8] = b"foo\r\nbar";
+    tokio::io::copy(&mut input, &mut parser).await.unwrap();
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(rx.borrow_and_update().contents(), "foo\nbar");
+    assert_eq!(parser.into_parser().screen().contents(), "foo\nbar");
+}
+
+#[test]
+fn unchanged() {
+    let mut parser = vt100::AsyncParser::new(vt100::Parser::new(24, 80, 0));
+    let mut rx = parser.subscribe();
+    parser.process(b"foo");
+    assert!(rx.has_changed().unwrap());
+    rx.borrow_and_update();
+
+    // output which doesn't change the screen isn't published
+    parser.process(b"");
+    parser.process(b"\x1b]2;title\x07");
+    parser.process(b"\x1b[1;4H");
+    assert!(!rx.has_changed().unwrap());
+
+    // changes which aren't to the contents of the screen are
+    parser.process(b"\x1b[H");
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(rx.borrow_and_update().cursor_position(), (0, 0));
+    parser.process(b"\x1b[?1h");
+    assert!(rx.has_changed().unwrap());
+    assert!(rx.borrow_and_update().application_cursor());
+}
+
+#[test]
+fn damage() {
+    // publishing screens leaves the damage of the parser's screen alone,
+    // so it can still be used by the owner of the parser
+    let mut parser = vt100::AsyncParser::new(vt100::Parser::new(24, 80, 0));
+    let mut rx = parser.subscribe();
+    parser.parser_mut().screen_mut().clear_damage();
+    parser.process(b"foo");
+    assert!(rx.has_changed().unwrap());
+    rx.borrow_and_update();
+    parser.process(b"\x1b[3Hbar");
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(rx.borrow_and_update().contents(), "foo\n\nbar");
+    assert_eq!(parser.parser().screen().damage().dirty_rows(), &[0, 2]);
+
+    // rows are compared against the published screen rather than relying
+    // on the damage, so clearing it doesn't hide later changes
+    parser.parser_mut().screen_mut().clear_damage();
+    parser.process(b"\x1b[2Hbaz");
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(rx.borrow_and_update().contents(), "foo\nbaz\nbar");
+    parser.process(b"\x1b[2Hbaz");
+    assert!(!rx.has_changed().unwrap());
+}
@@ -0,0 +1,139 @@
+fn ms(ms: u64) -> std::time::Duration {
+    std::time::Duration::from_millis(ms)
+}
+
+#[test]
+fn classic() {
+    let typescript = b"Script started on Sat 04 Jun 2016 01:31:36 AM EDT\n\
+        foo\r\nbar\x1b[31mbaz\r\nScript done on Sat 04 Jun 2016\n";
+    let timing = b"0.5 5\n0.25 3\n\n1.0 5\n0.000000 5\n";
+    let mut player =
+        vt100::TypescriptPlayer::new(&typescript[..], &timing[..], 24, 80)
+            .unwrap();
+    assert_eq!(player.duration(), ms(1750));
+    assert_eq!(player.screen().size(), (24, 80));
+
+    assert_eq!(player.step(), Some(ms(500)));
+    assert_eq!(player.screen().contents(), "foo");
+    assert_eq!(player.step(), Some(ms(750)));
+    assert_eq!(player.screen().contents(), "foo\nbar");
+    assert_eq!(player.step(), Some(ms(1750)));
+    assert_eq!(player.screen().contents(), "foo\nbar");
+    assert_eq!(player.step(), Some(ms(1750)));
+    assert_eq!(player.screen().contents(), "foo\nbarbaz");
+    assert_eq!(
+        player.screen().cell(1, 3).unwrap().fgcolor(),
+        vt100::Color::Idx(1)
+    );
+    assert_eq!(player.position(), ms(1750));
+    assert_eq!(player.step(), None);
+
+    player.seek(ms(600));
+    assert_eq!(player.screen().contents(), "foo");
+    assert_eq!(player.step(), Some(ms(750)));
+    assert_eq!(player.screen().contents(), "foo\nbar");
+}
+
+#[test]
+fn advanced() {
+    let typescript = b"Script started on 2024-01-01 00:00:00+00:00\n\
+        $ ls\r\nfoo  bar\r\n$ exit\r\n";
+    let timing = b"H 0.000000 START_TIME 2024-01-01 00:00:00+00:00\n\
+        H 0.000000 COLUMNS 40\n\
+        H 0.000000 LINES 10\n\
+        H 0.000000 TIMING_LOG timing\n\
+        H 0.000000 OUTPUT_LOG typescript\n\
+        O 0.100000 2\n\
+        I 0.500000 3\n\
+        O 0.000100 4\n\
+        O 0.100000 10\n\
+        S 0.500000 SIGWINCH ROWS=20 COLS=30\n\
+        I 0.500000 5\n\
+        O 0.000100 8\n\
+        S 0.000000 SIGTERM\n";
+    let mut player =
+        vt100::TypescriptPlayer::new(&typescript[..], &timing[..], 24, 80)
+            .unwrap();
+    assert_eq!(player.screen().size(), (10, 40));
+    player.seek(ms(650));
+    assert_eq!(player.screen().contents(), "$ ls");
+    player.seek(ms(800));
+    assert_eq!(player.screen().contents(), "$ ls\nfoo  bar");
+    assert_eq!(player.screen().size(), (10, 40));
+    player.seek(ms(1300));
+    assert_eq!(player.screen().size(), (20, 30));
+    player.seek(ms(2000));
+    assert_eq!(player.screen().contents(), "$ ls\nfoo  bar\n$ exit");
+
+    // with --log-io, input is logged to the same file as output
+    let typescript = b"$ ls\rls\r\nfoo\r\n";
+    let timing = b"H 0.000000 OUTPUT_LOG typescript\n\
+        H 0.000000 INPUT_LOG typescript\n\
+        O 0.100000 2\n\
+        I 0.500000 3\n\
+        O 0.000100 4\n\
+        O 0.100000 5\n";
+    let mut player =
+        vt100::TypescriptPlayer::new(&typescript[..], &timing[..], 24, 80)
+            .unwrap();
+    player.seek(player.duration());
+    assert_eq!(player.screen().contents(), "$ ls\nfoo");
+}
+
+#[test]
+fn weechat() {
+    let typescript = std::fs::read("tests/data/weechat.typescript").unwrap();
+    let header_len = typescript.iter().position(|&b| b == b'\n').unwrap() + 1;
+    let data = &typescript[header_len..];
+
+    // split the data into uneven chunks, so that escape sequences and
+    // characters are split across events
+    let mut timing = String::new();
+    let mut offsets = vec![];
+    let mut offset = 0;
+    while offset < data.len() {
+        let len = (offsets.len() * 7919 % 300 + 1).min(data.len() - offset);
+        timing.push_str(&format!("0.01 {len}\n"));
+        offset += len;
+        offsets.push(offset);
+    }
+
+    let mut player = vt100::TypescriptPlayer::new(
+        &typescript[..],
+        timing.as_bytes(),
+        37,
+        193,
+    )
+    .unwrap();
+    let last = offsets.len() - 1;
+    for i in [last, 5, last / 2, last / 2 - 1, 0, last / 3, last - 5, last] {
+        player.seek(ms(u64::try_from(i + 1).unwrap() * 10));
+        let mut parser = vt100::Parser::new(37, 193, 0);
+        parser.process(&data[..offsets[i]]);
+        assert_eq!(
+            player.screen().contents_formatted(),
+            parser.screen().contents_formatted(),
+            "{i}"
+        );
+    }
+}
+
+#[test]
+fn invalid() {
+    let invalid = |timing: &[u8]| match vt100::TypescriptPlayer::new(
+        &b"foobar"[..],
+        timing,
+        24,
+        80,
+    ) {
+        Err(vt100::TypescriptError::InvalidTiming(line)) => Some(line),
+        _ => None,
+    };
+    assert_eq!(invalid(b"0.1 3\n0.1 4\n"), Some(2));
+    assert_eq!(invalid(b"0.1 3\n-0.1 1\n"), Some(2));
+    assert_eq!(invalid(b"0.1\n"), Some(1));
+    assert_eq!(invalid(b"foo 0.1 3\n"), Some(1));
+    assert_eq!(invalid(b"O 0.1 3\nS 0.1 SIGWINCH ROWS=0\n"), Some(2));
+    assert_eq!(invalid(b"H 0.0 LINES foo\n"), Some(1));
+    assert_eq!(invalid(b"0.1 3\n0.1 3\n"), None);
+}
@@ -577,3 +577,67 @@ fn diff_crawl(i: usize) {
         }
     }
 }
+
+#[test]
+fn diff_scroll() {
+    let mut parser = vt100::Parser::new(24, 80, 0);
+    for i in 1..=24 {
+        parser.process(format!("\r\nline {i}").as_bytes());
+    }
+
+    let screen = parser.screen().clone();
+    parser.process(b"\r\nline 25");
+    assert_eq!(
+        parser.screen().contents_diff(&screen),
+        b"\x1b[S\x1b[24;1Hline 25"
+    );
+    helpers::assert_contents_diff_reproduces_state_from_screens(
+        &screen,
+        parser.screen(),
+    );
+
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[3Hfoo\x1b[10;20r\x1b[10H\x1b[3L\x1b[r\x1b[24;8H");
+    assert_eq!(
+        parser.screen().contents_diff(&screen),
+        b"\x1b[10;20r\x1b[3T\x1b[r\x1b[3;1Hfoo\x1b[24;8H"
+    );
+    helpers::assert_contents_diff_reproduces_state_from_screens(
+        &screen,
+        parser.screen(),
+    );
+
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[5H\x1b[2M\x1b[24;8H");
+    assert_eq!(
+        parser.screen().contents_diff(&screen),
+        b"\x1b[5;1H\x1b[2M\x1b[24;8H"
+    );
+    helpers::assert_contents_diff_reproduces_state_from_screens(
+        &screen,
+        parser.screen(),
+    );
+
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[H\x1bM\x1b[31mfoo");
+    assert_eq!(
+        parser.screen().contents_diff(&screen),
+        b"\x1b[T\x1b[H\x1b[31mfoo"
+    );
+    helpers::assert_contents_diff_reproduces_state_from_screens(
+        &screen,
+        parser.screen(),
+    );
+
+    // small changes are still drawn directly
+    let screen = parser.screen().clone();
+    parser.process(b"\x1b[m\x1b[5Hline 4\x1b[6Hline 5");
+    assert_eq!(
+        parser.screen().contents_diff(&screen),
+        b"\x1b[5;6H\x1b[m4\x1b[6;6H5"
+    );
+    helpers::assert_contents_diff_reproduces_state_from_screens(
+        &screen,
+        parser.screen(),
+    );
+}